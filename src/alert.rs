@@ -0,0 +1,594 @@
+use crate::config::Config;
+use crate::provider::Provider;
+use crate::{ids, Game, Team};
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+// A rule of the config, e.g. [[alerts]] with name = "Close finish" and
+// when = "margin <= 3 && period >= 4 && team in favorites". It is notified once
+// every time its condition becomes true for a game.
+//
+// Conditions combine comparisons with &&, || and !, in parentheses if needed.
+// Numbers: margin, period, home_score, visitor_score, total, scored (points since
+// the previous fetch) and seconds_left (only known for leagues with a game clock).
+// True or false: live, final, tied, started, ended, period_ended and
+// lead_changed, the last four only at the fetch where it happened.
+// Teams: team (either of the two), home and visitor compare with == and != to
+// abbreviations like BOS or "BOS", and with `in` to favorites or a list like
+// [BOS, NYK]. league is the league id, e.g. league == "nhl".
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Rule {
+    pub name: String,
+    pub when: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Bool,
+    Number,
+    Text,
+    Teams,
+    List,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Variable {
+    Margin,
+    Period,
+    HomeScore,
+    VisitorScore,
+    Total,
+    Scored,
+    SecondsLeft,
+    Live,
+    Final,
+    Tied,
+    Started,
+    Ended,
+    PeriodEnded,
+    LeadChanged,
+    Team,
+    Home,
+    Visitor,
+    Favorites,
+    League,
+}
+
+const VARIABLES: [(&str, Variable, Kind); 19] = [
+    ("margin", Variable::Margin, Kind::Number),
+    ("period", Variable::Period, Kind::Number),
+    ("home_score", Variable::HomeScore, Kind::Number),
+    ("visitor_score", Variable::VisitorScore, Kind::Number),
+    ("total", Variable::Total, Kind::Number),
+    ("scored", Variable::Scored, Kind::Number),
+    ("seconds_left", Variable::SecondsLeft, Kind::Number),
+    ("live", Variable::Live, Kind::Bool),
+    ("final", Variable::Final, Kind::Bool),
+    ("tied", Variable::Tied, Kind::Bool),
+    ("started", Variable::Started, Kind::Bool),
+    ("ended", Variable::Ended, Kind::Bool),
+    ("period_ended", Variable::PeriodEnded, Kind::Bool),
+    ("lead_changed", Variable::LeadChanged, Kind::Bool),
+    ("team", Variable::Team, Kind::Teams),
+    ("home", Variable::Home, Kind::Teams),
+    ("visitor", Variable::Visitor, Kind::Teams),
+    ("favorites", Variable::Favorites, Kind::List),
+    ("league", Variable::League, Kind::Text),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    Variable(Variable),
+    Number(i64),
+    Text(String),
+    List(Vec<String>),
+}
+
+impl Operand {
+    fn kind(&self) -> Kind {
+        match self {
+            Operand::Variable(variable) => VARIABLES.iter().find(|(_, other, _)| other == variable).map_or(Kind::Bool, |(_, _, kind)| *kind),
+            Operand::Number(_) => Kind::Number,
+            Operand::Text(_) => Kind::Text,
+            Operand::List(_) => Kind::List,
+        }
+    }
+
+    // As written in the condition, for errors
+    fn label(&self) -> String {
+        match self {
+            Operand::Variable(variable) => VARIABLES.iter().find(|(_, other, _)| other == variable).map_or(String::new(), |(name, _, _)| name.to_string()),
+            Operand::Number(number) => number.to_string(),
+            Operand::Text(text) => format!("\"{}\"", text),
+            Operand::List(items) => format!("[{}]", items.join(", ")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare(Operand, &'static str, Operand),
+    In(Operand, Operand),
+    Is(Variable),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(i64),
+    Text(String),
+    Word(String),
+    Symbol(&'static str),
+}
+
+const SYMBOLS: [&str; 14] = ["&&", "||", "==", "!=", "<=", ">=", "<", ">", "!", "(", ")", "[", "]", ","];
+
+fn tokenize(text: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut rest = text.trim_start();
+    while let Some(c) = rest.chars().next() {
+        let length = if let Some(symbol) = SYMBOLS.iter().find(|symbol| rest.starts_with(**symbol)) {
+            tokens.push(Token::Symbol(symbol));
+            symbol.len()
+        } else if c == '"' || c == '\'' {
+            let end = rest[1..].find(c).ok_or_else(|| anyhow!("Unclosed quote in {}", rest))?;
+            tokens.push(Token::Text(rest[1..end + 1].to_string()));
+            end + 2
+        } else if c.is_ascii_digit() {
+            let end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+            tokens.push(Token::Number(rest[..end].parse().with_context(|| format!("Invalid number {}", &rest[..end]))?));
+            end
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let end = rest.find(|c: char| !c.is_ascii_alphanumeric() && c != '_').unwrap_or(rest.len());
+            tokens.push(Token::Word(rest[..end].to_string()));
+            end
+        } else {
+            bail!("Unexpected {} in the condition", c);
+        };
+        rest = rest[length..].trim_start();
+    }
+    Ok(tokens)
+}
+
+// Recursive descent over the tokens, || binds weaker than && which binds weaker than !
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, symbol: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Symbol(other)) if *other == symbol);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<()> {
+        match self.eat(symbol) {
+            true => Ok(()),
+            false => bail!("Expected {} in the condition", symbol),
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr> {
+        let mut expr = self.and()?;
+        while self.eat("||") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr> {
+        let mut expr = self.not()?;
+        while self.eat("&&") {
+            expr = Expr::And(Box::new(expr), Box::new(self.not()?));
+        }
+        Ok(expr)
+    }
+
+    fn not(&mut self) -> Result<Expr> {
+        if self.eat("!") {
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        if self.eat("(") {
+            let expr = self.or()?;
+            self.expect(")")?;
+            return Ok(expr);
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr> {
+        let left = self.operand()?;
+        if matches!(self.peek(), Some(Token::Word(word)) if word == "in") {
+            self.pos += 1;
+            let right = self.operand()?;
+            if !matches!(left.kind(), Kind::Teams | Kind::Text) || right.kind() != Kind::List {
+                bail!("`in` needs a team on the left and a list or favorites on the right");
+            }
+            return Ok(Expr::In(left, right));
+        }
+        let operator = match self.peek() {
+            Some(Token::Symbol(symbol)) if ["==", "!=", "<=", ">=", "<", ">"].contains(symbol) => *symbol,
+            _ => {
+                return match left {
+                    Operand::Variable(variable) if left.kind() == Kind::Bool => Ok(Expr::Is(variable)),
+                    _ => bail!("{} is not true or false on its own", left.label()),
+                }
+            }
+        };
+        self.pos += 1;
+        let right = self.operand()?;
+        let texts = |kind| matches!(kind, Kind::Teams | Kind::Text);
+        let valid = match (left.kind(), right.kind()) {
+            (Kind::Number, Kind::Number) => true,
+            (left, right) if texts(left) && texts(right) => matches!(operator, "==" | "!="),
+            _ => false,
+        };
+        if !valid {
+            bail!("Can not compare {} {} {}", left.label(), operator, right.label());
+        }
+        Ok(Expr::Compare(left, operator, right))
+    }
+
+    fn operand(&mut self) -> Result<Operand> {
+        match self.next() {
+            Some(Token::Number(number)) => Ok(Operand::Number(number)),
+            Some(Token::Text(text)) => Ok(Operand::Text(text)),
+            Some(Token::Word(word)) => word_operand(&word),
+            Some(Token::Symbol("[")) => {
+                let mut items = Vec::new();
+                while !self.eat("]") {
+                    match self.next() {
+                        Some(Token::Text(text)) | Some(Token::Word(text)) => items.push(text),
+                        _ => bail!("Lists hold team abbreviations, e.g. [BOS, NYK]"),
+                    }
+                    if !self.eat(",") {
+                        self.expect("]")?;
+                        break;
+                    }
+                }
+                Ok(Operand::List(items))
+            }
+            Some(token) => bail!("Unexpected {:?} in the condition", token),
+            None => bail!("The condition ends too early"),
+        }
+    }
+}
+
+// A variable, or an abbreviation when written in capitals
+fn word_operand(word: &str) -> Result<Operand> {
+    if let Some((_, variable, _)) = VARIABLES.iter().find(|(name, _, _)| *name == word) {
+        return Ok(Operand::Variable(*variable));
+    }
+    if word.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()) {
+        return Ok(Operand::Text(word.to_string()));
+    }
+    let names: Vec<&str> = VARIABLES.iter().map(|(name, _, _)| *name).collect();
+    bail!("Unknown alert variable {}, use one of {}", word, names.join(", "))
+}
+
+fn parse(text: &str) -> Result<Expr> {
+    let mut parser = Parser { tokens: tokenize(text)?, pos: 0 };
+    let expr = parser.or()?;
+    if let Some(token) = parser.peek() {
+        bail!("Unexpected {:?} in the condition", token);
+    }
+    Ok(expr)
+}
+
+// A game as the rules see it, with its state at the previous fetch
+struct State<'a> {
+    provider: &'a dyn Provider,
+    favorite: Option<&'a str>,
+    old: &'a Game,
+    game: &'a Game,
+}
+
+enum Value<'a> {
+    Bool(bool),
+    // unknown numbers make every comparison false
+    Number(Option<i64>),
+    Text(String),
+    Teams(Vec<&'a Team>),
+    List(Vec<String>),
+}
+
+impl<'a> State<'a> {
+    fn value(&self, operand: &Operand) -> Value<'a> {
+        let variable = match operand {
+            Operand::Variable(variable) => *variable,
+            Operand::Number(number) => return Value::Number(Some(*number)),
+            Operand::Text(text) => return Value::Text(text.clone()),
+            Operand::List(items) => return Value::List(items.clone()),
+        };
+        let (old, game) = (self.old, self.game);
+        let total = |game: &Game| (game.home_team_score + game.visitor_team_score) as i64;
+        let lead = |game: &Game| game.home_team_score.cmp(&game.visitor_team_score);
+        let number = |number: i64| Value::Number(Some(number));
+        match variable {
+            Variable::Margin => number(game.home_team_score.abs_diff(game.visitor_team_score) as i64),
+            Variable::Period => number(game.period as i64),
+            Variable::HomeScore => number(game.home_team_score as i64),
+            Variable::VisitorScore => number(game.visitor_team_score as i64),
+            Variable::Total => number(total(game)),
+            Variable::Scored => number(total(game) - total(old)),
+            Variable::SecondsLeft => Value::Number(self.provider.seconds_left(game).map(i64::from)),
+            Variable::Live => Value::Bool(game.is_live()),
+            Variable::Final => Value::Bool(game.is_final()),
+            Variable::Tied => Value::Bool(lead(game) == Ordering::Equal),
+            Variable::Started => Value::Bool(!old.is_live() && !old.is_final() && (game.is_live() || game.is_final())),
+            Variable::Ended => Value::Bool(!old.is_final() && game.is_final()),
+            Variable::PeriodEnded => Value::Bool(old.period > 0 && (game.period > old.period || !old.is_final() && game.is_final())),
+            Variable::LeadChanged => Value::Bool(lead(old) != Ordering::Equal && lead(game) == lead(old).reverse()),
+            Variable::Team => Value::Teams(vec![&game.home_team, &game.visitor_team]),
+            Variable::Home => Value::Teams(vec![&game.home_team]),
+            Variable::Visitor => Value::Teams(vec![&game.visitor_team]),
+            Variable::Favorites => Value::List(self.favorite.map(str::to_string).into_iter().collect()),
+            Variable::League => Value::Text(self.provider.id().to_string()),
+        }
+    }
+
+    // Abbreviations match a team at any provider of the league
+    fn matches(&self, value: &Value, abbreviation: &str) -> bool {
+        match value {
+            Value::Teams(teams) => teams.iter().any(|team| ids::is(self.provider.id(), abbreviation, team)),
+            Value::Text(text) => text.eq_ignore_ascii_case(abbreviation),
+            _ => false,
+        }
+    }
+
+    fn eval(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::Or(left, right) => self.eval(left) || self.eval(right),
+            Expr::And(left, right) => self.eval(left) && self.eval(right),
+            Expr::Not(expr) => !self.eval(expr),
+            Expr::Is(variable) => matches!(self.value(&Operand::Variable(*variable)), Value::Bool(true)),
+            Expr::In(left, right) => match self.value(right) {
+                Value::List(items) => {
+                    let left = self.value(left);
+                    items.iter().any(|item| self.matches(&left, item))
+                }
+                _ => false,
+            },
+            Expr::Compare(left, operator, right) => match (self.value(left), self.value(right)) {
+                (Value::Number(Some(left)), Value::Number(Some(right))) => match *operator {
+                    "==" => left == right,
+                    "!=" => left != right,
+                    "<=" => left <= right,
+                    ">=" => left >= right,
+                    "<" => left < right,
+                    _ => left > right,
+                },
+                (Value::Number(_), _) => false,
+                (left, Value::Text(text)) | (Value::Text(text), left) => self.matches(&left, &text) == (*operator == "=="),
+                _ => false,
+            },
+        }
+    }
+}
+
+// The rules of the config and the last state of every game they saw. Every fetch
+// is compared to it, a rule fires when it becomes true for a game. The first
+// fetch of a game only sets its state, so starting up does not fire what
+// already held.
+pub struct Alerts {
+    rules: Vec<(String, Expr)>,
+    // by league id and game id, the game and whether each rule held for it
+    seen: HashMap<(String, u32), (Game, Vec<bool>)>,
+}
+
+impl Alerts {
+    pub fn from_config(config: &Config) -> Result<Alerts> {
+        let rules = config
+            .alerts
+            .iter()
+            .map(|rule| {
+                let name = if rule.name.is_empty() { rule.when.clone() } else { rule.name.clone() };
+                let expr = parse(&rule.when).with_context(|| format!("Invalid alert {}", name))?;
+                Ok((name, expr))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Alerts { rules, seen: HashMap::new() })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    // Names of the rules that became true for the game since its previous fetch
+    pub fn check(&mut self, provider: &dyn Provider, favorite: Option<&str>, game: &Game) -> Vec<String> {
+        if self.rules.is_empty() {
+            return Vec::new();
+        }
+        let key = (provider.id().to_string(), game.id);
+        let (old, held) = match self.seen.get(&key) {
+            Some((old, held)) => (old, Some(held)),
+            None => (game, None),
+        };
+        let state = State { provider, favorite, old, game };
+        let holds: Vec<bool> = self.rules.iter().map(|(_, expr)| state.eval(expr)).collect();
+        let fired = match held {
+            Some(held) => self.rules.iter().zip(&holds).zip(held).filter(|((_, now), before)| **now && !**before).map(|(((name, _), _), _)| name.clone()).collect(),
+            None => Vec::new(),
+        };
+        self.seen.insert(key, (game.clone(), holds));
+        fired
+    }
+
+    // Forget the final games that are no longer shown or followed, by league id and
+    // game id. They can not change anymore, should one be fetched again its first
+    // sighting only sets its state.
+    pub fn prune(&mut self, shown: &HashSet<(String, u32)>) {
+        self.seen.retain(|key, (game, _)| !game.is_final() || shown.contains(key));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::api::Canned;
+    use crate::provider::nba::NbaProvider;
+    use std::sync::Arc;
+
+    fn nba() -> NbaProvider {
+        NbaProvider::new("", Arc::new(Canned::new()))
+    }
+
+    fn team(abbreviation: &str) -> Team {
+        Team { abbreviation: abbreviation.to_string(), ..Team::default() }
+    }
+
+    // BOS at home against LAL
+    fn game(period: u32, home: u32, visitor: u32, time: &str) -> Game {
+        let status = if time == "Final" { "Final" } else { "In Progress" };
+        Game {
+            id: 1,
+            home_team: team("BOS"),
+            visitor_team: team("LAL"),
+            period,
+            home_team_score: home,
+            visitor_team_score: visitor,
+            status: status.to_string(),
+            time: Some(time.to_string()),
+            ..Game::default()
+        }
+    }
+
+    fn holds(when: &str, favorite: Option<&str>, game: &Game) -> bool {
+        let expr = parse(when).unwrap();
+        State { provider: &nba(), favorite, old: game, game }.eval(&expr)
+    }
+
+    fn error(when: &str) -> String {
+        format!("{:#}", parse(when).unwrap_err())
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or_and_not_tightest() {
+        let is = |variable| Box::new(Expr::Is(variable));
+        let expected = Expr::Or(is(Variable::Live), Box::new(Expr::And(is(Variable::Final), Box::new(Expr::Not(is(Variable::Tied))))));
+        assert_eq!(parse("live || final && !tied").unwrap(), expected);
+
+        let tied_live = game(2, 50, 50, "Q2 3:00");
+        let tied_final = game(4, 100, 100, "Final");
+        assert!(holds("live || final && !tied", None, &tied_live));
+        assert!(!holds("live || final && !tied", None, &tied_final));
+        assert!(!holds("(live || final) && !tied", None, &tied_live));
+        assert!(holds("!(live && tied) || final", None, &tied_final));
+    }
+
+    #[test]
+    fn in_matches_favorites_and_lists() {
+        let game = game(2, 50, 48, "Q2 3:00");
+        assert!(holds("team in favorites", Some("LAL"), &game));
+        assert!(holds("home in favorites", Some("BOS"), &game));
+        assert!(!holds("home in favorites", Some("LAL"), &game));
+        assert!(!holds("team in favorites", None, &game));
+        assert!(holds("team in [NYK, \"LAL\"]", None, &game));
+        assert!(holds("visitor in [LAL]", None, &game));
+        assert!(!holds("home in [NYK, MIA]", None, &game));
+        assert!(!holds("team in []", None, &game));
+        assert!(holds("league in [nhl, nba]", None, &game));
+    }
+
+    #[test]
+    fn teams_compare_as_text_and_scores_as_numbers() {
+        let game = game(3, 70, 61, "Q3 5:00");
+        assert!(holds("home == BOS", None, &game));
+        assert!(holds("visitor != 'BOS'", None, &game));
+        assert!(holds("team == LAL", None, &game));
+        assert!(holds("league == \"nba\"", None, &game));
+        assert!(holds("margin == 9 && total > 130 && home_score >= 70", None, &game));
+        assert!(!holds("visitor_score < 61", None, &game));
+
+        assert_eq!(error("margin == BOS"), "Can not compare margin == \"BOS\"");
+        assert_eq!(error("home < BOS"), "Can not compare home < \"BOS\"");
+        assert_eq!(error("home == 3"), "Can not compare home == 3");
+    }
+
+    #[test]
+    fn unknown_seconds_left_is_false_for_every_comparison() {
+        // no clock before the fourth quarter
+        let early = game(2, 50, 48, "Q2 1:00");
+        for when in ["seconds_left < 60", "seconds_left >= 0", "seconds_left == 0", "seconds_left != 0"] {
+            assert!(!holds(when, None, &early), "{} held", when);
+        }
+        assert!(holds("!(seconds_left < 60)", None, &early));
+
+        let late = game(4, 100, 98, "Q4 1:30");
+        assert!(holds("seconds_left <= 90 && margin <= 3", None, &late));
+    }
+
+    #[test]
+    fn bad_conditions_say_what_is_wrong() {
+        assert!(error("points > 3").starts_with("Unknown alert variable points, use one of margin, period,"));
+        assert_eq!(error("margin"), "margin is not true or false on its own");
+        assert_eq!(error("margin >"), "The condition ends too early");
+        assert_eq!(error("(live || final"), "Expected ) in the condition");
+        assert_eq!(error("live final"), "Unexpected Word(\"final\") in the condition");
+        assert_eq!(error("home == \"BOS"), "Unclosed quote in \"BOS");
+        assert_eq!(error("margin <= 3 ; live"), "Unexpected ; in the condition");
+        assert_eq!(error("margin in [BOS]"), "`in` needs a team on the left and a list or favorites on the right");
+        assert_eq!(error("team in [3]"), "Lists hold team abbreviations, e.g. [BOS, NYK]");
+
+        let config = Config { alerts: vec![Rule { name: "Close".to_string(), when: "margin <".to_string() }], ..Config::default() };
+        let error = Alerts::from_config(&config).err().unwrap();
+        assert_eq!(format!("{:#}", error), "Invalid alert Close: The condition ends too early");
+    }
+
+    fn alerts(when: &str) -> Alerts {
+        let config = Config { alerts: vec![Rule { name: String::new(), when: when.to_string() }], ..Config::default() };
+        Alerts::from_config(&config).unwrap()
+    }
+
+    #[test]
+    fn fires_once_when_a_rule_becomes_true() {
+        let mut alerts = alerts("margin <= 3 && period >= 4");
+        let provider = nba();
+        // already true at the first fetch
+        assert!(alerts.check(&provider, None, &game(4, 100, 99, "Q4 2:00")).is_empty());
+        assert!(alerts.check(&provider, None, &game(4, 104, 99, "Q4 1:40")).is_empty());
+        assert_eq!(alerts.check(&provider, None, &game(4, 104, 102, "Q4 1:00")), ["margin <= 3 && period >= 4"]);
+        assert!(alerts.check(&provider, None, &game(4, 105, 102, "Q4 0:30")).is_empty());
+    }
+
+    #[test]
+    fn prune_forgets_final_games_no_longer_shown() {
+        let mut alerts = alerts("final");
+        let provider = nba();
+        let live = game(4, 100, 99, "Q4 2:00");
+        let ended = Game { id: 2, ..game(4, 100, 99, "Final") };
+        alerts.check(&provider, None, &live);
+        alerts.check(&provider, None, &ended);
+
+        let shown = HashSet::from([("nba".to_string(), 3)]);
+        alerts.prune(&shown);
+        // the live game is kept, so its end still fires
+        assert!(alerts.seen.contains_key(&("nba".to_string(), 1)));
+        assert!(!alerts.seen.contains_key(&("nba".to_string(), 2)));
+        assert_eq!(alerts.check(&provider, None, &game(4, 102, 99, "Final")), ["final"]);
+
+        alerts.prune(&HashSet::from([("nba".to_string(), 1)]));
+        assert!(alerts.seen.contains_key(&("nba".to_string(), 1)));
+        alerts.prune(&HashSet::new());
+        assert!(alerts.seen.is_empty());
+    }
+}
@@ -0,0 +1,56 @@
+use crate::config::Config;
+use crate::{ids, Game};
+use anyhow::{Context, Result};
+use std::process::{Command, Stdio};
+
+// Speech synthesizer of the platform, it gets the text as its only argument
+#[cfg(target_os = "macos")]
+const SPEAK_COMMAND: &str = "say";
+#[cfg(not(target_os = "macos"))]
+const SPEAK_COMMAND: &str = "espeak";
+
+// Reads score changes of the favorite team's games aloud
+pub struct Announcer {
+    command: String,
+}
+
+impl Announcer {
+    // None unless announcing was turned on, with the configured command or the platform's synthesizer
+    pub fn from_config(config: &Config) -> Option<Announcer> {
+        let command = config.announce_command.as_deref().unwrap_or(SPEAK_COMMAND);
+        config.announce.then(|| Announcer { command: command.to_string() })
+    }
+
+    // Speaking runs in the background, the child is reaped by its own thread
+    pub fn speak(&self, text: &str) -> Result<()> {
+        let mut child = Command::new(&self.command)
+            .arg(text)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .with_context(|| format!("Could not run {}", self.command))?;
+        std::thread::spawn(move || child.wait());
+        Ok(())
+    }
+}
+
+// What to say about the games of a team that changed between two fetches of the same day
+pub fn score_changes(old: &[Game], new: &[Game], league: &str, team: &str) -> Vec<String> {
+    new.iter()
+        .filter(|game| ids::plays(league, team, game))
+        .filter_map(|game| {
+            let before = old.iter().find(|old| old.id == game.id)?;
+            let scored = (before.home_team_score, before.visitor_team_score) != (game.home_team_score, game.visitor_team_score);
+            let ended = !before.is_final() && game.is_final();
+            if !scored && !ended {
+                return None;
+            }
+            let score = format!(
+                "{} {}, {} {}",
+                game.home_team.full_name, game.home_team_score, game.visitor_team.full_name, game.visitor_team_score
+            );
+            Some(if ended { format!("Final: {}", score) } else { score })
+        })
+        .collect()
+}
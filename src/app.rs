@@ -0,0 +1,973 @@
+use crate::alert::Alerts;
+use crate::announce::Announcer;
+use crate::archive::Archive;
+use crate::calendar::Calendar;
+use crate::config::Config;
+use crate::details::GameDetails;
+use crate::elo::Ratings;
+use crate::followed::Followed;
+use crate::form::Form;
+use crate::goto::GotoPrompt;
+use crate::grouping::{GameSort, Grouping};
+use crate::h2h::HeadToHead;
+use crate::hyperlinks::Hyperlinks;
+use crate::jobs::{JobState, Jobs};
+use crate::jumps::JumpList;
+use crate::keymap::{Action, KeyMap};
+use crate::matrix::Matrix;
+use crate::notify::Notifier;
+use crate::palette::Palette;
+use crate::players::PlayersState;
+use crate::provider::Provider;
+use crate::refresh::{Refreshed, Refresher, Target};
+use crate::setup::SetupState;
+use crate::spoilers::Spoilers;
+use crate::standings::{Sort, Standings};
+use crate::status::FetchState;
+use crate::store::Store;
+use crate::teams::TeamsState;
+use crate::theme::Theme;
+use crate::toast::Toasts;
+use crate::tournament::Tournament;
+use crate::week::Week;
+use crate::{archive, cache, config, players, poll, provider, standings, store, week, Game, GameData};
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Utc};
+use ratatui::{prelude::Rect, widgets::TableState};
+use std::sync::Arc;
+use std::time::{Instant, SystemTime};
+#[cfg(feature = "logos")]
+use crate::logos;
+
+// Tabs of the tab bar, each view keeps its own state and fetches its own data
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum View {
+    Games,
+    Standings,
+    Players,
+    Teams,
+    Jobs,
+    Week,
+}
+
+impl View {
+    pub const ALL: [View; 6] = [View::Games, View::Standings, View::Players, View::Teams, View::Jobs, View::Week];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            View::Games => "Games",
+            View::Standings => "Standings",
+            View::Players => "Players",
+            View::Teams => "Teams",
+            View::Jobs => "Jobs",
+            View::Week => "Week",
+        }
+    }
+
+    // Most useful keys of the view, for the status bar
+    pub fn hints(&self) -> &'static str {
+        match self {
+            View::Games => "enter details  j|k day  left|right sort  ? help",
+            View::Standings => "left|right sort  o reverse  G ladder  ? help",
+            View::Players => "type to search  enter open  esc back",
+            View::Teams => "enter schedule  esc back  ? help",
+            View::Jobs => "up|down select  del cancel  ? help",
+            View::Week => "h|l week  j|k day  ? help",
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        View::ALL.iter().position(|view| view == self).unwrap_or_default()
+    }
+
+    // Neighbouring tab, wrapping around at both ends
+    pub fn cycle(&self, delta: isize) -> View {
+        let count = View::ALL.len() as isize;
+        View::ALL[(self.index() as isize + delta).rem_euclid(count) as usize]
+    }
+}
+
+// Something asked for with a key that is fetched in the background, it is shown
+// when it comes in if the user is still where they asked for it
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Opening {
+    // box score of the game id
+    Details(u32),
+    // season schedule of the team id
+    Schedule(u32),
+    // earlier meetings of the teams of the game id
+    HeadToHead(u32),
+    // season numbers of the player id
+    Player(u32),
+    // tournament of the browsed day
+    Tournament,
+    // the games of the day, to jump to the closest live one
+    Live(DateTime<Utc>),
+}
+
+impl Opening {
+    pub fn target(&self) -> Target {
+        match self {
+            Opening::Details(_) => Target::Details,
+            Opening::Schedule(_) | Opening::HeadToHead(_) => Target::Schedule,
+            Opening::Player(_) => Target::Player,
+            Opening::Tournament => Target::Tournament,
+            Opening::Live(_) => Target::Day,
+        }
+    }
+}
+
+// App state
+pub struct App {
+    pub day: DateTime<Utc>,
+    pub should_quit: bool,
+    pub game_data: Option<GameData>,
+    // selected game and scroll offset of the scoreboard
+    pub games_state: TableState,
+    // screen area of each game row drawn in the last frame, for mouse clicks
+    pub game_rows: Vec<(Rect, usize)>,
+    // when and on which game the mouse was clicked last, a second click opens it
+    pub last_click: Option<(Instant, usize)>,
+    // count typed before a motion and when its last digit was typed
+    pub count: Option<(usize, Instant)>,
+    pub jumps: JumpList,
+    pub config: Config,
+    // modification time of the config file when it was last read
+    pub config_modified: Option<SystemTime>,
+    pub providers: Vec<Arc<dyn Provider>>,
+    // index into providers of the league being shown
+    pub league: usize,
+    // the season browsing is kept in, named after the year it starts in
+    pub season: Option<i32>,
+    pub keymap: KeyMap,
+    pub cache: Arc<dyn Store>,
+    // every fetched game, when turned on in the config
+    pub archive: Option<Archive>,
+    pub view: View,
+    // standings of the browsed season, fetched when the standings tab is shown
+    pub standings: Option<Standings>,
+    pub standings_sort: Sort,
+    // first row of the standings table
+    pub standings_scroll: usize,
+    // game to select once the games of its day are shown
+    pub pending_game: Option<u32>,
+    // what is fetched to be shown next, at most one thing at a time
+    pub opening: Option<Opening>,
+    // Elo ratings of the league's teams as of the browsed day
+    pub elo: Option<((usize, NaiveDate), Ratings)>,
+    // streaks and last ten games of the teams coming into the browsed day
+    pub form: Option<Form>,
+    pub players: PlayersState,
+    pub teams: TeamsState,
+    // games of the browsed week, fetched when the week tab is shown
+    pub week: Option<Week>,
+    // Some while the box score of the selected game is shown
+    pub details: Option<GameDetails>,
+    // Some while the group tables and bracket of a tournament are shown
+    pub tournament: Option<Tournament>,
+    // Some while the earlier meetings of the selected game's teams are shown
+    pub head_to_head: Option<HeadToHead>,
+    // Some while the head-to-head records of the whole league are shown over the standings
+    pub matrix: Option<Matrix>,
+    // Some while the month calendar is shown over the games
+    pub calendar: Option<Calendar>,
+    // Some while the go to date prompt is shown
+    pub goto: Option<GotoPrompt>,
+    // Some while the ':' command line is shown
+    pub palette: Option<Palette>,
+    // headers the scoreboard puts the games under, the games are kept in that order
+    pub grouping: Grouping,
+    pub game_sort: GameSort,
+    // true while the games of the browsed day are fetched, placeholder rows are shown meanwhile
+    pub loading: bool,
+    // when the shown games were fetched, if they come from the archive instead of the provider
+    pub stale: Option<DateTime<Utc>>,
+    // standings and bracket are drawn instead of listed, on wide terminals
+    pub graphics: bool,
+    // games as a grid of cards instead of the table
+    pub cards: bool,
+    // scores hidden for replays, with the games revealed one at a time
+    pub spoilers: Spoilers,
+    pub theme: Theme,
+    // true while the keybindings are shown over everything else
+    pub help: bool,
+    // Some while the first-run setup wizard is shown
+    pub setup: Option<SetupState>,
+    pub refresher: Refresher,
+    // Some when the terminal shows clickable links
+    pub hyperlinks: Option<Hyperlinks>,
+    // Some when team logos are drawn with the kitty graphics protocol
+    #[cfg(feature = "logos")]
+    pub logos: Option<logos::Logos>,
+    // Some when score changes are read aloud
+    pub announcer: Option<Announcer>,
+    pub notifier: Notifier,
+    pub alerts: Alerts,
+    // when the scoreboard was refreshed last, live games are refreshed on an interval
+    pub refreshed_day_at: Instant,
+    // time after the last refresh the live games are refreshed again
+    pub poll_interval: std::time::Duration,
+    pub poll_rng: poll::Rng,
+    pub poll_adaptive: poll::Adaptive,
+    // today's games of the favorite teams, live ones are shown above every view
+    pub followed: Followed,
+    // last fetch of any view, for the status bar
+    pub fetch: FetchState,
+    pub jobs: Jobs,
+    pub toasts: Toasts,
+}
+
+impl App {
+    // Everything but the providers from the config, tests pass providers that need no network
+    pub fn new(config: Config, providers: Vec<Arc<dyn Provider>>, setup: Option<SetupState>, season: Option<i32>) -> Result<App> {
+        let keymap = build_keymap(&providers)?;
+        let cache = store::open(&config)?;
+        let archive = Archive::open(&config)?;
+        let announcer = Announcer::from_config(&config);
+        let notifier = Notifier::from_config(&config)?;
+        let alerts = Alerts::from_config(&config)?;
+        let grouping = config.group_games;
+        let spoilers = Spoilers::new(config.spoiler_free);
+        let theme = Theme::from_config(&config)?;
+        let hyperlinks = Hyperlinks::detect(&config);
+        #[cfg(feature = "logos")]
+        let logos = logos::Logos::detect(&config);
+        let poll_interval = config.auto_refresh_interval().unwrap_or_default();
+        let poll_rng = config.poll_seed.map_or_else(poll::Rng::from_clock, poll::Rng::seeded);
+        Ok(App {
+            day: Utc::now(),
+            should_quit: false,
+            game_data: None,
+            games_state: TableState::default(),
+            game_rows: Vec::new(),
+            last_click: None,
+            count: None,
+            jumps: JumpList::default(),
+            config,
+            config_modified: config::config_modified(),
+            providers,
+            league: 0,
+            season,
+            keymap,
+            cache,
+            archive,
+            details: None,
+            view: View::Games,
+            standings: None,
+            standings_sort: Sort::default(),
+            standings_scroll: 0,
+            pending_game: None,
+            opening: None,
+            elo: None,
+            form: None,
+            players: PlayersState::default(),
+            teams: TeamsState::default(),
+            week: None,
+            tournament: None,
+            head_to_head: None,
+            matrix: None,
+            calendar: None,
+            goto: None,
+            palette: None,
+            spoilers,
+            theme,
+            help: false,
+            graphics: false,
+            cards: false,
+            grouping,
+            game_sort: GameSort::default(),
+            loading: false,
+            stale: None,
+            setup,
+            refresher: Refresher::default(),
+            followed: Followed::default(),
+            fetch: FetchState::default(),
+            refreshed_day_at: Instant::now(),
+            poll_interval,
+            poll_rng,
+            poll_adaptive: poll::Adaptive::default(),
+            announcer,
+            notifier,
+            alerts,
+            hyperlinks,
+            #[cfg(feature = "logos")]
+            logos,
+            jobs: Jobs::default(),
+            toasts: Toasts::default(),
+        })
+    }
+
+    pub fn provider(&self) -> &dyn Provider {
+        self.providers[self.league].as_ref()
+    }
+
+    // Serve finished days from the disk cache, everything else from the provider
+    pub fn fetch_day(&self, day: DateTime<Utc>) -> Option<GameData> {
+        let provider = self.provider();
+        let cache_key = provider.cache_key();
+        if let Some(game_data) = self.cache.get(&cache_key, day) {
+            return Some(game_data);
+        }
+        if let Some(game_data) = self.archived_day(&cache_key, day) {
+            return Some(game_data);
+        }
+
+        let game_data = provider.fetch_day(day)?;
+        // a failing cache write only costs a refetch next time
+        let _ = self.cache.put(&cache_key, day, &game_data);
+        self.archive_games(&cache_key, &game_data.data);
+        Some(game_data)
+    }
+
+    // Finished days in the archive need no fetch even when the cache has evicted them
+    pub fn archived_day(&self, cache_key: &str, day: DateTime<Utc>) -> Option<GameData> {
+        let games = self.archive.as_ref()?.day(cache_key, day.date_naive());
+        let game_data = GameData::from_games(games);
+        (!game_data.data.is_empty() && cache::is_settled(day, &game_data)).then_some(game_data)
+    }
+
+    // The archived games of a day that could not be fetched, marked with when they were.
+    // False when the day is not in the archive.
+    pub fn show_stale_day(&mut self, cache_key: &str, day: DateTime<Utc>) -> bool {
+        let Some(archive) = &self.archive else {
+            return false;
+        };
+        let (games, updated) = (archive.day(cache_key, day.date_naive()), archive.updated(cache_key, day.date_naive()));
+        let Some(updated) = updated.filter(|_| !games.is_empty()) else {
+            return false;
+        };
+        self.set_game_data(Some(GameData::from_games(games)));
+        self.stale = Some(updated);
+        true
+    }
+
+    // Standings as of the browsed day, ranked from the archived results. Only for
+    // past days of seasons synced after them, the archive may miss games otherwise.
+    pub fn archived_standings(&self) -> Option<Standings> {
+        let archive = self.archive.as_ref()?;
+        let provider = self.provider();
+        let date = self.day.date_naive();
+        let season = self.season_of_day()?;
+        let (start, _) = provider.season_range(season)?;
+        let cache_key = provider.cache_key();
+        if archive.synced_at(&cache_key, season)?.date_naive() <= date {
+            return None;
+        }
+        let games = archive.range(&cache_key, start, date);
+        games.iter().any(Game::is_final).then(|| standings::compute(provider.ranking_rules(), &games))
+    }
+
+    // The season the browsed day is in, for leagues with seasons
+    pub fn season_of_day(&self) -> Option<i32> {
+        let provider = self.provider();
+        let date = self.day.date_naive();
+        let in_season = |season: &i32| provider.season_range(*season).is_some_and(|(start, end)| (start..=end).contains(&date));
+        [date.year(), date.year() - 1].into_iter().find(in_season)
+    }
+
+    // Archived games of the season before the browsed day, for the Elo ratings and
+    // the form of the teams. The season has to be synced once, days fetched later
+    // keep them going.
+    pub fn games_before_day(&self) -> Option<Vec<Game>> {
+        let archive = self.archive.as_ref()?;
+        let season = self.season_of_day()?;
+        let (start, _) = self.provider().season_range(season)?;
+        let cache_key = self.provider().cache_key();
+        archive.synced_at(&cache_key, season)?;
+        let before = self.day.date_naive().pred_opt()?;
+        Some(archive.range(&cache_key, start, before))
+    }
+
+    // Standings show the Elo ratings when there are some
+    pub fn set_standings(&mut self, mut standings: Option<Standings>) {
+        if let (Some(standings), Some((_, ratings))) = (&mut standings, &self.elo) {
+            standings.rate(ratings);
+        }
+        self.standings = standings;
+    }
+
+    // Like the cache, a failing archive write is not worth interrupting the user for
+    pub fn archive_games(&self, cache_key: &str, games: &[Game]) {
+        if let Some(archive) = &self.archive {
+            let _ = archive.record(cache_key, games);
+        }
+    }
+
+    // Cached days are shown right away, others are fetched in the background
+    pub fn load_day(&mut self) {
+        let provider = self.providers[self.league].clone();
+        let (day, league, cache_key) = (self.day, self.league, provider.cache_key());
+        if let Some(game_data) = self.cache.get(&cache_key, day) {
+            self.loading = false;
+            self.fetch.record(true);
+            self.set_game_data(Some(game_data));
+            return;
+        }
+        if self.config.offline {
+            self.loading = false;
+            if !self.show_stale_day(&cache_key, day) {
+                self.toasts.warning(format!("Offline, the games of {} are not in the cache or the archive.", day.format("%Y-%m-%d")));
+                self.set_game_data(None);
+            }
+            return;
+        }
+
+        self.loading = true;
+        self.refresher.spawn(Target::Day, move || Refreshed::Day { day, league, cache_key, game_data: provider.fetch_day(day) });
+    }
+
+    // New games start with the first one selected and the list scrolled to the top
+    pub fn set_game_data(&mut self, mut game_data: Option<GameData>) {
+        if let Some(game_data) = &mut game_data {
+            self.arrange(&mut game_data.data);
+        }
+        let count = game_data.as_ref().map(|game_data| game_data.data.len()).unwrap_or_default();
+        self.game_data = game_data;
+        self.stale = None;
+        // refreshes of the same day keep the ratings and the form
+        let rated = (self.league, self.day.date_naive());
+        if self.elo.as_ref().map(|(of, _)| *of) != Some(rated) {
+            let games = self.games_before_day();
+            self.form = games.as_deref().map(Form::compute);
+            self.elo = games.map(|games| (rated, Ratings::compute(&games)));
+        }
+        self.games_state = TableState::default().with_selected((count > 0).then_some(0));
+        // only the first games after the jump can have it
+        self.select_pending();
+        self.pending_game = None;
+    }
+
+    pub fn select_pending(&mut self) {
+        let Some(id) = self.pending_game else {
+            return;
+        };
+        if let Some(index) = self.game_data.as_ref().and_then(|game_data| game_data.data.iter().position(|game| game.id == id)) {
+            self.games_state.select(Some(index));
+            self.pending_game = None;
+        }
+    }
+
+    // Refreshed games keep the selected row where possible
+    pub fn replace_game_data(&mut self, game_data: GameData) {
+        let selected = self.games_state.selected();
+        self.set_game_data(Some(game_data));
+        if let Some(selected) = selected {
+            self.select_game(selected as isize);
+        }
+    }
+
+    // Sorted first so the games keep the sort order within their group
+    pub fn arrange(&self, games: &mut [Game]) {
+        self.game_sort.sort(games, self.provider().id(), self.config.favorite_teams.get(self.provider().id()).map(String::as_str));
+        self.grouping.arrange(games);
+    }
+
+    // Arrange the shown games again, the selected game stays selected wherever it ends up
+    pub fn rearrange_games(&mut self) {
+        let Some(mut game_data) = self.game_data.take() else {
+            return;
+        };
+        let id = self.games_state.selected().and_then(|index| game_data.data.get(index)).map(|game| game.id);
+        self.arrange(&mut game_data.data);
+        if let Some(index) = game_data.data.iter().position(|game| Some(game.id) == id) {
+            self.games_state.select(Some(index));
+        }
+        self.game_data = Some(game_data);
+    }
+
+    pub fn select_game(&mut self, delta: isize) {
+        let count = self.game_data.as_ref().map(|game_data| game_data.data.len()).unwrap_or_default();
+        if count == 0 {
+            return;
+        }
+        let selected = self.games_state.selected().unwrap_or_default() as isize + delta;
+        self.games_state.select(Some(selected.clamp(0, count as isize - 1) as usize));
+    }
+}
+
+// Refetch what is shown once a different day or set of games is browsed
+pub fn reload_if_moved(app: &mut App, shown: (DateTime<Utc>, usize, String)) {
+    clamp_to_season(app);
+    if shown == (app.day, app.league, app.provider().cache_key()) {
+        return;
+    }
+    app.load_day();
+    app.standings = None;
+    app.opening = None;
+    // the week only has to be fetched again once the day left it
+    let same_league = (shown.1, shown.2) == (app.league, app.provider().cache_key());
+    if !same_league || app.week.as_ref().is_some_and(|week| week.start != week::week_range(app.day.date_naive()).0) {
+        app.week = None;
+    }
+    load_view(app);
+}
+
+pub fn switch_league(app: &mut App, league: usize) {
+    app.league = league;
+    app.opening = None;
+    app.details = None;
+    app.tournament = None;
+    app.head_to_head = None;
+    app.matrix = None;
+    app.standings_sort = Sort::default();
+    app.standings_scroll = 0;
+    app.players = PlayersState::default();
+    app.teams = TeamsState::default();
+}
+
+// Pick up edits of the config file without losing the current day and league
+pub fn reload_config_if_changed(app: &mut App) -> Result<()> {
+    let modified = config::config_modified();
+    if modified == app.config_modified {
+        return Ok(());
+    }
+    app.config_modified = modified;
+
+    // --accessible stays on, --offline stays as it was started
+    let config = match Config::load() {
+        Ok(config) => Config { accessible: config.accessible || app.config.accessible, offline: app.config.offline, ..config },
+        Err(e) => {
+            app.toasts.error(format!("Config not reloaded: {:#}", e));
+            return Ok(());
+        }
+    };
+
+    set_providers(app, provider::build_providers(&config));
+    app.keymap = build_keymap(&app.providers)?;
+    match store::open(&config) {
+        Ok(cache) => app.cache = cache,
+        Err(e) => app.toasts.error(format!("Store not changed: {:#}", e)),
+    }
+    match Archive::open(&config) {
+        Ok(archive) => app.archive = archive,
+        Err(e) => app.toasts.error(format!("Archive not changed: {:#}", e)),
+    }
+    app.announcer = Announcer::from_config(&config);
+    if let Err(e) = app.notifier.reconfigure(&config) {
+        app.toasts.error(format!("Notifications not reconfigured: {:#}", e));
+    }
+    match Alerts::from_config(&config) {
+        Ok(alerts) => app.alerts = alerts,
+        Err(e) => app.toasts.error(format!("Alerts not changed: {:#}", e)),
+    }
+    match Theme::from_config(&config) {
+        Ok(theme) => app.theme = theme,
+        Err(e) => app.toasts.error(format!("Theme not changed: {:#}", e)),
+    }
+    app.hyperlinks = Hyperlinks::detect(&config);
+    #[cfg(feature = "logos")]
+    {
+        app.logos = logos::Logos::detect(&config);
+    }
+    app.config = config;
+    app.toasts.info("Config reloaded.");
+
+    // the wizard is not needed anymore if a key was added by hand
+    if app.setup.is_some() && app.config.api_key().is_some() {
+        app.setup = None;
+    }
+    if app.setup.is_none() {
+        app.load_day();
+    }
+    Ok(())
+}
+
+// Providers built from an edited config or a new API key. The Olympics come and go
+// with the config and the calendar, so when the leagues changed the shown one is
+// found again by id, and the refreshes and followed games that carry league
+// indices are dropped.
+pub fn set_providers(app: &mut App, providers: Vec<Arc<dyn Provider>>) {
+    let ids = |providers: &[Arc<dyn Provider>]| providers.iter().map(|provider| provider.id().to_string()).collect::<Vec<_>>();
+    if ids(&app.providers) == ids(&providers) {
+        app.providers = providers;
+        return;
+    }
+    let shown = app.provider().id().to_string();
+    app.providers = providers;
+    app.refresher.discard_running();
+    app.loading = false;
+    app.opening = None;
+    app.followed = Followed::default();
+    match app.providers.iter().position(|provider| provider.id() == shown) {
+        Some(league) => app.league = league,
+        None => switch_league(app, 0),
+    }
+    load_view(app);
+}
+
+pub fn show_view(app: &mut App, view: View) {
+    app.view = view;
+    app.tournament = None;
+    app.head_to_head = None;
+    app.matrix = None;
+    load_view(app);
+}
+
+// Fetch what the shown view needs in the background, unless it is already loaded
+fn load_view(app: &mut App) {
+    let league = app.league;
+    let provider = app.providers[league].clone();
+    let day = app.day;
+    match app.view {
+        View::Standings if app.standings.is_none() && app.refresher.spinner(Target::Standings).is_empty() => {
+            // archived standings are read from the disk, only fetched ones wait
+            match app.archived_standings() {
+                Some(standings) => app.set_standings(Some(standings)),
+                None => app.refresher.spawn(Target::Standings, move || Refreshed::Standings { day, league, standings: provider.fetch_standings(day) }),
+            }
+        }
+        View::Teams if app.teams.teams.is_none() && app.refresher.spinner(Target::Teams).is_empty() => {
+            app.refresher.spawn(Target::Teams, move || Refreshed::Teams { league, teams: provider.fetch_teams() });
+        }
+        View::Week if app.week.is_none() && app.refresher.spinner(Target::Week).is_empty() => {
+            let (start, end) = week::week_range(day.date_naive());
+            app.refresher.spawn(Target::Week, move || Refreshed::Week { league, start, games: provider.fetch_range(start, end) });
+        }
+        _ => {}
+    }
+}
+
+// Fetch the box score of the selected game, it opens when it comes in
+pub fn open_details(app: &mut App) {
+    let selected = app.games_state.selected();
+    let Some(game) = app.game_data.as_ref().zip(selected).and_then(|(game_data, index)| game_data.data.get(index)).cloned() else {
+        return;
+    };
+    let league = app.league;
+    let provider = app.providers[league].clone();
+    app.opening = Some(Opening::Details(game.id));
+    app.refresher.spawn(Target::Details, move || Refreshed::Details { league, details: provider.fetch_details(&game).map(Box::new) });
+}
+
+// First and last day of the browsed season in the shown league, if one is picked
+pub fn season_range(app: &App) -> Option<(NaiveDate, NaiveDate)> {
+    app.provider().season_range(app.season?)
+}
+
+// Keep the browsed day within the picked season, leagues without seasons go anywhere
+pub fn clamp_to_season(app: &mut App) {
+    let Some((start, end)) = season_range(app) else {
+        return;
+    };
+    let date = app.day.date_naive().clamp(start, end);
+    app.day = Utc.from_utc_datetime(&date.and_time(app.day.time()));
+}
+
+// "1996-97" for seasons over two years, "2023" for the others
+pub fn season_label(provider: &dyn Provider, season: i32) -> String {
+    match provider.season_range(season) {
+        Some((start, end)) if start.year() != end.year() => format!("{}-{:02}", season, end.year() % 100),
+        _ => season.to_string(),
+    }
+}
+
+// Show the scoreboard of another day, keeping the time of day
+pub fn jump_to(app: &mut App, date: NaiveDate) {
+    let shown = (app.day, app.league, app.provider().cache_key());
+    app.jumps.record(app.day);
+    app.day = Utc.from_utc_datetime(&date.and_time(app.day.time()));
+    clamp_to_season(app);
+    app.view = View::Games;
+    if shown != (app.day, app.league, app.provider().cache_key()) {
+        app.details = None;
+        app.opening = None;
+        app.load_day();
+        app.standings = None;
+        app.week = None;
+    }
+}
+
+// Show the live game of a favorite team of any league, or the live game of the shown
+// league with the smallest margin. The games of the day are loaded as usual, the
+// game is selected once they are there. Today's games of the shown league are
+// fetched in the background first when another day is shown.
+pub fn jump_to_live(app: &mut App) {
+    let margin = |game: &Game| game.home_team_score.abs_diff(game.visitor_team_score);
+    if let Some((league, game)) = app.followed.live().min_by_key(|(_, game)| margin(game)).cloned() {
+        show_live_game(app, league, game);
+        return;
+    }
+    let today = Utc::now();
+    let games = match &app.game_data {
+        Some(game_data) if app.day.date_naive() == today.date_naive() => game_data.data.clone(),
+        _ => {
+            let league = app.league;
+            let provider = app.providers[league].clone();
+            let cache_key = provider.cache_key();
+            app.opening = Some(Opening::Live(today));
+            app.refresher.spawn(Target::Day, move || Refreshed::Day { day: today, league, cache_key, game_data: provider.fetch_day(today) });
+            return;
+        }
+    };
+    jump_to_closest_live(app, games);
+}
+
+// The live game of the shown league with the smallest margin among the games
+pub fn jump_to_closest_live(app: &mut App, games: Vec<Game>) {
+    let margin = |game: &Game| game.home_team_score.abs_diff(game.visitor_team_score);
+    match games.into_iter().filter(Game::is_live).min_by_key(margin) {
+        Some(game) => show_live_game(app, app.league, game),
+        None => app.toasts.info(format!("No live {} games.", app.provider().name())),
+    }
+}
+
+fn show_live_game(app: &mut App, league: usize, game: Game) {
+    if league != app.league {
+        switch_league(app, league);
+    }
+    show_view(app, View::Games);
+    let date = archive::game_date(&game).unwrap_or_else(|| Utc::now().date_naive());
+    if date != app.day.date_naive() {
+        app.jumps.record(app.day);
+        app.day = Utc.from_utc_datetime(&date.and_time(app.day.time()));
+    }
+    app.pending_game = Some(game.id);
+    app.select_pending();
+}
+
+// Fetch the games of the calendar's month in the background to mark the game days
+pub fn load_calendar_month(app: &mut App) {
+    let Some(calendar) = &mut app.calendar else {
+        return;
+    };
+    let Some(month) = calendar.request() else {
+        return;
+    };
+    let (start, end) = calendar.month_range();
+    let league = app.league;
+    let provider = app.providers[league].clone();
+    app.refresher.spawn(Target::Calendar, move || Refreshed::Month { league, month, games: provider.fetch_range(start, end) });
+}
+
+// Fetch every past day of the browsed month that is not cached yet
+pub fn warm_cache(app: &mut App) {
+    if app.cache.read_only() {
+        app.toasts.warning("The cache is read-only, nothing to warm.");
+        return;
+    }
+    let provider = app.providers[app.league].clone();
+    let cache = app.cache.clone();
+    let archive = app.archive.clone();
+    let cache_key = provider.cache_key();
+
+    let first = app.day.date_naive().with_day(1).unwrap_or(app.day.date_naive());
+    let yesterday = Utc::now().date_naive() - Duration::days(1);
+    let days: Vec<DateTime<Utc>> = first
+        .iter_days()
+        .take_while(|date| date.month() == first.month() && *date <= yesterday)
+        .map(|date| Utc.from_utc_datetime(&date.and_time(app.day.time())))
+        .collect();
+
+    let name = format!("Warm cache: {} {}", provider.name(), first.format("%Y-%m"));
+    app.toasts.info(format!("Started {}", name));
+    app.jobs.spawn(name, move |context| {
+        context.set_total(days.len());
+        for day in days {
+            if context.is_cancelled() {
+                break;
+            }
+            if !cache.contains(&cache_key, day) {
+                let game_data = provider.fetch_day(day).ok_or_else(|| anyhow::anyhow!("could not fetch {}", day.format("%Y-%m-%d")))?;
+                cache.put(&cache_key, day, &game_data)?;
+                if let Some(archive) = &archive {
+                    archive.record(&cache_key, &game_data.data)?;
+                }
+            }
+            context.advance();
+        }
+        Ok(())
+    });
+}
+
+// Tell the user about jobs that ended while they were looking elsewhere
+pub fn report_jobs(app: &mut App) {
+    for (name, state) in app.jobs.finished() {
+        match state {
+            JobState::Done => app.toasts.info(format!("{} done", name)),
+            JobState::Cancelled => app.toasts.warning(format!("{} cancelled", name)),
+            JobState::Failed(error) => app.toasts.error(format!("{} failed: {}", name, error)),
+            JobState::Running => {}
+        }
+    }
+}
+
+// Search in the background once typing paused, the endpoint only gets the start
+// of the name and the candidates are matched fuzzily
+pub fn search_players_if_due(app: &mut App) {
+    let Some(query) = app.players.due_query() else {
+        return;
+    };
+    let league = app.league;
+    let provider = app.providers[league].clone();
+    app.refresher.spawn(Target::Players, move || {
+        let players = provider.search_players(&players::search_term(&query));
+        Refreshed::Players { league, query, players }
+    });
+}
+
+// Fetch the season numbers of the player selected in the search results
+pub fn open_player(app: &mut App) {
+    let Some(player) = app.players.selected().cloned() else {
+        return;
+    };
+    let (league, day) = (app.league, app.day);
+    let provider = app.providers[league].clone();
+    app.opening = Some(Opening::Player(player.id));
+    app.refresher.spawn(Target::Player, move || {
+        let season = provider.fetch_player_season(&player, day);
+        Refreshed::PlayerSeason { league, player, season }
+    });
+}
+
+// Fetch the season schedule of the team selected in the teams view
+pub fn open_schedule(app: &mut App) {
+    let Some(team) = app.teams.selected().cloned() else {
+        return;
+    };
+    let (league, day) = (app.league, app.day);
+    let provider = app.providers[league].clone();
+    app.opening = Some(Opening::Schedule(team.id));
+    app.refresher.spawn(Target::Schedule, move || {
+        let games = provider.fetch_schedule(&team, day);
+        Refreshed::Schedule { league, team, games }
+    });
+}
+
+// Earlier meetings of the selected game's teams. The season's come from the home
+// team's schedule, fetched in the background, or the archive for leagues without
+// schedules, earlier seasons from the archive only.
+pub fn open_head_to_head(app: &mut App) {
+    let selected = app.games_state.selected();
+    let Some(game) = app.game_data.as_ref().zip(selected).and_then(|(game_data, index)| game_data.data.get(index)).cloned() else {
+        return;
+    };
+    let (league, day) = (app.league, app.day);
+    let provider = app.providers[league].clone();
+    app.opening = Some(Opening::HeadToHead(game.id));
+    app.refresher.spawn(Target::Schedule, move || {
+        let games = provider.fetch_schedule(&game.home_team, day);
+        Refreshed::Schedule { league, team: game.home_team, games }
+    });
+}
+
+// The meetings once the home team's schedule came in, None when it did not
+pub fn show_head_to_head(app: &mut App, game: &Game, schedule: Option<Vec<Game>>) {
+    let provider = app.provider();
+    let cache_key = provider.cache_key();
+    let season = app.season_of_day();
+    let archived = |season: i32| match (&app.archive, provider.season_range(season)) {
+        (Some(archive), Some((start, end))) => archive.range(&cache_key, start, end),
+        _ => Vec::new(),
+    };
+
+    let Some(games) = schedule.or_else(|| season.map(archived).filter(|games| !games.is_empty())) else {
+        app.toasts.warning(format!("Could not load the meetings of {} and {}.", game.home_team.full_name, game.visitor_team.full_name));
+        return;
+    };
+    let earlier = season.map(|season| (1..=app.config.head_to_head_seasons as i32).flat_map(|back| archived(season - back)).collect()).unwrap_or_default();
+    app.head_to_head = Some(HeadToHead::new(game, games, earlier));
+}
+
+// Show the record of every team against every other team of the season up to the
+// browsed day, from the cached days and the archive without fetching anything
+pub fn toggle_matrix(app: &mut App) {
+    if app.matrix.take().is_some() {
+        return;
+    }
+    let provider = app.provider();
+    let Some((start, _)) = app.season_of_day().and_then(|season| provider.season_range(season)) else {
+        app.toasts.info(format!("{} has no season around {}.", provider.name(), app.day.format("%Y-%m-%d")));
+        return;
+    };
+    let cache_key = provider.cache_key();
+    let games: Vec<Game> = start
+        .iter_days()
+        .take_while(|date| *date <= app.day.date_naive())
+        .flat_map(|date| match app.cache.get(&cache_key, Utc.from_utc_datetime(&date.and_time(app.day.time()))) {
+            Some(game_data) => game_data.data,
+            None => app.archive.as_ref().map(|archive| archive.day(&cache_key, date)).unwrap_or_default(),
+        })
+        .collect();
+    app.matrix = Some(Matrix::compute(&games));
+}
+
+// Fetch the tournament the browsed day belongs to, or go back to the games
+pub fn toggle_tournament(app: &mut App) {
+    if app.tournament.is_some() {
+        app.tournament = None;
+        return;
+    }
+    let (league, day) = (app.league, app.day);
+    let provider = app.providers[league].clone();
+    app.opening = Some(Opening::Tournament);
+    app.refresher.spawn(Target::Tournament, move || Refreshed::Tournament { day, league, tournament: provider.fetch_tournament(day).map(Box::new) });
+}
+
+// Global bindings plus the league specific ones of every provider
+pub fn build_keymap(providers: &[Arc<dyn Provider>]) -> Result<KeyMap> {
+    let mut keymap = KeyMap::default();
+    for provider in providers {
+        for binding in provider.keybindings() {
+            keymap.register(provider.id(), binding.key, Action::League(binding.action))?;
+        }
+    }
+    Ok(keymap)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::store::Backend;
+    use rust_sports_cli::provider::api::ApiClient;
+    use rust_sports_cli::provider::nba::NbaProvider;
+
+    // The NBA on the client with the games of the day shown, nothing is written to disk
+    pub fn nba_app(client: Arc<dyn ApiClient>, day: DateTime<Utc>) -> App {
+        let provider = Arc::new(NbaProvider::new("key", client));
+        let config = Config { store: Backend::Memory, ..Config::default() };
+        let mut app = App::new(config, vec![provider], None, None).unwrap();
+        app.day = day;
+        let game_data = app.fetch_day(day);
+        app.set_game_data(game_data);
+        app
+    }
+
+    // Take over background results until none is running anymore
+    pub fn settle(app: &mut App) {
+        for _ in 0..200 {
+            crate::background::apply_refreshed(app);
+            if app.refresher.any_spinner().is_empty() {
+                return;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        panic!("background fetches still running");
+    }
+
+    #[test]
+    fn dropped_leagues_take_their_indices_along() {
+        use rust_sports_cli::provider::api::Canned;
+        use rust_sports_cli::provider::nhl::NhlProvider;
+        use rust_sports_cli::provider::olympics::OlympicsProvider;
+
+        let day = Utc.with_ymd_and_hms(2024, 1, 15, 12, 0, 0).unwrap();
+        let mut app = nba_app(Arc::new(Canned::new()), day);
+        let nba = app.providers[0].clone();
+        set_providers(&mut app, vec![nba.clone(), Arc::new(NhlProvider), Arc::new(OlympicsProvider)]);
+        app.league = 2;
+        app.followed.games = vec![(2, Game::default())];
+        app.refresher.spawn(Target::Week, || Refreshed::Week { league: 2, start: NaiveDate::default(), games: Some(Vec::new()) });
+
+        // the Games are over, index 2 is gone
+        set_providers(&mut app, vec![nba.clone(), Arc::new(NhlProvider)]);
+        assert_eq!(app.provider().id(), "nba");
+        assert!(app.followed.games.is_empty());
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        assert!(app.refresher.poll().is_empty());
+
+        // the shown league is found again by id
+        app.league = 1;
+        set_providers(&mut app, vec![Arc::new(OlympicsProvider), nba, Arc::new(NhlProvider)]);
+        assert_eq!((app.league, app.provider().id()), (2, "nhl"));
+    }
+}
@@ -0,0 +1,189 @@
+use crate::config::{home_dir, Config};
+use crate::Game;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Months, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+#[cfg(feature = "sqlite")]
+use std::sync::Arc;
+
+/// Every fetched game by provider cache key, in one JSON file per month mapping game
+/// ids to their latest version or in an SQLite database. Unlike the day cache nothing
+/// is ever evicted, so past seasons stay available without fetching them again.
+#[derive(Clone)]
+pub struct Archive {
+    dir: PathBuf,
+    #[cfg(feature = "sqlite")]
+    database: Option<Arc<crate::sqlite::SqliteArchive>>,
+}
+
+/// Where the archive keeps its games
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveBackend {
+    /// JSON files by month in the archive directory
+    #[default]
+    Files,
+    /// archive.sqlite3 in the archive directory, a games table keyed by league, id
+    /// and date (needs the `sqlite` feature)
+    Sqlite,
+}
+
+// One file of the archive
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct Month {
+    games: BTreeMap<u32, Game>,
+    // RFC 3339 time the games of a day (YYYY-MM-DD) were fetched last
+    fetched: BTreeMap<String, String>,
+}
+
+/// $XDG_DATA_HOME/rust_sports_cli/archive, falling back to ~/.local/share
+pub fn default_archive_dir() -> PathBuf {
+    let base = std::env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home_dir().join(".local").join("share"));
+
+    base.join("rust_sports_cli").join("archive")
+}
+
+/// Day the game is played, the API sends either a date or a full timestamp
+pub fn game_date(game: &Game) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(game.date.get(..10)?, "%Y-%m-%d").ok()
+}
+
+impl Archive {
+    /// None unless the archive is turned on in the config
+    pub fn open(config: &Config) -> Result<Option<Archive>> {
+        if !config.archive {
+            return Ok(None);
+        }
+        let dir = default_archive_dir();
+        Ok(Some(match config.archive_backend {
+            ArchiveBackend::Files => Archive {
+                dir,
+                #[cfg(feature = "sqlite")]
+                database: None,
+            },
+            #[cfg(feature = "sqlite")]
+            ArchiveBackend::Sqlite => {
+                let database = crate::sqlite::SqliteArchive::open(&dir.join("archive.sqlite3"))?;
+                Archive { dir, database: Some(Arc::new(database)) }
+            }
+            #[cfg(not(feature = "sqlite"))]
+            ArchiveBackend::Sqlite => anyhow::bail!("The SQLite archive needs sports built with the sqlite feature"),
+        }))
+    }
+
+    fn path(&self, provider: &str, year: i32, month: u32) -> PathBuf {
+        self.dir.join(provider).join(format!("{:04}-{:02}.json", year, month))
+    }
+
+    fn month(&self, provider: &str, year: i32, month: u32) -> Month {
+        std::fs::read_to_string(self.path(provider, year, month))
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    /// Newer versions of a game replace the archived one
+    pub fn record(&self, provider: &str, games: &[Game]) -> Result<()> {
+        #[cfg(feature = "sqlite")]
+        if let Some(database) = &self.database {
+            return database.record(provider, games);
+        }
+        let mut months: BTreeMap<(i32, u32), Vec<&Game>> = BTreeMap::new();
+        for game in games {
+            if let Some(date) = game_date(game) {
+                months.entry((date.year(), date.month())).or_default().push(game);
+            }
+        }
+        for ((year, month), games) in months {
+            let mut archived = self.month(provider, year, month);
+            let now = Utc::now().to_rfc3339();
+            for game in games {
+                archived.fetched.extend(game_date(game).map(|date| (date.to_string(), now.clone())));
+                archived.games.insert(game.id, game.clone());
+            }
+
+            let path = self.path(provider, year, month);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).with_context(|| format!("Could not create directory {}", parent.display()))?;
+            }
+            // written next to it first so a crash never leaves half a month behind
+            let partial = path.with_extension("json.partial");
+            std::fs::write(&partial, serde_json::to_string(&archived)?).with_context(|| format!("Could not write {}", partial.display()))?;
+            std::fs::rename(&partial, &path).with_context(|| format!("Could not write {}", path.display()))?;
+        }
+        Ok(())
+    }
+
+    /// Archived games played between the dates, both included, oldest first
+    pub fn range(&self, provider: &str, from: NaiveDate, to: NaiveDate) -> Vec<Game> {
+        #[cfg(feature = "sqlite")]
+        if let Some(database) = &self.database {
+            return database.range(provider, from, to).unwrap_or_default();
+        }
+        let mut games = Vec::new();
+        let mut first = from.with_day(1).unwrap_or(from);
+        while first <= to {
+            let archived = self.month(provider, first.year(), first.month());
+            games.extend(archived.games.into_values().filter(|game| game_date(game).is_some_and(|date| from <= date && date <= to)));
+            match first.checked_add_months(Months::new(1)) {
+                Some(next) => first = next,
+                None => break,
+            }
+        }
+        games.sort_by_key(|game| (game_date(game), game.id));
+        games
+    }
+
+    pub fn day(&self, provider: &str, date: NaiveDate) -> Vec<Game> {
+        self.range(provider, date, date)
+    }
+
+    // Seasons fetched completely by sync, by the year they start in, with when they were
+    fn synced(&self, provider: &str) -> BTreeMap<i32, String> {
+        std::fs::read_to_string(self.dir.join(provider).join("synced.json"))
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn mark_synced(&self, provider: &str, season: i32) -> Result<()> {
+        #[cfg(feature = "sqlite")]
+        if let Some(database) = &self.database {
+            return database.mark_synced(provider, season);
+        }
+        let mut synced = self.synced(provider);
+        synced.insert(season, Utc::now().to_rfc3339());
+        let path = self.dir.join(provider).join("synced.json");
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| format!("Could not create directory {}", parent.display()))?;
+        }
+        std::fs::write(&path, serde_json::to_string(&synced)?).with_context(|| format!("Could not write {}", path.display()))
+    }
+
+    /// When the season was synced last, every game played before is in the archive
+    pub fn synced_at(&self, provider: &str, season: i32) -> Option<DateTime<Utc>> {
+        #[cfg(feature = "sqlite")]
+        if let Some(database) = &self.database {
+            return database.synced_at(provider, season).ok().flatten();
+        }
+        let synced = self.synced(provider);
+        DateTime::parse_from_rfc3339(synced.get(&season)?).ok().map(|synced| synced.with_timezone(&Utc))
+    }
+
+    /// When the games of the day were archived last, None if they never were
+    pub fn updated(&self, provider: &str, date: NaiveDate) -> Option<DateTime<Utc>> {
+        #[cfg(feature = "sqlite")]
+        if let Some(database) = &self.database {
+            return database.updated(provider, date).ok().flatten();
+        }
+        let archived = self.month(provider, date.year(), date.month());
+        let fetched = archived.fetched.get(&date.to_string())?;
+        DateTime::parse_from_rfc3339(fetched).ok().map(|fetched| fetched.with_timezone(&Utc))
+    }
+}
@@ -0,0 +1,532 @@
+use crate::app::{jump_to_closest_live, reload_if_moved, show_head_to_head, App, Opening, View};
+use crate::config::NotifyStrategy;
+use crate::details::GameDetails;
+use crate::notify::{Event, Notification};
+use crate::provider::Provider;
+use crate::refresh::{Refreshed, Target};
+use crate::schedule::Schedule;
+use crate::{announce, followed, ids, overlay, poll, week, Game, GameData};
+use chrono::Utc;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Instant;
+
+// Refetch what the shown view displays in the background, bypassing the disk
+// cache. Refreshing all covers everything that is loaded for the league.
+pub fn refresh(app: &mut App, all: bool) {
+    if app.config.offline {
+        app.toasts.info("Offline, nothing is refreshed.");
+        return;
+    }
+    let league = app.league;
+    let provider = app.providers[league].clone();
+    let day = app.day;
+
+    if all || app.view == View::Games {
+        refresh_day(app);
+    }
+    // archived standings are of past days, which do not change anymore
+    if let Some(standings) = app.archived_standings().filter(|_| app.view == View::Standings || (all && app.standings.is_some())) {
+        app.set_standings(Some(standings));
+    } else if app.view == View::Standings || (all && app.standings.is_some()) {
+        let provider = provider.clone();
+        app.refresher.spawn(Target::Standings, move || Refreshed::Standings { day, league, standings: provider.fetch_standings(day) });
+    }
+    if app.view == View::Week || (all && app.week.is_some()) {
+        let provider = provider.clone();
+        let (start, end) = week::week_range(day.date_naive());
+        app.refresher.spawn(Target::Week, move || Refreshed::Week { league, start, games: provider.fetch_range(start, end) });
+    }
+    if app.view == View::Teams || all {
+        if let Some(team) = app.teams.schedule.as_ref().map(|schedule| schedule.team.clone()) {
+            let provider = provider.clone();
+            app.refresher.spawn(Target::Schedule, move || {
+                let games = provider.fetch_schedule(&team, day);
+                Refreshed::Schedule { league, team, games }
+            });
+        } else if app.view == View::Teams || app.teams.teams.is_some() {
+            app.refresher.spawn(Target::Teams, move || Refreshed::Teams { league, teams: provider.fetch_teams() });
+        }
+    }
+}
+
+// Refetch the scoreboard and the opened box score
+fn refresh_day(app: &mut App) {
+    let league = app.league;
+    let provider = app.providers[league].clone();
+    let (day, cache_key) = (app.day, provider.cache_key());
+    app.refreshed_day_at = Instant::now();
+    if let Some(base) = app.config.auto_refresh_interval() {
+        let games = app.game_data.as_ref().map(|game_data| game_data.data.as_slice()).unwrap_or_default();
+        let base = app.poll_adaptive.interval(base, games);
+        app.poll_interval = poll::interval(base, games, app.config.poll_break_factor(), app.config.poll_jitter(), &mut app.poll_rng);
+    }
+
+    if let Some(game) = app.details.as_ref().map(|details| details.game.clone()) {
+        let provider = provider.clone();
+        app.refresher.spawn(Target::Details, move || Refreshed::Details { league, details: provider.fetch_details(&game).map(Box::new) });
+    }
+    app.refresher.spawn(Target::Day, move || Refreshed::Day { day, league, cache_key, game_data: provider.fetch_day(day) });
+}
+
+// Keep live scores current while today is shown, past days can not change anymore.
+// The interval is drawn at every refresh from how often the live games changed,
+// longer while they are in a break.
+pub fn auto_refresh(app: &mut App) {
+    if app.config.auto_refresh_interval().is_none() {
+        return;
+    }
+    let today = app.day.date_naive() == Utc::now().date_naive();
+    let live = app.game_data.as_ref().is_some_and(|game_data| game_data.data.iter().any(Game::is_live));
+    if !today || !live || app.config.offline || app.loading || !app.refresher.spinner(Target::Day).is_empty() || app.refreshed_day_at.elapsed() < app.poll_interval {
+        return;
+    }
+    refresh_day(app);
+}
+
+// Check today's games of the favorite teams of every league, on the refresh interval
+// while one of them is live
+pub fn refresh_followed(app: &mut App) {
+    let Some(interval) = app.config.auto_refresh_interval() else {
+        return;
+    };
+    let clutch_interval = std::time::Duration::from_secs(app.config.clutch.refresh_secs);
+    let due = app.followed.due(interval, clutch_interval);
+    if app.config.favorite_teams.is_empty() || !app.refresher.spinner(Target::Followed).is_empty() || !due {
+        return;
+    }
+    app.followed.fetched_at = Some(Instant::now());
+
+    let leagues: Vec<(usize, Arc<dyn Provider>, String)> = app
+        .providers
+        .iter()
+        .enumerate()
+        .filter_map(|(league, provider)| Some((league, provider.clone(), app.config.favorite_teams.get(provider.id())?.clone())))
+        .collect();
+    app.refresher.spawn(Target::Followed, move || {
+        let today = Utc::now();
+        let fetched: Vec<Option<Vec<(usize, Game)>>> = leagues
+            .iter()
+            .map(|(league, provider, team)| {
+                let games = provider.fetch_day(today)?.data;
+                ids::learn_games(provider.id(), &games);
+                Some(followed::of_team(games, provider.id(), team).map(|game| (*league, game)).collect())
+            })
+            .collect();
+        let games = match fetched.iter().all(Option::is_none) {
+            true => None,
+            false => Some(fetched.into_iter().flatten().flatten().collect()),
+        };
+        Refreshed::Followed { games }
+    });
+}
+
+// Notifications for periods and games of the followed teams that finished since
+// the previous fetch, hidden scores are not sent. Leagues of notify_desktop get
+// their overtimes and finals on the desktop in any case.
+fn notify_period_ends(app: &mut App, old: &[(usize, Game)]) {
+    let strategy = app.config.notify_finals;
+    let mut notifications = Vec::new();
+    let mut finished = false;
+    for (league, game, period) in followed::period_ends(old, &app.followed.games) {
+        finished |= game.is_final();
+        let provider = app.providers[league].as_ref();
+        // numbered periods read "period 2", overtimes keep their label
+        let label = provider.period_label(period as usize - 1);
+        let label = if label.parse::<u32>().is_ok() { format!("period {}", label) } else { label };
+        let overtime = !game.is_final() && provider.period_label(game.period as usize - 1).parse::<u32>().is_err();
+        let routed = match game.is_final() {
+            true => strategy == NotifyStrategy::PerGame,
+            false => app.config.notify_period_ends,
+        };
+        let desktop = (game.is_final() || overtime) && app.config.notify_desktop.iter().any(|id| id == provider.id());
+        if !(routed || desktop) || !app.spoilers.shows(game) {
+            continue;
+        }
+        let title = format!("{} {} vs {}", provider.name(), game.home_team.abbreviation, game.visitor_team.abbreviation);
+        let mut body = match (game.is_final(), overtime) {
+            (true, _) => format!("Final: {}", provider.score(game)),
+            (false, true) => format!("{} after the {}: {}", provider.period_label(game.period as usize - 1), label, provider.score(game)),
+            (false, false) => format!("End of {}: {}", label, provider.score(game)),
+        };
+        if let Some((home, visitor)) = game.linescore().get(period as usize - 1) {
+            body.push_str(&format!(" ({}-{} in the {})", home, visitor, label));
+        }
+        let event = match (game.is_final(), overtime) {
+            (true, _) => Event::Final,
+            (false, true) => Event::Overtime,
+            (false, false) => Event::PeriodEnd,
+        };
+        let teams = vec![game.home_team.abbreviation.clone(), game.visitor_team.abbreviation.clone()];
+        notifications.push((Notification { event, teams, title, body }, routed, desktop));
+    }
+
+    // the digest goes out once the last followed game of the day finished
+    let all_final = app.followed.games.iter().all(|(_, game)| game.is_final());
+    if strategy == NotifyStrategy::Digest && finished && all_final {
+        let results: Vec<String> = app
+            .followed
+            .games
+            .iter()
+            .map(|(league, game)| {
+                let provider = app.providers[*league].as_ref();
+                let score = app.spoilers.score(provider, game);
+                format!("{} {} {} {}", provider.name(), game.home_team.abbreviation, score, game.visitor_team.abbreviation)
+            })
+            .collect();
+        let teams = app
+            .followed
+            .games
+            .iter()
+            .flat_map(|(_, game)| [game.home_team.abbreviation.clone(), game.visitor_team.abbreviation.clone()])
+            .collect();
+        let notification = Notification { event: Event::Digest, teams, title: "Today's results".to_string(), body: results.join("\n") };
+        notifications.push((notification, true, false));
+    }
+
+    let mut failed = None;
+    for (notification, routed, desktop) in notifications {
+        let sent = match desktop {
+            true => app.notifier.notify_desktop(notification, routed),
+            false => app.notifier.notify(notification),
+        };
+        if let Err(e) = sent {
+            failed = Some(e);
+        }
+    }
+    if let Some(e) = failed {
+        app.toasts.error(format!("Could not notify: {:#}", e));
+    }
+}
+
+// Followed games that are close in their final minutes are polled faster, flash and
+// send a notification once if asked for
+fn check_clutch(app: &mut App) {
+    let config = &app.config.clutch;
+    let clutch: Vec<(usize, Game)> = app
+        .followed
+        .live()
+        .filter(|(league, game)| {
+            let provider = app.providers[*league].as_ref();
+            let margin = config.margins.get(provider.id()).copied().unwrap_or_else(|| provider.close_margin());
+            let final_minutes = provider.seconds_left(game).is_some_and(|seconds| seconds <= config.seconds_left);
+            config.enabled && final_minutes && game.home_team_score.abs_diff(game.visitor_team_score) <= margin
+        })
+        .cloned()
+        .collect();
+    let key = |league: usize, game: &Game| (app.providers[league].id().to_string(), game.id);
+    app.followed.clutch = clutch.iter().map(|(league, game)| key(*league, game)).collect();
+    // games that are not followed anymore, e.g. yesterday's, need no dedup
+    let followed: HashSet<(String, u32)> = app.followed.games.iter().map(|(league, game)| key(*league, game)).collect();
+    app.followed.notified.retain(|game| followed.contains(game));
+
+    if !config.notify {
+        return;
+    }
+    for (league, game) in clutch {
+        if !app.followed.notified.insert(key(league, &game)) {
+            continue;
+        }
+        let provider = app.providers[league].as_ref();
+        let title = format!("{} {} vs {}", provider.name(), game.home_team.abbreviation, game.visitor_team.abbreviation);
+        let body = format!("Close game: {} {}", app.spoilers.score(provider, &game), provider.clock(&game));
+        let teams = vec![game.home_team.abbreviation.clone(), game.visitor_team.abbreviation.clone()];
+        if let Err(e) = app.notifier.notify(Notification { event: Event::Clutch, teams, title, body }) {
+            app.toasts.error(format!("Could not notify: {:#}", e));
+        }
+    }
+}
+
+// Notifications of the configured alerts that became true for the fetched games,
+// hidden scores are not sent
+fn check_alerts<'a>(app: &mut App, games: impl IntoIterator<Item = (usize, &'a Game)>) {
+    if app.alerts.is_empty() {
+        return;
+    }
+    let mut failed = None;
+    for (league, game) in games {
+        let provider = app.providers[league].as_ref();
+        let favorite = app.config.favorite_teams.get(provider.id()).map(String::as_str);
+        let fired = app.alerts.check(provider, favorite, game);
+        if fired.is_empty() || !app.spoilers.shows(game) {
+            continue;
+        }
+        let body = format!(
+            "{} {} {} {} {}",
+            provider.name(),
+            game.home_team.abbreviation,
+            provider.score(game),
+            game.visitor_team.abbreviation,
+            provider.clock(game)
+        );
+        for title in fired {
+            let teams = vec![game.home_team.abbreviation.clone(), game.visitor_team.abbreviation.clone()];
+            if let Err(e) = app.notifier.notify(Notification { event: Event::Alert, teams, title, body: body.clone() }) {
+                failed = Some(e);
+            }
+        }
+    }
+    if let Some(e) = failed {
+        app.toasts.error(format!("Could not notify: {:#}", e));
+    }
+}
+
+// The alerts keep the state of the shown and followed games only, once final
+fn prune_alerts(app: &mut App) {
+    let league = app.provider().id().to_string();
+    let mut shown: HashSet<(String, u32)> = app.game_data.iter().flat_map(|game_data| &game_data.data).map(|game| (league.clone(), game.id)).collect();
+    shown.extend(app.followed.games.iter().map(|(league, game)| (app.providers[*league].id().to_string(), game.id)));
+    app.alerts.prune(&shown);
+}
+
+// Take over finished refreshes that still belong to what is shown, failed ones keep the old data
+// The shown games are kept in the sort and grouping order, fetched ones are compared the same way
+fn unchanged(app: &App, shown: &GameData, fetched: &GameData) -> bool {
+    let mut fetched = fetched.clone();
+    app.arrange(&mut fetched.data);
+    *shown == fetched
+}
+
+// Teams and players of every fetch go into the id table, so favorites match them
+// whichever provider of the league sent them
+fn learn_ids(app: &App, refreshed: &Refreshed) {
+    let id = |league: &usize| app.providers[*league].id();
+    match refreshed {
+        Refreshed::Day { league, game_data: Some(game_data), .. } => ids::learn_games(id(league), &game_data.data),
+        Refreshed::Teams { league, teams: Some(teams) } => ids::learn_teams(id(league), teams),
+        Refreshed::Players { league, players: Some(players), .. } => ids::learn_players(id(league), players),
+        Refreshed::Schedule { league, games: Some(games), .. } | Refreshed::Month { league, games: Some(games), .. } | Refreshed::Week { league, games: Some(games), .. } => {
+            ids::learn_games(id(league), games)
+        }
+        _ => {}
+    }
+}
+
+// Show what was asked for with a key once it came in, if the user is still where
+// they asked for it. Results fetched for something else are handed back.
+fn apply_opening(app: &mut App, refreshed: Refreshed) -> Option<Refreshed> {
+    let Some(opening) = app.opening else {
+        return Some(refreshed);
+    };
+    let failed = refreshed.failed();
+    match (opening, refreshed) {
+        (Opening::Details(id), Refreshed::Details { league, details }) if league == app.league => match details {
+            Some(details) if details.game.id != id => return Some(Refreshed::Details { league, details: Some(details) }),
+            Some(details) => app.details = Some(*details),
+            None => app.toasts.info("No box score available for this game."),
+        },
+        (Opening::Schedule(id), Refreshed::Schedule { league, team, games }) if league == app.league && team.id == id => match games {
+            Some(games) => app.teams.schedule = Some(Schedule::new(team, games)),
+            None => app.toasts.warning(format!("Could not load the schedule of {}.", team.full_name)),
+        },
+        (Opening::HeadToHead(id), Refreshed::Schedule { league, games, .. }) if league == app.league => {
+            let game = app.game_data.as_ref().and_then(|game_data| game_data.data.iter().find(|game| game.id == id)).cloned();
+            if let Some(game) = game {
+                show_head_to_head(app, &game, games);
+            }
+        }
+        (Opening::Player(id), Refreshed::PlayerSeason { league, player, season }) if league == app.league && player.id == id => {
+            app.players.season = Some((player, season));
+        }
+        (Opening::Tournament, Refreshed::Tournament { day, league, tournament }) if (day, league) == (app.day, app.league) => match tournament {
+            Some(tournament) => {
+                tournament.groups.iter().for_each(|group| ids::learn_games(app.provider().id(), &group.games));
+                app.tournament = Some(*tournament);
+            }
+            None => app.toasts.info(format!("No {} tournament around {}.", app.provider().name(), app.day.format("%Y-%m-%d"))),
+        },
+        (Opening::Live(asked), Refreshed::Day { day, league, cache_key, game_data }) if (day, league) == (asked, app.league) => {
+            app.opening = None;
+            let Some(game_data) = game_data else {
+                app.toasts.warning(format!("Could not load today's {} games.", app.provider().name()));
+                return None;
+            };
+            let shown = (app.day, app.league, app.provider().cache_key());
+            jump_to_closest_live(app, game_data.data.clone());
+            reload_if_moved(app, shown);
+            // the games just fetched are shown right away
+            if app.loading && app.day.date_naive() == day.date_naive() {
+                app.loading = false;
+                app.set_game_data(Some(game_data.clone()));
+            }
+            // cached and checked for alerts like every fetched day
+            return Some(Refreshed::Day { day, league, cache_key, game_data: Some(game_data) });
+        }
+        (_, refreshed) => return Some(refreshed),
+    }
+    app.opening = None;
+    app.fetch.record(!failed);
+    None
+}
+
+pub fn apply_refreshed(app: &mut App) {
+    for refreshed in app.refresher.poll() {
+        learn_ids(app, &refreshed);
+        let Some(refreshed) = apply_opening(app, refreshed) else {
+            continue;
+        };
+        if let Refreshed::Players { league, query, players } = refreshed {
+            if league != app.league {
+                continue;
+            }
+            match players {
+                Some(players) => app.players.set_matches(&query, players),
+                None => app.players.error = Some(format!("Player search is not available for {}.", app.provider().name())),
+            }
+            continue;
+        }
+        if let Refreshed::Month { league, month, games } = &refreshed {
+            let favorite = app.config.favorite_teams.get(app.provider().id()).cloned();
+            if let Some(calendar) = app.calendar.as_mut().filter(|_| *league == app.league) {
+                calendar.set_games(*month, games.as_deref().unwrap_or_default(), app.providers[*league].id(), favorite.as_deref());
+            }
+            continue;
+        }
+        // a day that could not be loaded has nothing to fall back to
+        if let Refreshed::Day { day, league, cache_key, game_data: None } = &refreshed {
+            if app.loading && (*day, *league, cache_key.clone()) == (app.day, app.league, app.provider().cache_key()) {
+                app.loading = false;
+                app.fetch.record(false);
+                if app.show_stale_day(cache_key, *day) {
+                    app.toasts.warning(format!("Could not load the games of {}, showing the archived ones.", day.format("%Y-%m-%d")));
+                } else {
+                    app.toasts.warning(format!("Could not load the games of {}.", day.format("%Y-%m-%d")));
+                    app.set_game_data(None);
+                }
+                continue;
+            }
+        }
+        app.fetch.record(!refreshed.failed());
+        if refreshed.failed() {
+            app.toasts.warning(format!("Could not refresh the {}, showing the previous data.", refreshed.target().label()));
+            continue;
+        }
+        match refreshed {
+            Refreshed::Day { day, league, cache_key, game_data: Some(game_data) } => {
+                // a scoreboard the server said did not change since the last poll
+                let shown = (day, league, cache_key.as_str()) == (app.day, app.league, app.provider().cache_key().as_str());
+                if shown && !app.loading {
+                    if let (Some(base), Some(old)) = (app.config.auto_refresh_interval(), &app.game_data) {
+                        app.poll_adaptive.observe(base, &old.data, &game_data.data);
+                    }
+                }
+                if shown && !app.loading && app.game_data.as_ref().is_some_and(|old| unchanged(app, old, &game_data)) {
+                    continue;
+                }
+                check_alerts(app, game_data.data.iter().map(|game| (league, game)));
+                let _ = app.cache.put(&cache_key, day, &game_data);
+                app.archive_games(&cache_key, &game_data.data);
+                if (day, league, cache_key) == (app.day, app.league, app.provider().cache_key()) {
+                    if app.loading {
+                        app.loading = false;
+                        app.set_game_data(Some(game_data));
+                    } else {
+                        announce_changes(app, &game_data);
+                        app.replace_game_data(game_data);
+                    }
+                }
+                prune_alerts(app);
+            }
+            Refreshed::Details { league, details: Some(details) } if league == app.league => {
+                if let Some(shown) = app.details.as_mut().filter(|shown| shown.game.id == details.game.id) {
+                    *shown = GameDetails { focus: shown.focus, sort: shown.sort, show_shots: shown.show_shots, scroll: shown.scroll, ..*details };
+                }
+            }
+            Refreshed::Standings { day, league, standings: Some(standings) } if (day, league) == (app.day, app.league) => {
+                app.set_standings(Some(standings));
+            }
+            Refreshed::Followed { games: Some(games) } => {
+                let old = std::mem::replace(&mut app.followed.games, games);
+                notify_period_ends(app, &old);
+                check_clutch(app);
+                let followed = app.followed.games.clone();
+                check_alerts(app, followed.iter().map(|(league, game)| (*league, game)));
+                prune_alerts(app);
+                if let Some(path) = &app.config.watch_party_file {
+                    if let Err(e) = overlay::write(path, &followed, &app.providers, &app.spoilers) {
+                        app.toasts.error(format!("Watch party file not written: {:#}", e));
+                    }
+                }
+            }
+            Refreshed::Week { league, start, games: Some(games) } if league == app.league => {
+                if let Some(shown) = app.week.as_mut().filter(|shown| shown.start == start) {
+                    shown.games = games;
+                }
+            }
+            Refreshed::Teams { league, teams: Some(teams) } if league == app.league => {
+                let selected = app.teams.state.selected();
+                app.teams.set_teams(Some(teams));
+                if let Some(selected) = selected {
+                    app.teams.select(selected as isize);
+                }
+            }
+            Refreshed::Schedule { league, team, games: Some(games) } if league == app.league => {
+                if let Some(shown) = app.teams.schedule.as_mut().filter(|shown| shown.team.id == team.id) {
+                    let selected = shown.state.selected();
+                    *shown = Schedule::new(team, games);
+                    shown.state.select(selected);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// Read the new scores of the favorite team's games aloud, a missing
+// synthesizer turns announcing off for the session
+fn announce_changes(app: &mut App, game_data: &GameData) {
+    let (Some(team), Some(shown)) = (app.config.favorite_teams.get(app.provider().id()), &app.game_data) else {
+        return;
+    };
+    let Some(announcer) = &app.announcer else {
+        return;
+    };
+    // hidden scores are not read aloud either
+    let shown_games: Vec<Game> = game_data.data.iter().filter(|game| app.spoilers.shows(game)).cloned().collect();
+    for text in announce::score_changes(&shown.data, &shown_games, app.provider().id(), team) {
+        if let Err(e) = announcer.speak(&text) {
+            app.toasts.error(format!("Announcing turned off: {:#}", e));
+            app.announcer = None;
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::tests::nba_app;
+    use crate::config::Config;
+    use crate::notify::Notifier;
+    use chrono::TimeZone;
+    use rust_sports_cli::provider::api::Canned;
+    use rust_sports_cli::provider::nhl::NhlProvider;
+
+    // a one goal or point game with a minute left in the last regulation period
+    fn close(period: u32) -> Game {
+        Game { id: 7, period, status: "In Progress".to_string(), time: Some("1:00".to_string()), home_team_score: 3, visitor_team_score: 2, ..Game::default() }
+    }
+
+    #[test]
+    fn clutch_notifications_are_sent_once_per_league_and_game() {
+        let mut app = nba_app(Arc::new(Canned::new()), Utc.with_ymd_and_hms(2024, 1, 15, 12, 0, 0).unwrap());
+        app.providers.push(Arc::new(NhlProvider));
+        app.config.clutch.notify = true;
+        app.notifier = Notifier::from_config(&Config { notify_command: Some("true".to_string()), ..Config::default() }).unwrap();
+
+        // the same game id in two leagues is two games
+        app.followed.games = vec![(0, close(4)), (1, close(3))];
+        check_clutch(&mut app);
+        let both = HashSet::from([("nba".to_string(), 7), ("nhl".to_string(), 7)]);
+        assert_eq!(app.followed.clutch, both);
+        assert_eq!(app.followed.notified, both);
+
+        // the NHL game is not followed anymore
+        app.followed.games.truncate(1);
+        check_clutch(&mut app);
+        assert_eq!(app.followed.notified, HashSet::from([("nba".to_string(), 7)]));
+        app.followed.games.clear();
+        check_clutch(&mut app);
+        assert!(app.followed.notified.is_empty());
+    }
+}
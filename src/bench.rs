@@ -0,0 +1,57 @@
+use crate::provider::Provider;
+use chrono::{DateTime, Utc};
+use std::time::{Duration, Instant};
+
+// Latencies of the fetches of one provider, failed fetches are counted apart
+pub struct Report {
+    pub league: String,
+    pub latencies: Vec<Duration>,
+    pub failures: usize,
+    pub elapsed: Duration,
+}
+
+impl Report {
+    // Nearest-rank percentile of the successful fetches
+    fn percentile(&self, percent: usize) -> Option<Duration> {
+        let rank = (percent * self.latencies.len()).div_ceil(100).max(1);
+        self.latencies.get(rank - 1).copied()
+    }
+
+    pub fn line(&self) -> String {
+        let millis = |latency: Option<Duration>| latency.map(|latency| format!("{}ms", latency.as_millis())).unwrap_or_else(|| "-".to_string());
+        let fetches = self.latencies.len() + self.failures;
+        let throughput = fetches as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON);
+        format!(
+            "{:<10} {:>8} {:>8} {:>8} {:>8} {:>8.2} {:>4}/{}",
+            self.league,
+            millis(self.percentile(50)),
+            millis(self.percentile(90)),
+            millis(self.percentile(99)),
+            millis(self.latencies.last().copied()),
+            throughput,
+            self.failures,
+            fetches
+        )
+    }
+}
+
+pub fn header() -> String {
+    format!("{:<10} {:>8} {:>8} {:>8} {:>8} {:>8} {:>6}", "league", "p50", "p90", "p99", "max", "req/s", "failed")
+}
+
+// Fetch the day from the provider one request after the other, bypassing the cache.
+// Call conditional::bypass first, revalidated responses are not full fetches.
+pub fn bench(provider: &dyn Provider, day: DateTime<Utc>, requests: usize) -> Report {
+    let mut latencies = Vec::new();
+    let mut failures = 0;
+    let started = Instant::now();
+    for _ in 0..requests {
+        let start = Instant::now();
+        match provider.fetch_day(day) {
+            Some(_) => latencies.push(start.elapsed()),
+            None => failures += 1,
+        }
+    }
+    latencies.sort();
+    Report { league: provider.id().to_string(), latencies, failures, elapsed: started.elapsed() }
+}
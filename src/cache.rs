@@ -0,0 +1,376 @@
+use crate::config::{home_dir, Config};
+use crate::GameData;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+// Removed corrupted files are listed here, inside the cache directory
+const INCIDENT_LOG: &str = "corrupted.log";
+
+// Extension of a compacted season, named after its first and last day
+const SEASON_EXTENSION: &str = "season";
+
+// Days of a compacted season by date, YYYY-MM-DD
+type Season = BTreeMap<String, GameData>;
+
+// A compacted season with the file it was read from
+type LoadedSeason = (PathBuf, Arc<Season>);
+
+// Finished days are immutable, so they are kept on disk between runs
+#[derive(Clone)]
+pub struct Cache {
+    dir: PathBuf,
+    max_size: u64,
+    read_only: bool,
+    // the compacted season read last, views read its days one after the other
+    season: Arc<Mutex<Option<LoadedSeason>>>,
+}
+
+// What compacting a season did
+pub struct Compacted {
+    pub path: PathBuf,
+    pub days: usize,
+    // bytes of the day files before and of the compacted season after
+    pub before: u64,
+    pub after: u64,
+}
+
+// $XDG_CACHE_HOME/rust_sports_cli, falling back to ~/.cache/rust_sports_cli
+pub fn default_cache_dir() -> PathBuf {
+    let base = std::env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home_dir().join(".cache"));
+
+    base.join("rust_sports_cli")
+}
+
+impl Cache {
+    pub fn new(config: &Config) -> Cache {
+        Cache { dir: cache_dir(config), max_size: config.cache_max_size(), read_only: config.cache_read_only, season: Default::default() }
+    }
+
+    fn path(&self, provider: &str, day: DateTime<Utc>) -> PathBuf {
+        self.dir.join(provider).join(format!("{}.json", day.format("%Y-%m-%d")))
+    }
+
+    pub fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    pub fn contains(&self, provider: &str, day: DateTime<Utc>) -> bool {
+        self.path(provider, day).exists() || self.compacted(provider, day).is_some()
+    }
+
+    // Corrupted files are removed so the day is fetched again, the incident is logged
+    pub fn get(&self, provider: &str, day: DateTime<Utc>) -> Option<GameData> {
+        let path = self.path(provider, day);
+        let Ok(text) = std::fs::read_to_string(&path) else {
+            return self.compacted(provider, day);
+        };
+
+        let game_data = match verify(&text) {
+            Ok(json) => serde_json::from_str(json).map_err(|e| format!("unreadable payload: {}", e)),
+            Err(reason) => Err(reason),
+        };
+        let game_data = match game_data {
+            Ok(game_data) => game_data,
+            Err(reason) => {
+                self.repair(&path, &reason);
+                return None;
+            }
+        };
+        // reads of a read-only cache never touch the files, so no locking is needed
+        if self.read_only {
+            return Some(game_data);
+        }
+
+        touch(&path);
+        Some(game_data)
+    }
+
+    // The compacted season of the provider that covers the day, if there is one
+    fn season_path(&self, provider: &str, day: NaiveDate) -> Option<PathBuf> {
+        std::fs::read_dir(self.dir.join(provider)).ok()?.flatten().map(|entry| entry.path()).find(|path| {
+            let range = path.extension().filter(|extension| *extension == SEASON_EXTENSION).and(path.file_stem()).and_then(|stem| stem.to_str());
+            range.and_then(season_range).is_some_and(|(first, last)| (first..=last).contains(&day))
+        })
+    }
+
+    // A day of a compacted season, decompressed once for all its days
+    fn compacted(&self, provider: &str, day: DateTime<Utc>) -> Option<GameData> {
+        let path = self.season_path(provider, day.date_naive())?;
+        let season = {
+            let mut memo = self.season.lock().ok()?;
+            match &*memo {
+                Some((memo_path, season)) if *memo_path == path => season.clone(),
+                _ => {
+                    let season = match read_season(&path) {
+                        Ok(season) => Arc::new(season),
+                        Err(reason) => {
+                            self.repair(&path, &reason);
+                            return None;
+                        }
+                    };
+                    *memo = Some((path.clone(), season.clone()));
+                    season
+                }
+            }
+        };
+        if !self.read_only {
+            touch(&path);
+        }
+        season.get(&day.date_naive().to_string()).cloned()
+    }
+
+    // Move the cached days of a finished season into one compressed file, which
+    // is read like the day files were. Compacting again adds the days fetched since.
+    pub fn compact(&self, provider: &str, first: NaiveDate, last: NaiveDate) -> Result<Compacted> {
+        if self.read_only {
+            bail!("The cache directory {} is read-only, not compacting it", self.dir.display());
+        }
+        if last >= Utc::now().date_naive() {
+            bail!("The season runs until {}, only finished seasons can be compacted", last);
+        }
+
+        let dir = self.dir.join(provider);
+        let path = dir.join(format!("{}_{}.{}", first, last, SEASON_EXTENSION));
+        let mut season = match path.exists() {
+            true => read_season(&path).map_err(|reason| anyhow::anyhow!("Could not read {}: {}", path.display(), reason))?,
+            false => Season::new(),
+        };
+        let before = std::fs::metadata(&path).map(|metadata| metadata.len()).unwrap_or_default();
+
+        let mut files = Vec::new();
+        for day in first.iter_days().take_while(|day| *day <= last) {
+            let day_path = dir.join(format!("{}.json", day));
+            let Ok(text) = std::fs::read_to_string(&day_path) else {
+                continue;
+            };
+            // corrupted days are left to be repaired when they are read
+            if let Some(game_data) = verify(&text).ok().and_then(|json| serde_json::from_str(json).ok()) {
+                season.insert(day.to_string(), game_data);
+                files.push((day_path, text.len() as u64));
+            }
+        }
+        if season.is_empty() {
+            bail!("No cached days between {} and {} to compact", first, last);
+        }
+
+        let json = serde_json::to_vec(&season)?;
+        let mut bytes = checksum(&json).to_le_bytes().to_vec();
+        bytes.extend(crate::lz::compress(&json));
+        let partial = path.with_extension(format!("{}.{}.partial", SEASON_EXTENSION, std::process::id()));
+        std::fs::create_dir_all(&dir).with_context(|| format!("Could not create cache directory {}", dir.display()))?;
+        std::fs::write(&partial, &bytes)
+            .and_then(|()| std::fs::rename(&partial, &path))
+            .with_context(|| format!("Could not write {}", path.display()))?;
+
+        // the days are only removed once the season holding them is written
+        for (day_path, _) in &files {
+            std::fs::remove_file(day_path).with_context(|| format!("Could not remove cache file {}", day_path.display()))?;
+        }
+        if let Ok(mut memo) = self.season.lock() {
+            *memo = None;
+        }
+        let before = before + files.iter().map(|(_, size)| size).sum::<u64>();
+        Ok(Compacted { path, days: season.len(), before, after: bytes.len() as u64 })
+    }
+
+    // Someone with write access has to repair a read-only cache, until then the day is fetched
+    fn repair(&self, path: &Path, reason: &str) {
+        if self.read_only {
+            return;
+        }
+        let _ = std::fs::remove_file(path);
+        let line = format!("{} removed {}: {}\n", Utc::now().to_rfc3339(), path.display(), reason);
+        if let Ok(mut log) = std::fs::File::options().create(true).append(true).open(self.dir.join(INCIDENT_LOG)) {
+            let _ = log.write_all(line.as_bytes());
+        }
+    }
+
+    pub fn put(&self, provider: &str, day: DateTime<Utc>, game_data: &GameData) -> Result<()> {
+        if self.read_only || !is_settled(day, game_data) {
+            return Ok(());
+        }
+
+        let path = self.path(provider, day);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Could not create cache directory {}", parent.display()))?;
+        }
+        // written next to the final file and renamed, a crash never leaves half a day behind
+        // and readers on other machines never see one. The pid keeps writers apart.
+        let json = serde_json::to_string(game_data)?;
+        let partial = path.with_extension(format!("json.{}.partial", std::process::id()));
+        std::fs::write(&partial, format!("{:016x}\n{}", checksum(json.as_bytes()), json))
+            .and_then(|()| std::fs::rename(&partial, &path))
+            .with_context(|| format!("Could not write cache file {}", path.display()))?;
+
+        self.evict()
+    }
+
+    // Drop the least recently used days until the cache fits into max_size. Compacted
+    // seasons do not count, their days are not fetched again once they are gone.
+    fn evict(&self) -> Result<()> {
+        let mut files = Vec::new();
+        collect_files(&self.dir, &mut files)?;
+        files.retain(|(path, _, _)| path.extension().is_none_or(|extension| extension != SEASON_EXTENSION));
+
+        let mut total: u64 = files.iter().map(|(_, size, _)| size).sum();
+        if total <= self.max_size {
+            return Ok(());
+        }
+
+        files.sort_by_key(|(_, _, modified)| *modified);
+        for (path, size, _) in files {
+            if total <= self.max_size {
+                break;
+            }
+            std::fs::remove_file(&path).with_context(|| format!("Could not evict cache file {}", path.display()))?;
+            total -= size;
+        }
+        Ok(())
+    }
+}
+
+// Only past days where every game is final can not change anymore
+pub fn is_settled(day: DateTime<Utc>, game_data: &GameData) -> bool {
+    day.date_naive() < Utc::now().date_naive() && game_data.data.iter().all(|game| game.is_final())
+}
+
+// Bump the modification time so eviction drops the least recently used days
+fn touch(path: &Path) {
+    if let Ok(file) = std::fs::File::options().write(true).open(path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+}
+
+// First and last day of a compacted season from its name, e.g. 2023-10-01_2024-06-30
+fn season_range(stem: &str) -> Option<(NaiveDate, NaiveDate)> {
+    let (first, last) = stem.split_once('_')?;
+    Some((first.parse().ok()?, last.parse().ok()?))
+}
+
+// A compacted season is the checksum of its JSON followed by the compressed JSON
+fn read_season(path: &Path) -> Result<Season, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("unreadable file: {}", e))?;
+    let (sum, compressed) = bytes.split_first_chunk::<8>().ok_or("missing checksum")?;
+    let json = crate::lz::decompress(compressed).ok_or("corrupted compression")?;
+    if u64::from_le_bytes(*sum) != checksum(&json) {
+        return Err("checksum mismatch".to_string());
+    }
+    serde_json::from_slice(&json).map_err(|e| format!("unreadable payload: {}", e))
+}
+
+// FNV-1a, enough to notice truncated or garbled files
+fn checksum(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x100000001b3))
+}
+
+// The payload of a cache file if its checksum line matches. Files written before
+// checksums were added start with the payload and are only checked by parsing.
+fn verify(text: &str) -> Result<&str, String> {
+    if text.starts_with('{') {
+        return Ok(text);
+    }
+    let (sum, json) = text.split_once('\n').ok_or("missing checksum")?;
+    let sum = u64::from_str_radix(sum, 16).map_err(|_| "malformed checksum")?;
+    if sum != checksum(json.as_bytes()) {
+        return Err("checksum mismatch".to_string());
+    }
+    Ok(json)
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<(PathBuf, u64, SystemTime)>) -> Result<()> {
+    if !dir.exists() {
+        return Ok(());
+    }
+
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            collect_files(&entry.path(), files)?;
+        } else {
+            files.push((entry.path(), metadata.len(), metadata.modified()?));
+        }
+    }
+    Ok(())
+}
+
+// The configured cache directory, the XDG one by default
+pub fn cache_dir(config: &Config) -> PathBuf {
+    config.cache_dir.clone().unwrap_or_else(default_cache_dir)
+}
+
+// Remove every cached day, returns the directory that was cleared
+pub fn clear(config: &Config) -> Result<PathBuf> {
+    let dir = cache_dir(config);
+    if config.cache_read_only {
+        bail!("The cache directory {} is read-only, not clearing it", dir.display());
+    }
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir).with_context(|| format!("Could not remove cache directory {}", dir.display()))?;
+    }
+    Ok(dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Game;
+    use chrono::TimeZone;
+
+    fn cache(name: &str, max_size_mb: u64) -> (Cache, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("rust_sports_cli-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let config = Config { cache_dir: Some(dir.clone()), cache_max_size_mb: Some(max_size_mb), ..Config::default() };
+        (Cache::new(&config), dir)
+    }
+
+    fn day(date: &str) -> DateTime<Utc> {
+        let date: NaiveDate = date.parse().unwrap();
+        Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+    }
+
+    fn finished(id: u32) -> GameData {
+        GameData::from_games(vec![Game { id, status: "Final".to_string(), home_team_score: id, ..Game::default() }])
+    }
+
+    #[test]
+    fn compacted_days_are_read_from_the_season() {
+        let (cache, dir) = cache("compact", 100);
+        for (id, date) in [(1, "2023-10-24"), (2, "2023-10-25"), (3, "2023-10-27")] {
+            cache.put("nba", day(date), &finished(id)).unwrap();
+        }
+        let first = "2023-10-01".parse().unwrap();
+        let last = "2024-06-30".parse().unwrap();
+        let compacted = cache.compact("nba", first, last).unwrap();
+
+        assert_eq!(compacted.days, 3);
+        assert!(!dir.join("nba").join("2023-10-24.json").exists());
+        assert_eq!(cache.get("nba", day("2023-10-25")).unwrap().data[0].id, 2);
+        assert!(cache.get("nba", day("2023-10-26")).is_none());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn eviction_keeps_compacted_seasons() {
+        let (cache, dir) = cache("evict", 100);
+        cache.put("nba", day("2023-10-24"), &finished(1)).unwrap();
+        let compacted = cache.compact("nba", "2023-10-01".parse().unwrap(), "2024-06-30".parse().unwrap()).unwrap();
+
+        // nothing fits, every day file goes
+        let small = Cache { max_size: 0, ..cache.clone() };
+        small.put("nba", day("2024-10-22"), &finished(2)).unwrap();
+        assert!(!dir.join("nba").join("2024-10-22.json").exists());
+        assert!(compacted.path.exists());
+        assert_eq!(small.get("nba", day("2023-10-24")).unwrap().data[0].id, 1);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
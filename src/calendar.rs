@@ -0,0 +1,137 @@
+use crate::{ids, Game};
+use crate::theme::Theme;
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    prelude::{Frame, Line, Rect, Span},
+    style::{Modifier, Style},
+    widgets::{Clear, Paragraph},
+};
+use std::collections::HashMap;
+
+// Month calendar for picking the day of the scoreboard, opened over the games
+pub struct Calendar {
+    pub cursor: NaiveDate,
+    // games per day of the loaded month, and whether the favorite team plays
+    days: HashMap<NaiveDate, (usize, bool)>,
+    loaded: Option<(i32, u32)>,
+    // month whose games were asked for last, to not ask twice
+    requested: Option<(i32, u32)>,
+}
+
+// What the calendar wants the app to do after a key press
+pub enum CalendarOutcome {
+    Pending,
+    Jump(NaiveDate),
+    Close,
+}
+
+impl Calendar {
+    pub fn new(cursor: NaiveDate) -> Calendar {
+        Calendar { cursor, days: HashMap::new(), loaded: None, requested: None }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> CalendarOutcome {
+        match key.code {
+            KeyCode::Left => self.cursor -= Duration::days(1),
+            KeyCode::Right => self.cursor += Duration::days(1),
+            KeyCode::Up => self.cursor -= Duration::days(7),
+            KeyCode::Down => self.cursor += Duration::days(7),
+            KeyCode::PageUp => self.cursor = self.cursor - Months::new(1),
+            KeyCode::PageDown => self.cursor = self.cursor + Months::new(1),
+            KeyCode::Enter => return CalendarOutcome::Jump(self.cursor),
+            KeyCode::Esc => return CalendarOutcome::Close,
+            _ => {}
+        }
+        CalendarOutcome::Pending
+    }
+
+    pub fn month(&self) -> (i32, u32) {
+        (self.cursor.year(), self.cursor.month())
+    }
+
+    // First and last day of the month under the cursor
+    pub fn month_range(&self) -> (NaiveDate, NaiveDate) {
+        let first = self.cursor.with_day(1).unwrap_or(self.cursor);
+        let last = first + Months::new(1) - Duration::days(1);
+        (first, last)
+    }
+
+    // The month under the cursor if its games still have to be fetched
+    pub fn request(&mut self) -> Option<(i32, u32)> {
+        let month = self.month();
+        if self.requested == Some(month) {
+            return None;
+        }
+        self.requested = Some(month);
+        Some(month)
+    }
+
+    // Count the games of a fetched month, results for another month are ignored
+    pub fn set_games(&mut self, month: (i32, u32), games: &[Game], league: &str, favorite: Option<&str>) {
+        if month != self.month() {
+            return;
+        }
+        self.days.clear();
+        for game in games {
+            let Ok(date) = NaiveDate::parse_from_str(&game.date.chars().take(10).collect::<String>(), "%Y-%m-%d") else {
+                continue;
+            };
+            let plays = favorite.is_some_and(|team| ids::plays(league, team, game));
+            let day = self.days.entry(date).or_default();
+            day.0 += 1;
+            day.1 |= plays;
+        }
+        self.loaded = Some(month);
+    }
+}
+
+const DAY_WIDTH: u16 = 4;
+
+pub fn ui(calendar: &Calendar, theme: &Theme, f: &mut Frame, area: Rect) {
+    let width = (DAY_WIDTH * 7 + 2).min(area.width);
+    let height = 12.min(area.height);
+    let spot = Rect { x: area.x + (area.width - width) / 2, y: area.y + (area.height - height) / 2, width, height };
+
+    let (first, last) = calendar.month_range();
+    let loaded = calendar.loaded == Some(calendar.month());
+
+    let mut lines = vec![Line::from(
+        ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"].map(|day| Span::styled(format!("{:>3} ", day), theme.header)).to_vec(),
+    )];
+
+    // weeks start on Monday, leading blanks up to the first of the month
+    let mut week: Vec<Span> = vec![Span::raw(" ".repeat(DAY_WIDTH as usize)); first.weekday().num_days_from_monday() as usize];
+    let mut day = first;
+    while day <= last {
+        let (games, favorite) = calendar.days.get(&day).copied().unwrap_or_default();
+        // favorite team days are marked apart, not only colored
+        let marker = if favorite { "*" } else if games > 0 && theme.plain { "+" } else if games > 0 { "•" } else { " " };
+        let mut style = Style::default();
+        if games > 0 {
+            style = style.add_modifier(Modifier::BOLD);
+        }
+        if favorite {
+            style = style.patch(theme.accent);
+        }
+        if day == calendar.cursor {
+            style = style.patch(theme.selected);
+        }
+        week.push(Span::styled(format!("{:>3}{}", day.day(), marker), style));
+
+        if week.len() == 7 {
+            lines.push(Line::from(std::mem::take(&mut week)));
+        }
+        day += Duration::days(1);
+    }
+    if !week.is_empty() {
+        lines.push(Line::from(week));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(if loaded { "• games, pgup|pgdn: month" } else { "loading games..." }));
+
+    let title = calendar.cursor.format("%B %Y").to_string();
+    f.render_widget(Clear, spot);
+    f.render_widget(Paragraph::new(lines).block(theme.block(title)), spot);
+}
@@ -0,0 +1,346 @@
+use crate::archive::Archive;
+use crate::config::Config;
+use crate::export::ExportFormat;
+use crate::report::OutputFormat;
+use crate::{bench, cache, export, history, ical, ids, provider, report, schema, standings, store, sync, Game};
+use anyhow::{Context, Result};
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveTime, Utc};
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(name = "sports", about = "NBA scores in your terminal")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// Pin the fields of exports and schemas to an output version, the latest by default
+    #[arg(long, global = true)]
+    pub output_version: Option<u32>,
+    /// Plain text for screen readers: no box drawing, spinners, graphics or colors
+    #[arg(long)]
+    pub accessible: bool,
+    /// Browse only this season, named after the year it starts in, e.g. 1996 for 1996-97
+    #[arg(long)]
+    pub season: Option<i32>,
+    /// Fetch nothing, show the days in the cache and the archive
+    #[arg(long, global = true)]
+    pub offline: bool,
+    /// Write every API response to a fixture file in this directory
+    #[arg(long, global = true, value_name = "DIR", conflicts_with_all = ["replay", "offline"])]
+    pub record: Option<PathBuf>,
+    /// Answer API requests from the fixtures recorded in this directory, never the network
+    #[arg(long, global = true, value_name = "DIR", conflicts_with = "offline")]
+    pub replay: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Enter and validate the balldontlie API key
+    Setup,
+    /// Manage the on-disk cache of fetched days
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommand,
+    },
+    /// Export the games between two dates, or a season from the archive, one row per game
+    Export {
+        /// League to export, e.g. nba, nhl, mlb or soccer
+        #[arg(long, default_value = "nba")]
+        league: String,
+        /// First day to export, YYYY-MM-DD
+        #[arg(long, required_unless_present = "season")]
+        from: Option<NaiveDate>,
+        /// Last day to export, YYYY-MM-DD
+        #[arg(long, required_unless_present = "season")]
+        to: Option<NaiveDate>,
+        /// Season in the archive to export instead, named after the year it starts in
+        #[arg(long, conflicts_with_all = ["from", "to", "resume"])]
+        season: Option<i32>,
+        /// Format of the output file
+        #[arg(long, value_enum, default_value_t)]
+        format: ExportFormat,
+        /// CSV file to write
+        #[arg(short, long)]
+        output: PathBuf,
+        /// Continue an interrupted export, skipping the days already written
+        #[arg(long)]
+        resume: bool,
+    },
+    /// Write the upcoming games of teams as an iCalendar file to import into a calendar
+    Ical {
+        /// League of the teams, e.g. nba, nhl, mlb or soccer
+        #[arg(long, default_value = "nba")]
+        league: String,
+        /// Abbreviation of a team, repeat for several, the favorite team by default
+        #[arg(long)]
+        team: Vec<String>,
+        /// Days ahead to include
+        #[arg(long, default_value_t = 30)]
+        days: u32,
+        /// .ics file to write
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+    /// Print the standings of a league as the standings view shows them
+    Standings {
+        /// League of the standings, e.g. nba, nhl, mlb or soccer
+        #[arg(long, default_value = "nba")]
+        league: String,
+        /// Standings as of this day, YYYY-MM-DD, today by default
+        #[arg(long)]
+        date: Option<NaiveDate>,
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
+    /// Print the top performers of every started game of a day, as the box scores show them
+    Leaders {
+        /// League of the games, e.g. nba, nhl, mlb or soccer
+        #[arg(long, default_value = "nba")]
+        league: String,
+        /// Day of the games, YYYY-MM-DD, today by default
+        #[arg(long)]
+        date: Option<NaiveDate>,
+        /// Only the game with this id
+        #[arg(long)]
+        game: Option<u32>,
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
+    /// Print the versioned JSON Schema of an output format
+    Schema {
+        #[arg(value_enum)]
+        format: schema::Format,
+    },
+    /// List the recorded export and cache commands, numbered for rerun
+    History,
+    /// Run a command from the history again
+    Rerun {
+        /// Number of the command as listed by history
+        n: usize,
+    },
+    /// Download every game of a season into the archive
+    Sync {
+        /// League to sync, e.g. nba, nhl, mlb or soccer
+        #[arg(long, default_value = "nba")]
+        league: String,
+        /// Season named after the year it starts in, e.g. 2023 for 2023-24
+        #[arg(long)]
+        season: i32,
+        /// Milliseconds between requests, raise it for APIs with strict rate limits
+        #[arg(long, default_value_t = 1000)]
+        pause_ms: u64,
+    },
+    /// List the ids and abbreviations the providers use for the same teams and players
+    Ids {
+        /// Only this league, e.g. nba, nhl, mlb or soccer
+        #[arg(long)]
+        league: Option<String>,
+        /// List players instead of teams
+        #[arg(long)]
+        players: bool,
+    },
+    /// Measure how fast each configured league answers from this network
+    BenchProvider {
+        /// Only this league, e.g. nba, nhl, mlb or soccer
+        #[arg(long)]
+        league: Option<String>,
+        /// Fetches of today's games per league
+        #[arg(short = 'n', long, default_value_t = 10)]
+        requests: usize,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CacheCommand {
+    /// Remove every cached day
+    Clear,
+    /// Pack the cached days of a finished season into one compressed file
+    Compact {
+        /// League of the season, e.g. nba, nhl, mlb or soccer
+        #[arg(long, default_value = "nba")]
+        league: String,
+        /// Season named after the year it starts in, e.g. 2023 for 2023-24
+        #[arg(long)]
+        season: i32,
+    },
+}
+
+// Standings ranked from the archived games of the season up to the day, where
+// the league's API has none for it
+fn archived_standings(config: &Config, provider: &dyn provider::Provider, date: NaiveDate) -> Option<standings::Standings> {
+    let archive = Archive::open(config).ok()??;
+    let in_season = |season: &i32| provider.season_range(*season).is_some_and(|(start, end)| (start..=end).contains(&date));
+    let season = [date.year(), date.year() - 1].into_iter().find(in_season)?;
+    let (start, _) = provider.season_range(season)?;
+    let games = archive.range(&provider.cache_key(), start, date);
+    games.iter().any(Game::is_final).then(|| standings::compute(provider.ranking_rules(), &games))
+}
+
+// Runs a subcommand that does not need the TUI, false if the command needs it
+pub fn run_command(cli: &Cli, config: &Config) -> Result<bool> {
+    let Some(command) = &cli.command else {
+        return Ok(false);
+    };
+    match command {
+        Command::Cache { command: CacheCommand::Clear } => {
+            let dir = cache::clear(config)?;
+            println!("Cleared cache directory {}", dir.display());
+        }
+        Command::Cache { command: CacheCommand::Compact { league, season } } => {
+            if config.store != store::Backend::Files {
+                anyhow::bail!("Only the files store can be compacted");
+            }
+            let providers = provider::build_providers(config);
+            let provider = providers
+                .iter()
+                .find(|provider| provider.id() == league)
+                .ok_or_else(|| anyhow::anyhow!("Unknown league {}", league))?;
+            let (first, last) = provider.season_range(*season).ok_or_else(|| anyhow::anyhow!("{} has no seasons", provider.name()))?;
+            let compacted = cache::Cache::new(config).compact(&provider.cache_key(), first, last)?;
+            println!(
+                "Compacted {} days of {} {} into {}, {} kB instead of {} kB",
+                compacted.days,
+                provider.name(),
+                season,
+                compacted.path.display(),
+                compacted.after.div_ceil(1024),
+                compacted.before.div_ceil(1024)
+            );
+        }
+        Command::Export { league, from, to, season, format: ExportFormat::Csv, output, resume } => {
+            let providers = provider::build_providers(config);
+            let provider = providers
+                .iter()
+                .find(|provider| provider.id() == league)
+                .ok_or_else(|| anyhow::anyhow!("Unknown league {}", league))?;
+            let version = schema::output_version(cli.output_version)?;
+            match (season, from, to) {
+                (Some(season), _, _) => {
+                    let archive = Archive::open(config)?.ok_or_else(|| anyhow::anyhow!("The archive is off, turn it on with archive = true in the config"))?;
+                    let games = export::export_season(provider.as_ref(), &archive, *season, output, version)?;
+                    println!("Exported {} games to {}", games, output.display());
+                }
+                (None, Some(from), Some(to)) => {
+                    let cache = store::open(config)?;
+                    let days = export::export_csv(provider.as_ref(), cache.as_ref(), *from, *to, output, *resume, version, |date, games| {
+                        eprintln!("{} {} games", date, games);
+                    })?;
+                    println!("Exported {} days to {}", days, output.display());
+                }
+                _ => anyhow::bail!("Export needs --from and --to, or --season"),
+            }
+        }
+        Command::Ical { league, team, days, output } => {
+            let providers = provider::build_providers(config);
+            let provider = providers
+                .iter()
+                .find(|provider| provider.id() == league)
+                .ok_or_else(|| anyhow::anyhow!("Unknown league {}", league))?;
+            let teams: Vec<&String> = match team.is_empty() {
+                true => config.favorite_teams.get(provider.id()).into_iter().collect(),
+                false => team.iter().collect(),
+            };
+            if teams.is_empty() {
+                anyhow::bail!("No teams given and no favorite {} team configured, pass --team", provider.name());
+            }
+            let today = Local::now().date_naive();
+            let games = provider
+                .fetch_range(today, today + Duration::days(*days as i64))
+                .ok_or_else(|| anyhow::anyhow!("Could not fetch the {} schedule", provider.name()))?;
+            let plays = |game: &Game| teams.iter().any(|team| ids::plays(provider.id(), team, game));
+            let upcoming: Vec<Game> = games.into_iter().filter(|game| game.period == 0 && !game.is_final() && plays(game)).collect();
+            std::fs::write(output, ical::calendar(provider.name(), &provider.cache_key(), &upcoming))
+                .with_context(|| format!("Could not write {}", output.display()))?;
+            println!("Wrote {} games to {}", upcoming.len(), output.display());
+        }
+        Command::Ids { league, players } => {
+            let ids = ids::load();
+            let table = if *players { &ids.players } else { &ids.teams };
+            for (id, entities) in table.iter().filter(|(id, _)| league.as_ref().is_none_or(|league| league == *id)) {
+                for entity in entities.values() {
+                    let abbreviations: Vec<&str> = entity.abbreviations.iter().map(String::as_str).collect();
+                    let numbers: Vec<String> = entity.ids.iter().map(u32::to_string).collect();
+                    println!("{:<8} {:<32} {:<16} {}", id, entity.name, abbreviations.join(", "), numbers.join(", "));
+                }
+            }
+            if table.is_empty() {
+                println!("Nothing learned yet, ids are recorded as games are fetched, see {}", ids::ids_path().display());
+            }
+        }
+        Command::Standings { league, date, format } => {
+            let providers = provider::build_providers(config);
+            let provider = providers
+                .iter()
+                .find(|provider| provider.id() == league)
+                .ok_or_else(|| anyhow::anyhow!("Unknown league {}", league))?;
+            let day = date.map_or_else(Utc::now, |date| date.and_time(NaiveTime::MIN).and_utc());
+            let standings = provider.fetch_standings(day).or_else(|| archived_standings(config, provider.as_ref(), day.date_naive()));
+            let standings = standings.ok_or_else(|| anyhow::anyhow!("Could not fetch the {} standings", provider.name()))?;
+            print!("{}", report::standings(&standings, *format)?);
+        }
+        Command::Leaders { league, date, game, format } => {
+            let providers = provider::build_providers(config);
+            let provider = providers
+                .iter()
+                .find(|provider| provider.id() == league)
+                .ok_or_else(|| anyhow::anyhow!("Unknown league {}", league))?;
+            let day = date.map_or_else(Utc::now, |date| date.and_time(NaiveTime::MIN).and_utc());
+            let games = provider.fetch_day(day).ok_or_else(|| anyhow::anyhow!("Could not fetch the {} games", provider.name()))?.data;
+            let started = games.into_iter().filter(|other| game.is_none_or(|id| other.id == id) && (other.is_live() || other.is_final()));
+            let mut leaders = Vec::new();
+            for game in started {
+                let details = provider.fetch_details(&game);
+                leaders.extend(details.into_iter().flat_map(|details| details.leaders).map(|leader| (game.clone(), leader)));
+            }
+            print!("{}", report::leaders(&leaders, *format)?);
+        }
+        Command::Schema { format } => {
+            let version = schema::output_version(cli.output_version)?;
+            println!("{}", serde_json::to_string_pretty(&schema::schema(*format, version))?);
+        }
+        Command::History => {
+            for (index, entry) in history::load().iter().enumerate() {
+                println!("{:>4}  {}  {}", index + 1, entry.at, entry.command_line());
+            }
+        }
+        Command::Rerun { n } => {
+            let entry = history::get(*n)?;
+            eprintln!("sports {}", entry.command_line());
+            let cli = Cli::try_parse_from(std::iter::once("sports".to_string()).chain(entry.args))?;
+            if cli.command.is_none() {
+                anyhow::bail!("History entry {} has no command", n);
+            }
+            return run_command(&cli, config);
+        }
+        Command::Sync { league, season, pause_ms } => {
+            let providers = provider::build_providers(config);
+            let provider = providers
+                .iter()
+                .find(|provider| provider.id() == league)
+                .ok_or_else(|| anyhow::anyhow!("Unknown league {}", league))?;
+            let archive = Archive::open(config)?.ok_or_else(|| anyhow::anyhow!("The archive is off, turn it on with archive = true in the config"))?;
+            let synced = sync::sync(provider.as_ref(), &archive, *season, std::time::Duration::from_millis(*pause_ms), |done, total, games| {
+                eprint!("\r{}", sync::progress_bar(done, total, games));
+            });
+            // the progress bar keeps its line, errors go below it
+            eprintln!();
+            let games = synced?;
+            println!("Archived {} games of the {} {} season", games, provider.name(), season);
+        }
+        Command::BenchProvider { league, requests } => {
+            let providers = provider::build_providers(config);
+            let providers: Vec<_> = providers.iter().filter(|provider| league.as_deref().is_none_or(|league| provider.id() == league)).collect();
+            if providers.is_empty() {
+                anyhow::bail!("Unknown league {}", league.as_deref().unwrap_or_default());
+            }
+            // full fetches every time, not 304s answered from the last parse
+            provider::conditional::bypass();
+            println!("{}", bench::header());
+            for provider in providers {
+                println!("{}", bench::bench(provider.as_ref(), Utc::now(), *requests).line());
+            }
+        }
+        Command::Setup => return Ok(false),
+    }
+    Ok(true)
+}
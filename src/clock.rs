@@ -0,0 +1,101 @@
+use crate::Game;
+use chrono::{DateTime, Local, NaiveDateTime};
+
+// Game state for period based sports, e.g. "Q3 5:32", "OT1 0:45", "Half", "Final"
+pub fn period_clock(game: &Game) -> String {
+    if game.is_final() {
+        return "Final".to_string();
+    }
+    if !game.is_live() {
+        return scheduled(game);
+    }
+    if game.status.contains("Half") {
+        return "Half".to_string();
+    }
+
+    let period = match game.period {
+        1..=4 => format!("Q{}", game.period),
+        overtime => format!("OT{}", overtime - 4),
+    };
+    match game.time.as_deref().map(str::trim).filter(|time| !time.is_empty()) {
+        Some(time) => format!("{} {}", period, time),
+        None => period,
+    }
+}
+
+// Soccer game state, e.g. "67'", "45'+3", "HT", "FT"
+pub fn soccer_clock(game: &Game) -> String {
+    if game.is_final() {
+        return "FT".to_string();
+    }
+    if !game.is_live() {
+        return scheduled(game);
+    }
+    if game.status == "HT" || game.status.contains("Half") {
+        return "HT".to_string();
+    }
+
+    let clock = game.time.as_deref().unwrap_or_default().trim();
+    // Providers send either minutes with added time ("45'+3'") or a running clock ("67:12")
+    if let Some((minute, _)) = clock.split_once(':') {
+        let minute: u32 = minute.parse().unwrap_or_default();
+        return format!("{}'", minute + 1);
+    }
+    match clock.split_once('+') {
+        Some((minute, added)) => format!("{}'+{}", minute.trim_end_matches('\''), added.trim_end_matches('\'')),
+        None => format!("{}'", clock.trim_end_matches('\'')),
+    }
+}
+
+// Live games between periods, at halftime or in an intermission, as far as their
+// status tells, e.g. "Halftime", "HT", "End of 3rd" or "Mid 5th"
+pub fn in_break(game: &Game) -> bool {
+    let status = game.status.trim();
+    game.is_live()
+        && (status == "HT"
+            || status.contains("Half")
+            || status.contains("Intermission")
+            || ["End", "Mid"].iter().any(|prefix| status.starts_with(prefix)))
+}
+
+// Seconds on the running clock from the last regulation period on, None before
+// that or when the clock is not running, e.g. at halftime or in a shootout
+pub fn seconds_left(game: &Game, regulation: u32) -> Option<u32> {
+    if !game.is_live() || game.period < regulation {
+        return None;
+    }
+    let time = game.time.as_deref()?.split_whitespace().last()?;
+    let (minutes, seconds) = time.split_once(':')?;
+    Some(minutes.parse::<u32>().ok()? * 60 + seconds.parse::<f32>().ok()? as u32)
+}
+
+// Header of a linescore column, regulation periods are numbered and the
+// ones after them are overtimes, e.g. "4", "OT", "2OT"
+pub fn period_label(index: usize, regulation: usize) -> String {
+    match index.checked_sub(regulation) {
+        None => (index + 1).to_string(),
+        Some(0) => "OT".to_string(),
+        Some(overtime) => format!("{}OT", overtime + 1),
+    }
+}
+
+// Local kick-off/tip-off time, from the start timestamp or the status of scheduled games
+pub fn start_time(game: &Game) -> Option<String> {
+    starts_at(game).map(|time| time.format("%H:%M").to_string())
+}
+
+pub fn starts_at(game: &Game) -> Option<DateTime<Local>> {
+    game.datetime.iter().chain(std::iter::once(&game.status)).find_map(|timestamp| parse_timestamp(timestamp))
+}
+
+fn parse_timestamp(timestamp: &str) -> Option<DateTime<Local>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|time| time.with_timezone(&Local))
+        .or_else(|_| NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%dT%H:%MZ").map(|time| time.and_utc().with_timezone(&Local)))
+        .ok()
+}
+
+// Start time of a scheduled game, the API status when there is no timestamp
+fn scheduled(game: &Game) -> String {
+    start_time(game).unwrap_or_else(|| game.status.trim().to_string())
+}
@@ -0,0 +1,90 @@
+use unicode_width::UnicodeWidthStr;
+
+// A table column sized from what it shows
+pub struct Column {
+    // never narrower than this, usually the header
+    pub min: u16,
+    // the widest cell
+    pub content: u16,
+    // columns with a lower priority get their content width first
+    pub priority: u8,
+    // takes a share of the space left once every column fits its content
+    pub fill: bool,
+}
+
+impl Column {
+    pub fn new(header: &str, cells: impl IntoIterator<Item = u16>, priority: u8) -> Column {
+        let min = width(header);
+        let content = cells.into_iter().max().unwrap_or_default().max(min);
+        Column { min, content, priority, fill: false }
+    }
+
+    pub fn fill(self) -> Column {
+        Column { fill: true, ..self }
+    }
+}
+
+// Terminal cells the text takes, wide characters count twice
+pub fn width(text: &str) -> u16 {
+    text.width().try_into().unwrap_or(u16::MAX)
+}
+
+// Widths of the columns in the available cells. Every column starts at its minimum,
+// then grows towards its content by priority, columns of the same priority a cell at
+// a time so they stay even. What is left goes to the fill columns.
+pub fn widths(columns: &[Column], available: u16, spacing: u16) -> Vec<u16> {
+    let mut widths: Vec<u16> = columns.iter().map(|column| column.min).collect();
+    let used = widths.iter().sum::<u16>() + spacing * columns.len().saturating_sub(1) as u16;
+    let mut left = available.saturating_sub(used);
+
+    let mut priorities: Vec<u8> = columns.iter().map(|column| column.priority).collect();
+    priorities.sort_unstable();
+    priorities.dedup();
+    for priority in priorities {
+        let group: Vec<usize> = (0..columns.len()).filter(|index| columns[*index].priority == priority).collect();
+        while left > 0 {
+            let growing: Vec<usize> = group.iter().copied().filter(|index| widths[*index] < columns[*index].content).collect();
+            if growing.is_empty() {
+                break;
+            }
+            for index in growing.into_iter().take(left as usize) {
+                widths[index] += 1;
+                left -= 1;
+            }
+        }
+    }
+
+    let fills: Vec<usize> = (0..columns.len()).filter(|index| columns[*index].fill).collect();
+    let count = fills.len() as u16;
+    if let Some(share) = left.checked_div(count) {
+        let mut extra = left % count;
+        for index in fills {
+            widths[index] += share + u16::from(extra > 0);
+            extra = extra.saturating_sub(1);
+        }
+    }
+    widths
+}
+
+// The text cut to the width with an ellipsis at the end, unchanged if it fits
+pub fn truncate(text: &str, max: u16) -> String {
+    if width(text) <= max {
+        return text.to_string();
+    }
+    let mut truncated = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let cell = width(c.encode_utf8(&mut [0; 4]));
+        if used + cell + 1 > max {
+            break;
+        }
+        truncated.push(c);
+        used += cell;
+    }
+    // no space in front of the ellipsis
+    truncated.truncate(truncated.trim_end().len());
+    if max > 0 {
+        truncated.push('…');
+    }
+    truncated
+}
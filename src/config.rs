@@ -0,0 +1,270 @@
+use anyhow::{Context, Result};
+use crate::alert::Rule;
+use crate::archive::ArchiveBackend;
+use crate::decorator::RowDecorator;
+use crate::grouping::Grouping;
+use crate::store::Backend;
+use crate::notify::{Channel, QuietHours, Route};
+use crate::theme::ThemeSpec;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+// User configuration, stored as TOML in the XDG config directory
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct Config {
+    pub api_key: Option<String>,
+    // Upper bound for the on-disk day cache, oldest entries are evicted first
+    pub cache_max_size_mb: Option<u64>,
+    // Cache directory to use instead of the XDG one, e.g. a share backfilled for a whole team
+    pub cache_dir: Option<PathBuf>,
+    // Only read the cache, nothing is written, evicted or repaired. For shared directories.
+    pub cache_read_only: bool,
+    // Where fetched days are kept: files in the cache directory, memory for this run only,
+    // a Redis server or an SQLite file
+    pub store: Backend,
+    // Server of the redis store, redis://[[user]:password@]host[:port][/db], local by default
+    pub redis_url: Option<String>,
+    // Keep every fetched game in the data directory, finished seasons can then be
+    // browsed and exported without the API
+    pub archive: bool,
+    // Where the archive keeps the games: JSON files by month or an SQLite database
+    pub archive_backend: ArchiveBackend,
+    // Earlier seasons of the archive the head-to-head view goes back, besides the current one
+    pub head_to_head_seasons: u32,
+    // Set by --offline: nothing is fetched, days come from the cache and the archive
+    #[serde(skip)]
+    pub offline: bool,
+    pub soccer: SoccerConfig,
+    // Show the Olympics provider, unset means only during the Games
+    pub olympics: Option<bool>,
+    // Extra lines below the scoreboard rows per league id, replacing the league's own
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub row_decorators: HashMap<String, Vec<RowDecorator>>,
+    // Abbreviation of the favorite team per league id, highlighted in the calendar
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub favorite_teams: HashMap<String, String>,
+    // Headers the scoreboard starts with: none, conference, division, start_time or status
+    pub group_games: Grouping,
+    // Start with the scores of started games hidden, for watching replays
+    pub spoiler_free: bool,
+    // Seconds between refreshes of today's scoreboard while games are live, 0 turns it off
+    pub auto_refresh_secs: Option<u64>,
+    // Refreshes wait this many times longer while every live game is in a break,
+    // e.g. at halftime or between periods, 1 keeps the interval
+    pub poll_break_factor: Option<u32>,
+    // Up to this percentage of the interval is randomly added or taken off every
+    // refresh, so instances started together do not poll at the same moment. At most 50.
+    pub poll_jitter_percent: Option<u32>,
+    // Seed of the jitter, the same seed draws the same intervals, e.g. to reproduce
+    // a session replayed with --replay. Drawn from the clock when unset.
+    pub poll_seed: Option<u64>,
+    // Team logos next to matchups on kitty protocol terminals (needs the `logos`
+    // feature), colored abbreviations everywhere else
+    pub team_logos: bool,
+    // Clickable links of the links row decorator, unset turns them on in terminals
+    // known to support OSC 8 hyperlinks, the others show the game's address
+    pub hyperlinks: Option<bool>,
+    // Speak score changes of the favorite teams' games when they are refreshed
+    pub announce: bool,
+    // Text-to-speech program to run instead of `say` on macOS or `espeak` elsewhere
+    pub announce_command: Option<String>,
+    // Notification program to run instead of `osascript` on macOS or `notify-send`
+    // elsewhere, it gets the title and the body as arguments
+    pub notify_command: Option<String>,
+    // Notify at the end of every period of the favorite teams' games with its score
+    pub notify_period_ends: bool,
+    // How finished games of the favorite teams are notified: per_game, digest or off
+    pub notify_finals: NotifyStrategy,
+    // League ids whose favorite team's games notify the desktop when they go to
+    // overtime or are final, e.g. ["nba", "nhl"], besides notify_finals and the routes
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub notify_desktop: Vec<String>,
+    // Channels by name, e.g. [notify_channels.phone] with type = "telegram", bot_token
+    // and chat_id, or type = "webhook" and url. "desktop" is always there.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub notify_channels: HashMap<String, Channel>,
+    // [[notify_routes]] with events (period_end, overtime, final, digest, clutch, alert), teams and
+    // channels, notifications only go to the desktop without any
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub notify_routes: Vec<Route>,
+    // [[alerts]] with a name and a condition on every fetched game, e.g. when =
+    // "margin <= 3 && period >= 4 && team in favorites", notified as the alert event
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub alerts: Vec<Rule>,
+    // Local times between which notifications are held back and sent as one digest
+    // after, e.g. start = "23:00" and end = "07:00"
+    pub quiet_hours: Option<QuietHours>,
+    // File kept up to date with the scores of the followed games for stream overlays,
+    // TOML if it ends in .toml and JSON otherwise
+    pub watch_party_file: Option<PathBuf>,
+    pub clutch: ClutchConfig,
+    // Name of the theme, built-in (default, light, mono, nord,
+    // deuteranopia, protanopia, high_contrast) or one of the themes below
+    pub theme: Option<String>,
+    // Plain text for screen readers, also turned on with --accessible
+    pub accessible: bool,
+    // Custom themes by name, e.g. [themes.mine] with base = "nord" and live = "bold green"
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub themes: HashMap<String, ThemeSpec>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyStrategy {
+    // one notification per finished game
+    PerGame,
+    // a single summary once the last game of the day is final
+    Digest,
+    #[default]
+    Off,
+}
+
+// Escalation when a followed game is close in its final minutes
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ClutchConfig {
+    pub enabled: bool,
+    // Seconds left in the last regulation period or an overtime
+    pub seconds_left: u32,
+    // Largest margin that is still close per league id, the league's own otherwise
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub margins: HashMap<String, u32>,
+    // Seconds between refreshes of the followed games meanwhile
+    pub refresh_secs: u64,
+    // Send a desktop notification when a game becomes close
+    pub notify: bool,
+}
+
+impl Default for ClutchConfig {
+    fn default() -> Self {
+        ClutchConfig { enabled: true, seconds_left: 120, margins: HashMap::new(), refresh_secs: 10, notify: false }
+    }
+}
+
+// Settings of the soccer provider, competitions are ESPN league slugs
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct SoccerConfig {
+    pub league: String,
+    pub domestic_cup: String,
+    pub european_cup: String,
+    // Shown in the tournament view, e.g. "fifa.world" or "uefa.euro"
+    pub tournament: String,
+    // Competitions shown in the day view and team schedule
+    pub competitions: Vec<Competition>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Competition {
+    League,
+    DomesticCup,
+    EuropeanCup,
+}
+
+impl Competition {
+    pub const ALL: [Competition; 3] = [Competition::League, Competition::DomesticCup, Competition::EuropeanCup];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Competition::League => "League",
+            Competition::DomesticCup => "Cup",
+            Competition::EuropeanCup => "Europe",
+        }
+    }
+}
+
+impl Default for SoccerConfig {
+    fn default() -> Self {
+        SoccerConfig {
+            league: "eng.1".to_string(),
+            domestic_cup: "eng.fa".to_string(),
+            european_cup: "uefa.champions".to_string(),
+            tournament: "fifa.world".to_string(),
+            competitions: Competition::ALL.to_vec(),
+        }
+    }
+}
+
+const DEFAULT_CACHE_MAX_SIZE_MB: u64 = 100;
+const DEFAULT_AUTO_REFRESH_SECS: u64 = 30;
+const DEFAULT_POLL_BREAK_FACTOR: u32 = 3;
+const DEFAULT_POLL_JITTER_PERCENT: u32 = 10;
+// More jitter could draw an interval of almost nothing and poll on every tick
+const MAX_POLL_JITTER_PERCENT: u32 = 50;
+
+// $XDG_CONFIG_HOME/rust_sports_cli, falling back to ~/.config/rust_sports_cli
+pub fn config_dir() -> PathBuf {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home_dir().join(".config"));
+
+    base.join("rust_sports_cli")
+}
+
+pub fn config_path() -> PathBuf {
+    config_dir().join("config.toml")
+}
+
+// Used to notice edits of the config file while the TUI is running
+pub fn config_modified() -> Option<SystemTime> {
+    std::fs::metadata(config_path()).and_then(|metadata| metadata.modified()).ok()
+}
+
+pub fn home_dir() -> PathBuf {
+    std::env::var_os("HOME").map(PathBuf::from).unwrap_or_default()
+}
+
+impl Config {
+    // Missing config file is not an error, it just means first run
+    pub fn load() -> Result<Config> {
+        let path = config_path();
+
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("Could not read config file {}", path.display()))?;
+
+        toml::from_str(&text).with_context(|| format!("Could not parse config file {}", path.display()))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = config_path();
+
+        std::fs::create_dir_all(config_dir())
+            .with_context(|| format!("Could not create config directory {}", config_dir().display()))?;
+
+        std::fs::write(&path, toml::to_string_pretty(self)?)
+            .with_context(|| format!("Could not write config file {}", path.display()))
+    }
+
+    pub fn api_key(&self) -> Option<&str> {
+        self.api_key.as_deref().filter(|key| !key.is_empty())
+    }
+
+    pub fn auto_refresh_interval(&self) -> Option<Duration> {
+        match self.auto_refresh_secs.unwrap_or(DEFAULT_AUTO_REFRESH_SECS) {
+            0 => None,
+            secs => Some(Duration::from_secs(secs)),
+        }
+    }
+
+    pub fn poll_break_factor(&self) -> u32 {
+        self.poll_break_factor.unwrap_or(DEFAULT_POLL_BREAK_FACTOR).max(1)
+    }
+
+    // Share of the interval, at most half of it
+    pub fn poll_jitter(&self) -> f64 {
+        self.poll_jitter_percent.unwrap_or(DEFAULT_POLL_JITTER_PERCENT).min(MAX_POLL_JITTER_PERCENT) as f64 / 100.0
+    }
+
+    pub fn cache_max_size(&self) -> u64 {
+        self.cache_max_size_mb.unwrap_or(DEFAULT_CACHE_MAX_SIZE_MB) * 1024 * 1024
+    }
+}
@@ -0,0 +1,327 @@
+use anyhow::{anyhow, bail, Context, Result};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::os::unix::net::UnixStream;
+use std::time::Duration;
+
+// A notification server that does not answer must not freeze the UI for long
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+// Shown by the notification server as the sender
+const APP_NAME: &str = "sports";
+
+// Message types and header fields of the D-Bus wire format
+const METHOD_CALL: u8 = 1;
+const METHOD_RETURN: u8 = 2;
+const ERROR: u8 = 3;
+const FIELD_PATH: u8 = 1;
+const FIELD_INTERFACE: u8 = 2;
+const FIELD_MEMBER: u8 = 3;
+const FIELD_ERROR_NAME: u8 = 4;
+const FIELD_REPLY_SERIAL: u8 = 5;
+const FIELD_DESTINATION: u8 = 6;
+const FIELD_SIGNATURE: u8 = 8;
+
+extern "C" {
+    fn getuid() -> u32;
+}
+
+/// Shows the notification through org.freedesktop.Notifications on the session
+/// bus, waiting until the notification server has taken it
+pub fn notify(title: &str, body: &str) -> Result<()> {
+    let mut bus = Bus::connect()?;
+    bus.call(&MethodCall {
+        destination: "org.freedesktop.DBus",
+        path: "/org/freedesktop/DBus",
+        interface: "org.freedesktop.DBus",
+        member: "Hello",
+        signature: "",
+        body: Vec::new(),
+    })?;
+    bus.call(&MethodCall {
+        destination: "org.freedesktop.Notifications",
+        path: "/org/freedesktop/Notifications",
+        interface: "org.freedesktop.Notifications",
+        member: "Notify",
+        signature: "susssasa{sv}i",
+        body: notify_body(title, body),
+    })
+}
+
+// Notify(app_name, replaces_id, app_icon, summary, body, actions, hints, expire_timeout)
+fn notify_body(title: &str, body: &str) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    put_string(&mut buffer, APP_NAME);
+    put_u32(&mut buffer, 0);
+    put_string(&mut buffer, "");
+    put_string(&mut buffer, title);
+    put_string(&mut buffer, body);
+    // no actions
+    put_u32(&mut buffer, 0);
+    // no hints, an empty array is still padded to its dict entries
+    put_u32(&mut buffer, 0);
+    pad(&mut buffer, 8);
+    // the server's default timeout
+    put_u32(&mut buffer, -1i32 as u32);
+    buffer
+}
+
+// Alignment is from the start of the message, headers and bodies both start at a multiple of 8
+fn pad(buffer: &mut Vec<u8>, alignment: usize) {
+    buffer.resize(buffer.len().next_multiple_of(alignment), 0);
+}
+
+fn put_u32(buffer: &mut Vec<u8>, value: u32) {
+    pad(buffer, 4);
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_string(buffer: &mut Vec<u8>, value: &str) {
+    put_u32(buffer, value.len() as u32);
+    buffer.extend_from_slice(value.as_bytes());
+    buffer.push(0);
+}
+
+fn put_signature(buffer: &mut Vec<u8>, value: &str) {
+    buffer.push(value.len() as u8);
+    buffer.extend_from_slice(value.as_bytes());
+    buffer.push(0);
+}
+
+struct MethodCall<'a> {
+    destination: &'a str,
+    path: &'a str,
+    interface: &'a str,
+    member: &'a str,
+    signature: &'a str,
+    body: Vec<u8>,
+}
+
+impl MethodCall<'_> {
+    fn encode(&self, serial: u32) -> Vec<u8> {
+        let mut fields = Vec::new();
+        let mut field = |code: u8, signature: &str, value: &str| {
+            // fields are structs, aligned to 8 from the start of the message
+            let start = 16 + fields.len();
+            fields.resize(fields.len() + (start.next_multiple_of(8) - start), 0);
+            fields.push(code);
+            put_signature(&mut fields, signature);
+            match signature {
+                "g" => put_signature(&mut fields, value),
+                _ => {
+                    // strings are aligned to 4 from the start of the message as well
+                    let start = 16 + fields.len();
+                    fields.resize(fields.len() + (start.next_multiple_of(4) - start), 0);
+                    fields.extend_from_slice(&(value.len() as u32).to_le_bytes());
+                    fields.extend_from_slice(value.as_bytes());
+                    fields.push(0);
+                }
+            }
+        };
+        field(FIELD_PATH, "o", self.path);
+        field(FIELD_INTERFACE, "s", self.interface);
+        field(FIELD_MEMBER, "s", self.member);
+        field(FIELD_DESTINATION, "s", self.destination);
+        if !self.signature.is_empty() {
+            field(FIELD_SIGNATURE, "g", self.signature);
+        }
+
+        let mut message = vec![b'l', METHOD_CALL, 0, 1];
+        message.extend_from_slice(&(self.body.len() as u32).to_le_bytes());
+        message.extend_from_slice(&serial.to_le_bytes());
+        message.extend_from_slice(&(fields.len() as u32).to_le_bytes());
+        message.extend_from_slice(&fields);
+        pad(&mut message, 8);
+        message.extend_from_slice(&self.body);
+        message
+    }
+}
+
+// What a reply to one of our calls says, other messages on the bus are skipped
+#[derive(Debug, PartialEq)]
+struct Reply {
+    kind: u8,
+    reply_serial: Option<u32>,
+    error_name: Option<String>,
+}
+
+fn parse_header(header: &[u8]) -> Result<(Reply, usize)> {
+    let little = match header.first() {
+        Some(b'l') => true,
+        Some(b'B') => false,
+        _ => bail!("Malformed D-Bus message"),
+    };
+    let u32_at = |at: usize| -> Result<u32> {
+        let bytes: [u8; 4] = header.get(at..at + 4).and_then(|bytes| bytes.try_into().ok()).ok_or_else(|| anyhow!("Truncated D-Bus message"))?;
+        Ok(if little { u32::from_le_bytes(bytes) } else { u32::from_be_bytes(bytes) })
+    };
+    let body_len = u32_at(4)? as usize;
+    let fields_end = 16 + u32_at(12)? as usize;
+
+    let mut reply = Reply { kind: header[1], reply_serial: None, error_name: None };
+    let mut at = 16;
+    while at < fields_end {
+        at = at.next_multiple_of(8);
+        let code = *header.get(at).ok_or_else(|| anyhow!("Truncated D-Bus message"))?;
+        let signature_len = *header.get(at + 1).ok_or_else(|| anyhow!("Truncated D-Bus message"))? as usize;
+        let signature = header.get(at + 2..at + 2 + signature_len).ok_or_else(|| anyhow!("Truncated D-Bus message"))?;
+        at += 3 + signature_len;
+        match signature {
+            b"s" | b"o" => {
+                at = at.next_multiple_of(4);
+                let len = u32_at(at)? as usize;
+                let value = header.get(at + 4..at + 4 + len).ok_or_else(|| anyhow!("Truncated D-Bus message"))?;
+                if code == FIELD_ERROR_NAME {
+                    reply.error_name = Some(String::from_utf8_lossy(value).into_owned());
+                }
+                at += 5 + len;
+            }
+            b"g" => at += 2 + *header.get(at).ok_or_else(|| anyhow!("Truncated D-Bus message"))? as usize,
+            b"u" => {
+                at = at.next_multiple_of(4);
+                if code == FIELD_REPLY_SERIAL {
+                    reply.reply_serial = Some(u32_at(at)?);
+                }
+                at += 4;
+            }
+            _ => bail!("Unexpected D-Bus header field"),
+        }
+    }
+    Ok((reply, fields_end.next_multiple_of(8) + body_len))
+}
+
+struct Bus {
+    stream: BufReader<UnixStream>,
+    serial: u32,
+}
+
+impl Bus {
+    fn connect() -> Result<Bus> {
+        let address = std::env::var("DBUS_SESSION_BUS_ADDRESS")
+            .ok()
+            .filter(|address| !address.is_empty())
+            .or_else(|| std::env::var("XDG_RUNTIME_DIR").ok().map(|dir| format!("unix:path={}/bus", dir)))
+            .ok_or_else(|| anyhow!("No D-Bus session bus"))?;
+        let stream = address.split(';').find_map(|address| open(address).ok()).ok_or_else(|| anyhow!("Could not connect to the D-Bus session bus {}", address))?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        stream.set_write_timeout(Some(TIMEOUT))?;
+        let mut bus = Bus { stream: BufReader::new(stream), serial: 0 };
+        bus.authenticate()?;
+        Ok(bus)
+    }
+
+    // SASL EXTERNAL, the bus checks the uid against the socket's peer credentials
+    fn authenticate(&mut self) -> Result<()> {
+        let uid = unsafe { getuid() }.to_string();
+        let hex: String = uid.bytes().map(|byte| format!("{:02x}", byte)).collect();
+        let stream = self.stream.get_mut();
+        stream.write_all(format!("\0AUTH EXTERNAL {}\r\n", hex).as_bytes())?;
+        let mut line = String::new();
+        self.stream.read_line(&mut line).context("No answer from the D-Bus session bus")?;
+        if !line.starts_with("OK ") {
+            bail!("The D-Bus session bus refused us: {}", line.trim());
+        }
+        self.stream.get_mut().write_all(b"BEGIN\r\n")?;
+        Ok(())
+    }
+
+    // Sends the call and waits for its reply
+    fn call(&mut self, call: &MethodCall) -> Result<()> {
+        self.serial += 1;
+        self.stream.get_mut().write_all(&call.encode(self.serial))?;
+        loop {
+            let mut header = vec![0; 16];
+            self.stream.read_exact(&mut header).context("No reply on the D-Bus session bus")?;
+            let fields_len = u32::from_le_bytes([header[12], header[13], header[14], header[15]]);
+            let fields_len = if header[0] == b'B' { fields_len.swap_bytes() } else { fields_len } as usize;
+            header.resize(16 + fields_len, 0);
+            self.stream.read_exact(&mut header[16..])?;
+            let (reply, len) = parse_header(&header)?;
+            // the padding after the fields and the body
+            std::io::copy(&mut (&mut self.stream).take((len - header.len()) as u64), &mut std::io::sink())?;
+
+            if reply.reply_serial != Some(self.serial) {
+                continue;
+            }
+            return match reply.kind {
+                METHOD_RETURN => Ok(()),
+                ERROR => Err(anyhow!("{} failed: {}", call.member, reply.error_name.unwrap_or_default())),
+                _ => continue,
+            };
+        }
+    }
+}
+
+// One address of the list, unix:path=... or unix:abstract=...
+fn open(address: &str) -> Result<UnixStream> {
+    let options = address.strip_prefix("unix:").ok_or_else(|| anyhow!("Unsupported D-Bus address {}", address))?;
+    for option in options.split(',') {
+        match option.split_once('=') {
+            Some(("path", path)) => return Ok(UnixStream::connect(unescape(path))?),
+            Some(("abstract", name)) => {
+                use std::os::linux::net::SocketAddrExt;
+                let address = std::os::unix::net::SocketAddr::from_abstract_name(unescape(name).as_bytes())?;
+                return Ok(UnixStream::connect_addr(&address)?);
+            }
+            _ => {}
+        }
+    }
+    bail!("Unsupported D-Bus address {}", address)
+}
+
+// Values in addresses escape bytes as %xx
+fn unescape(value: &str) -> String {
+    let mut bytes = Vec::new();
+    let mut rest = value.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        match (byte, tail.get(..2).and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok())) {
+            (b'%', Some(decoded)) => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            _ => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_call_reads_back_as_its_own_header() {
+        let call = MethodCall {
+            destination: "org.freedesktop.Notifications",
+            path: "/org/freedesktop/Notifications",
+            interface: "org.freedesktop.Notifications",
+            member: "Notify",
+            signature: "susssasa{sv}i",
+            body: notify_body("Final", "BOS 101 - 99 NYK"),
+        };
+        let message = call.encode(7);
+
+        let (reply, len) = parse_header(&message).unwrap();
+        assert_eq!(reply, Reply { kind: METHOD_CALL, reply_serial: None, error_name: None });
+        assert_eq!(len, message.len());
+        assert_eq!(message[8..12], 7u32.to_le_bytes());
+        // the body starts on a multiple of 8
+        assert_eq!((message.len() - call.body.len()) % 8, 0);
+    }
+
+    #[test]
+    fn the_notify_body_pads_the_empty_hints() {
+        let body = notify_body("", "");
+        // app name, replaces id, 3 empty strings, empty actions, empty hints padded to 8, timeout
+        assert_eq!(body.len(), 52);
+        assert_eq!(body[48..], (-1i32).to_le_bytes());
+    }
+
+    #[test]
+    fn unescapes_addresses() {
+        assert_eq!(unescape("/run/user/1000/bus"), "/run/user/1000/bus");
+        assert_eq!(unescape("/tmp/dbus%2dtest%"), "/tmp/dbus-test%");
+    }
+}
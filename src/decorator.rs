@@ -0,0 +1,71 @@
+use crate::Game;
+use serde::{Deserialize, Serialize};
+
+// Extra lines below a game's row in the scoreboard. Providers pick the ones
+// that make sense for their league, users can override them per league in the
+// config, e.g. `[row_decorators]` with `mlb = ["pitchers", "broadcast"]`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RowDecorator {
+    // Probable starters of upcoming baseball games
+    Pitchers,
+    // Leading scorer of each team in live and finished games
+    TopScorers,
+    // Betting line of upcoming games
+    Odds,
+    // TV and streaming channels
+    Broadcast,
+    // Pages of the game and the teams on the provider's website
+    Links,
+}
+
+// A label that opens the url when clicked, in terminals with hyperlinks
+#[derive(Clone, Debug, PartialEq)]
+pub struct Link {
+    pub label: String,
+    pub url: String,
+}
+
+// One extra line of a row, either split below the two teams or a note below the away team
+pub enum RowLine {
+    Sides(String, String),
+    Note(String),
+    // labels below the away team, where hyperlinks are not supported the first url instead
+    Links(Vec<Link>),
+}
+
+impl RowDecorator {
+    pub fn line(&self, game: &Game) -> Option<RowLine> {
+        let upcoming = !game.is_live() && !game.is_final();
+        match self {
+            RowDecorator::Pitchers if upcoming => {
+                let home = game.home_pitcher.as_ref().map(|pitcher| pitcher.label());
+                let visitor = game.visitor_pitcher.as_ref().map(|pitcher| pitcher.label());
+                if home.is_none() && visitor.is_none() {
+                    return None;
+                }
+                let tbd = || "TBD".to_string();
+                Some(RowLine::Sides(home.unwrap_or_else(tbd), visitor.unwrap_or_else(tbd)))
+            }
+            RowDecorator::TopScorers if !upcoming => {
+                let home = game.home_leader.clone();
+                let visitor = game.visitor_leader.clone();
+                if home.is_none() && visitor.is_none() {
+                    return None;
+                }
+                Some(RowLine::Sides(home.unwrap_or_default(), visitor.unwrap_or_default()))
+            }
+            RowDecorator::Odds if upcoming => game.odds.clone().map(RowLine::Note),
+            RowDecorator::Broadcast => game.broadcast.as_ref().map(|broadcast| RowLine::Note(format!("TV: {}", broadcast))),
+            RowDecorator::Links => {
+                let game_link = game.link.clone().map(|url| Link { label: "Game".to_string(), url });
+                let team_links = [&game.visitor_team, &game.home_team]
+                    .into_iter()
+                    .filter_map(|team| Some(Link { label: team.abbreviation.clone(), url: team.link.clone()? }));
+                let links: Vec<Link> = game_link.into_iter().chain(team_links).collect();
+                (!links.is_empty()).then_some(RowLine::Links(links))
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,362 @@
+use crate::provider::Provider;
+use crate::standings::Sort;
+use crate::{Game, Team};
+use crate::scroll;
+use crate::theme::Theme;
+use ratatui::{
+    prelude::{Constraint, Direction, Frame, Layout, Line, Rect, Span},
+    symbols::Marker,
+    widgets::canvas::{Canvas, Circle, Points, Rectangle},
+    widgets::{Paragraph, Row, Table},
+};
+
+// Box score of a single game, shown when a game is opened from the scoreboard
+#[derive(Default)]
+pub struct GameDetails {
+    pub game: Game,
+    pub leaders: Vec<Leader>,
+    // Full box score, only for leagues with a stats endpoint
+    pub players: Vec<PlayerStats>,
+    // Team whose box score table has the focus, 0 home and 1 away
+    pub focus: usize,
+    // Sort of the focused box score table
+    pub sort: Sort,
+    // Field goal attempts with their location, only where a provider has them
+    pub shots: Vec<Shot>,
+    // Shot chart of the focused team shown next to the box score
+    pub show_shots: bool,
+    // First row of the focused box score table
+    pub scroll: usize,
+}
+
+// Location of a field goal attempt in feet, x across the court from the left
+// sideline (0-50) and y from the baseline under the basket
+#[derive(Clone, Debug)]
+pub struct Shot {
+    pub team: String,
+    pub x: f64,
+    pub y: f64,
+    pub made: bool,
+}
+
+impl GameDetails {
+    pub fn switch_team(&mut self) {
+        self.focus = 1 - self.focus;
+        self.sort = Sort::default();
+        self.scroll = 0;
+    }
+
+    // Stops at the last player of the focused team
+    pub fn scroll(&mut self, delta: isize) {
+        let team = if self.focus == 0 { &self.game.home_team } else { &self.game.visitor_team };
+        let rows = self.players.iter().filter(|player| player.team == team.abbreviation).count();
+        self.scroll = self.scroll.saturating_add_signed(delta).min(rows.saturating_sub(1));
+    }
+
+    pub fn toggle_shots(&mut self) {
+        self.show_shots = !self.show_shots;
+    }
+
+    pub fn cycle_sort(&mut self, delta: isize) {
+        self.sort.cycle(BOX_SCORE_COLUMNS.len(), delta);
+    }
+}
+
+// One player's line of a box score, shots are (made, attempted)
+#[derive(Clone, Debug, Default)]
+pub struct PlayerStats {
+    pub name: String,
+    pub team: String,
+    // minutes played as sent by the API, e.g. "34" or "34:12"
+    pub min: String,
+    pub pts: u32,
+    pub reb: u32,
+    pub ast: u32,
+    pub fg: (u32, u32),
+    pub fg3: (u32, u32),
+    pub ft: (u32, u32),
+}
+
+impl PlayerStats {
+    fn minutes(&self) -> u32 {
+        self.min.split(':').next().and_then(|minutes| minutes.trim().parse().ok()).unwrap_or_default()
+    }
+}
+
+// Wide enough for the half court to keep its proportions in braille dots
+const SHOT_CHART_WIDTH: u16 = 30;
+
+// Columns of the box score after the player's name
+const BOX_SCORE_COLUMNS: [&str; 7] = ["MIN", "PTS", "REB", "AST", "FG", "3P", "FT"];
+
+// Best player of a team in one category, e.g. "Points"
+pub struct Leader {
+    pub team: String,
+    pub category: String,
+    pub name: String,
+    pub value: String,
+}
+
+// Points, rebounds and assists leaders of each team from the box score
+pub fn leaders_from_players(game: &Game, players: &[PlayerStats]) -> Vec<Leader> {
+    type Stat = fn(&PlayerStats) -> u32;
+    let categories: [(&str, Stat); 3] = [
+        ("Points", |player| player.pts),
+        ("Rebounds", |player| player.reb),
+        ("Assists", |player| player.ast),
+    ];
+
+    let mut leaders = Vec::new();
+    for team in [&game.home_team.abbreviation, &game.visitor_team.abbreviation] {
+        for (category, stat) in categories {
+            let best = players.iter().filter(|player| &player.team == team).max_by_key(|player| stat(player));
+            if let Some(player) = best.filter(|player| stat(player) > 0) {
+                leaders.push(Leader {
+                    team: team.clone(),
+                    category: category.to_string(),
+                    name: player.name.clone(),
+                    value: stat(player).to_string(),
+                });
+            }
+        }
+    }
+    leaders
+}
+
+// How the teams of the matchup header are marked
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Badges {
+    Plain,
+    // abbreviations in the team colors
+    Colors,
+    // room is left in front of each abbreviation for a logo image
+    #[cfg(feature = "logos")]
+    Logos,
+}
+
+// Cells left free for a logo in front of an abbreviation
+#[cfg(feature = "logos")]
+const LOGO_WIDTH: u16 = 3;
+
+// Where the logos of the matchup header go, for a details block drawn at area
+#[cfg(feature = "logos")]
+pub fn logo_spots(game: &Game, area: Rect) -> Vec<(Team, u16, u16)> {
+    let (x, y) = (area.x + 1, area.y + 1);
+    let home = format!("{} {} - {} ", game.home_team.abbreviation, game.home_team_score, game.visitor_team_score);
+    vec![(game.home_team.clone(), x, y), (game.visitor_team.clone(), x + LOGO_WIDTH + home.chars().count() as u16, y)]
+}
+
+pub fn ui(details: &GameDetails, provider: &dyn Provider, badges: Badges, theme: &Theme, f: &mut Frame, area: Rect) {
+    let game = &details.game;
+    let clock = provider.clock(game);
+    let title = format!("{} @ {}", game.visitor_team.full_name, game.home_team.full_name);
+    let block = theme.block(title);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(2), Constraint::Length(5), Constraint::Min(0)])
+        .split(inner);
+
+    let team = |team: &Team| match badges {
+        Badges::Plain => Span::raw(team.abbreviation.clone()),
+        Badges::Colors => Span::styled(team.abbreviation.clone(), team.style()),
+        #[cfg(feature = "logos")]
+        Badges::Logos => Span::raw(format!("{}{}", " ".repeat(LOGO_WIDTH as usize), team.abbreviation)),
+    };
+    let mut header = Line::from(vec![
+        team(&game.home_team),
+        Span::raw(format!(" {} - {} ", game.home_team_score, game.visitor_team_score)),
+        team(&game.visitor_team),
+        Span::raw("    "),
+    ]);
+    // tagged in words too, not only by color
+    if game.is_live() {
+        header.spans.extend([Span::styled(" LIVE ", theme.live_tag), Span::raw(" ")]);
+    } else if game.is_final() {
+        header.spans.extend([Span::styled(" FINAL ", theme.final_tag), Span::raw(" ")]);
+    }
+    header.spans.push(Span::raw(clock));
+    f.render_widget(Paragraph::new(header).style(theme.header), chunks[0]);
+
+    // upcoming games have no linescore yet, their probable starters are shown instead
+    if game.linescore().is_empty() && (game.home_pitcher.is_some() || game.visitor_pitcher.is_some()) {
+        render_pitchers(game, theme, f, chunks[1]);
+    } else {
+        render_linescore(game, provider, theme, f, chunks[1]);
+    }
+
+    let focused = if details.focus == 0 { &game.home_team } else { &game.visitor_team };
+    let area = if details.show_shots && !details.shots.is_empty() && !theme.plain {
+        let panes = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Length(SHOT_CHART_WIDTH)])
+            .split(chunks[2]);
+        render_shot_chart(focused, &details.shots, theme, f, panes[1]);
+        panes[0]
+    } else {
+        chunks[2]
+    };
+
+    if details.players.is_empty() {
+        render_leaders(&details.leaders, theme, f, area);
+        return;
+    }
+
+    let tables = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Fill(1), Constraint::Fill(1)])
+        .split(area);
+    for (index, team) in [&game.home_team, &game.visitor_team].into_iter().enumerate() {
+        let (sort, scroll) = if index == details.focus { (Some(details.sort), details.scroll) } else { (None, 0) };
+        render_box_score(team, &details.players, sort, scroll, theme, f, tables[index]);
+    }
+}
+
+// Half court in braille dots, made shots green and missed ones red
+fn render_shot_chart(team: &Team, shots: &[Shot], theme: &Theme, f: &mut Frame, area: Rect) {
+    let points = |made: bool| -> Vec<(f64, f64)> {
+        shots.iter().filter(|shot| shot.team == team.abbreviation && shot.made == made).map(|shot| (shot.x, shot.y)).collect()
+    };
+    let (made, missed) = (points(true), points(false));
+
+    let canvas = Canvas::default()
+        .block(theme.block(format!("{} shots ({} of {})", team.abbreviation, made.len(), made.len() + missed.len())))
+        .marker(Marker::Braille)
+        .x_bounds([0.0, 50.0])
+        .y_bounds([0.0, 47.0])
+        .paint(|ctx| {
+            // paint, three point line and basket
+            ctx.draw(&Rectangle { x: 0.0, y: 0.0, width: 50.0, height: 47.0, color: Theme::color(theme.muted) });
+            ctx.draw(&Rectangle { x: 17.0, y: 0.0, width: 16.0, height: 19.0, color: Theme::color(theme.muted) });
+            ctx.draw(&Circle { x: 25.0, y: 5.25, radius: 23.75, color: Theme::color(theme.muted) });
+            ctx.draw(&Circle { x: 25.0, y: 5.25, radius: 0.75, color: Theme::color(theme.accent) });
+            ctx.layer();
+            ctx.draw(&Points { coords: &missed, color: Theme::color(theme.bad) });
+            ctx.draw(&Points { coords: &made, color: Theme::color(theme.good) });
+        });
+    f.render_widget(canvas, area);
+}
+
+// Box score of one team, the focused table has a bold border and can be sorted and scrolled
+fn render_box_score(team: &Team, players: &[PlayerStats], sort: Option<Sort>, scroll: usize, theme: &Theme, f: &mut Frame, area: Rect) {
+    let mut block = theme.block(format!("{} box score", team.full_name));
+    if sort.is_some() {
+        block = block.border_style(theme.focused).title_style(theme.focused);
+    }
+    let sort = sort.unwrap_or_default();
+
+    let mut lines: Vec<&PlayerStats> = players.iter().filter(|player| player.team == team.abbreviation).collect();
+    if let Some(column) = sort.column {
+        let value = |player: &PlayerStats| match column {
+            0 => player.minutes(),
+            1 => player.pts,
+            2 => player.reb,
+            3 => player.ast,
+            4 => player.fg.0,
+            5 => player.fg3.0,
+            _ => player.ft.0,
+        };
+        lines.sort_by_key(|player| std::cmp::Reverse(value(player)));
+    }
+    if sort.reversed {
+        lines.reverse();
+    }
+
+    let shots = |(made, attempted): (u32, u32)| format!("{}-{}", made, attempted);
+    let count = lines.len();
+    let rows = lines.into_iter().map(|player| {
+        Row::new(vec![
+            player.name.clone(),
+            player.min.clone(),
+            player.pts.to_string(),
+            player.reb.to_string(),
+            player.ast.to_string(),
+            shots(player.fg),
+            shots(player.fg3),
+            shots(player.ft),
+        ])
+    });
+
+    let mut header = vec!["Player".to_string()];
+    header.extend(BOX_SCORE_COLUMNS.iter().enumerate().map(|(index, column)| match sort.column {
+        Some(sorted) if sorted == index => format!("{}{}", column, if sort.reversed { "▲" } else { "▼" }),
+        _ => column.to_string(),
+    }));
+    let widths = [
+        Constraint::Fill(1),
+        Constraint::Length(6),
+        Constraint::Length(5),
+        Constraint::Length(5),
+        Constraint::Length(5),
+        Constraint::Length(6),
+        Constraint::Length(6),
+        Constraint::Length(6),
+    ];
+
+    let table = Table::new(rows, widths).header(Row::new(header).style(theme.header));
+    scroll::render(table.block(block), count, scroll, theme, f, area);
+}
+
+// One column per period including overtimes, labelled the league's way
+fn render_linescore(game: &Game, provider: &dyn Provider, theme: &Theme, f: &mut Frame, area: Rect) {
+    let periods = game.linescore();
+    if periods.is_empty() {
+        f.render_widget(Paragraph::new("No linescore available."), area);
+        return;
+    }
+
+    let mut header = vec!["".to_string()];
+    header.extend((0..periods.len()).map(|index| provider.period_label(index)));
+    header.push("T".to_string());
+
+    let mut home = vec![game.home_team.abbreviation.clone()];
+    home.extend(periods.iter().map(|(points, _)| points.to_string()));
+    home.push(game.home_team_score.to_string());
+
+    let mut visitor = vec![game.visitor_team.abbreviation.clone()];
+    visitor.extend(periods.iter().map(|(_, points)| points.to_string()));
+    visitor.push(game.visitor_team_score.to_string());
+
+    let mut widths = vec![Constraint::Length(5)];
+    widths.extend(std::iter::repeat_n(Constraint::Length(4), periods.len() + 1));
+
+    let table = Table::new(vec![Row::new(home), Row::new(visitor)], widths)
+        .header(Row::new(header).style(theme.header))
+        .block(theme.block("Linescore"));
+    f.render_widget(table, area);
+}
+
+fn render_pitchers(game: &Game, theme: &Theme, f: &mut Frame, area: Rect) {
+    let sides = [(&game.home_team, &game.home_pitcher), (&game.visitor_team, &game.visitor_pitcher)];
+    let rows = sides.into_iter().map(|(team, pitcher)| match pitcher {
+        Some(pitcher) => Row::new(vec![
+            team.abbreviation.clone(),
+            pitcher.name.clone(),
+            format!("{}-{}", pitcher.wins, pitcher.losses),
+            pitcher.era.clone(),
+        ]),
+        None => Row::new(vec![team.abbreviation.clone(), "TBD".to_string()]),
+    });
+
+    let widths = [Constraint::Length(5), Constraint::Fill(1), Constraint::Length(7), Constraint::Length(6)];
+    let table = Table::new(rows, widths)
+        .header(Row::new(vec!["", "Probable pitcher", "W-L", "ERA"]).style(theme.header))
+        .block(theme.block("Pitching matchup"));
+    f.render_widget(table, area);
+}
+
+fn render_leaders(leaders: &[Leader], theme: &Theme, f: &mut Frame, area: Rect) {
+    let block = theme.block("Top performers");
+    if leaders.is_empty() {
+        f.render_widget(Paragraph::new("No player stats available.").block(block), area);
+        return;
+    }
+
+    let rows = leaders.iter().map(|leader| {
+        Row::new(vec![leader.team.clone(), leader.category.clone(), leader.name.clone(), leader.value.clone()])
+    });
+    let widths = [Constraint::Length(5), Constraint::Length(10), Constraint::Fill(1), Constraint::Length(10)];
+    f.render_widget(Table::new(rows, widths).block(block), area);
+}
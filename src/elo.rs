@@ -0,0 +1,51 @@
+use crate::Game;
+use std::collections::HashMap;
+
+// Every team starts at the average rating
+const START: f64 = 1500.0;
+
+// How far one result moves the ratings
+const K: f64 = 20.0;
+
+// Rating points playing at home is worth
+const HOME_ADVANTAGE: f64 = 100.0;
+
+// Elo ratings of the teams by abbreviation. The winner takes points from the loser,
+// more for an upset than for a win it was expected to get.
+pub struct Ratings {
+    ratings: HashMap<String, f64>,
+}
+
+impl Ratings {
+    // Ratings after the finished games, played in the order given
+    pub fn compute(games: &[Game]) -> Ratings {
+        let mut ratings: HashMap<String, f64> = HashMap::new();
+        for game in games.iter().filter(|game| game.is_final()) {
+            let home = *ratings.get(&game.home_team.abbreviation).unwrap_or(&START);
+            let visitor = *ratings.get(&game.visitor_team.abbreviation).unwrap_or(&START);
+            let result = match game.home_team_score.cmp(&game.visitor_team_score) {
+                std::cmp::Ordering::Greater => 1.0,
+                std::cmp::Ordering::Equal => 0.5,
+                std::cmp::Ordering::Less => 0.0,
+            };
+            let change = K * (result - expected(home, visitor));
+            ratings.insert(game.home_team.abbreviation.clone(), home + change);
+            ratings.insert(game.visitor_team.abbreviation.clone(), visitor - change);
+        }
+        Ratings { ratings }
+    }
+
+    pub fn get(&self, team: &str) -> Option<f64> {
+        self.ratings.get(team).copied()
+    }
+
+    // Chance of the home team winning, None until both teams have played
+    pub fn home_win_probability(&self, game: &Game) -> Option<f64> {
+        Some(expected(self.get(&game.home_team.abbreviation)?, self.get(&game.visitor_team.abbreviation)?))
+    }
+}
+
+// Expected result of the home team, a draw counting as half a win
+fn expected(home: f64, visitor: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((visitor - home - HOME_ADVANTAGE) / 400.0))
+}
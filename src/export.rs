@@ -0,0 +1,173 @@
+use crate::archive::Archive;
+use crate::provider::Provider;
+use crate::schema::{game_columns, SCHEMA_VERSION};
+use crate::store::Store;
+use crate::Game;
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{NaiveDate, NaiveTime, TimeZone, Utc};
+use clap::ValueEnum;
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default)]
+pub enum ExportFormat {
+    /// Comma separated values with a header, one row per game
+    #[default]
+    Csv,
+}
+
+// Finished days of an export, one "<date> <length of the csv>" line per day.
+// Written after the rows of a day so a resumed export can cut off a half written day.
+fn checkpoint_path(output: &Path) -> PathBuf {
+    let mut name = output.as_os_str().to_owned();
+    name.push(".progress");
+    PathBuf::from(name)
+}
+
+fn read_checkpoint(path: &Path) -> (HashSet<NaiveDate>, u64) {
+    let mut done = HashSet::new();
+    let mut length = 0;
+    for line in std::fs::read_to_string(path).unwrap_or_default().lines() {
+        let Some((date, len)) = line.split_once(' ') else {
+            continue;
+        };
+        if let (Ok(date), Ok(len)) = (date.parse(), len.parse()) {
+            done.insert(date);
+            length = len;
+        }
+    }
+    (done, length)
+}
+
+// Export every game between two dates, inclusive, as CSV. Days are fetched one at a time,
+// from the cache where possible, and checkpointed so an interrupted export continues with
+// `resume` instead of starting over. The columns are those of the output version.
+// Returns the number of days written.
+#[allow(clippy::too_many_arguments)]
+pub fn export_csv(
+    provider: &dyn Provider,
+    cache: &dyn Store,
+    from: NaiveDate,
+    to: NaiveDate,
+    output: &Path,
+    resume: bool,
+    version: u32,
+    mut progress: impl FnMut(NaiveDate, usize),
+) -> Result<usize> {
+    let columns = game_columns(version);
+    let header = columns.join(",");
+    let checkpoint = checkpoint_path(output);
+    let (done, length) = if resume { read_checkpoint(&checkpoint) } else { (HashSet::new(), 0) };
+
+    // resuming must not append rows of other columns
+    if length > 0 {
+        let written = std::fs::read_to_string(output).unwrap_or_default();
+        if written.lines().next() != Some(header.as_str()) {
+            bail!("{} was written with other columns, resume it with the same --output-version", output.display());
+        }
+    }
+
+    // drops rows of a day that was cut off before its checkpoint, or everything when starting over
+    OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(false)
+        .open(output)
+        .and_then(|file| file.set_len(length))
+        .with_context(|| format!("Could not open {}", output.display()))?;
+    let mut file = OpenOptions::new().append(true).open(output)?;
+    if length == 0 {
+        writeln!(file, "{}", header)?;
+    }
+
+    let mut progress_file = if resume { OpenOptions::new().create(true).append(true).open(&checkpoint) } else { File::create(&checkpoint) }
+        .with_context(|| format!("Could not write {}", checkpoint.display()))?;
+
+    let cache_key = provider.cache_key();
+    let mut written = 0;
+    for date in from.iter_days().take_while(|date| *date <= to) {
+        if done.contains(&date) {
+            continue;
+        }
+
+        let day = Utc.from_utc_datetime(&date.and_time(NaiveTime::MIN));
+        let game_data = match cache.get(&cache_key, day) {
+            Some(game_data) => game_data,
+            None => {
+                let game_data = provider
+                    .fetch_day(day)
+                    .ok_or_else(|| anyhow!("Could not fetch {}, continue with --resume", date))?;
+                cache.put(&cache_key, day, &game_data)?;
+                game_data
+            }
+        };
+
+        let rows: String = game_data.data.iter().map(|game| csv_row(columns, provider.name(), game)).collect();
+        file.write_all(rows.as_bytes())?;
+        file.sync_data()?;
+        writeln!(progress_file, "{} {}", date, file.metadata()?.len())?;
+
+        written += 1;
+        progress(date, game_data.data.len());
+    }
+
+    // a complete export needs no checkpoint anymore
+    let _ = std::fs::remove_file(&checkpoint);
+    Ok(written)
+}
+
+// Export the archived games of the season starting in the year, nothing is fetched.
+// Returns the number of games written.
+pub fn export_season(provider: &dyn Provider, archive: &Archive, season: i32, output: &Path, version: u32) -> Result<usize> {
+    let (start, end) = provider.season_range(season).ok_or_else(|| anyhow!("{} has no seasons", provider.name()))?;
+    let games = archive.range(&provider.cache_key(), start, end);
+    if games.is_empty() {
+        bail!("No games of the {} {} season in the archive, fetch them with sync first", provider.name(), season);
+    }
+    write_rows(game_columns(version), provider.name(), &games, output)?;
+    Ok(games.len())
+}
+
+// Write games that are already loaded, e.g. the browsed day, as CSV in the latest columns
+pub fn write_csv(league: &str, games: &[Game], output: &Path) -> Result<()> {
+    write_rows(game_columns(SCHEMA_VERSION), league, games, output)
+}
+
+fn write_rows(columns: &[&str], league: &str, games: &[Game], output: &Path) -> Result<()> {
+    let mut csv = format!("{}\n", columns.join(","));
+    csv.extend(games.iter().map(|game| csv_row(columns, league, game)));
+    std::fs::write(output, csv).with_context(|| format!("Could not write {}", output.display()))
+}
+
+fn csv_row(columns: &[&str], league: &str, game: &Game) -> String {
+    let fields: Vec<String> = columns.iter().map(|column| csv_field(&field(column, league, game))).collect();
+    format!("{}\n", fields.join(","))
+}
+
+// Value of a column, see schema::game_columns
+fn field(column: &str, league: &str, game: &Game) -> String {
+    match column {
+        "date" => game.date.chars().take(10).collect(),
+        "league" => league.to_string(),
+        "competition" => game.competition.clone().unwrap_or_default(),
+        "visitor" => game.visitor_team.abbreviation.clone(),
+        "visitor_score" => game.visitor_team_score.to_string(),
+        "home" => game.home_team.abbreviation.clone(),
+        "home_score" => game.home_team_score.to_string(),
+        "status" => game.status.clone(),
+        "period" => game.period.to_string(),
+        "postseason" => game.postseason.to_string(),
+        _ => String::new(),
+    }
+}
+
+// Quote fields that contain separators, quotes or line breaks
+pub fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
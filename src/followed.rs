@@ -0,0 +1,92 @@
+use crate::provider::Provider;
+use crate::spoilers::Spoilers;
+use crate::{ids, Game};
+use crate::theme::Theme;
+use ratatui::{
+    prelude::{Frame, Line, Rect, Span},
+    style::Modifier,
+    widgets::Paragraph,
+};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+// How often today's games are checked for a followed game to start
+const IDLE_INTERVAL: Duration = Duration::from_secs(300);
+
+// Today's games of the favorite teams of every league, as (league index, game)
+#[derive(Default)]
+pub struct Followed {
+    pub games: Vec<(usize, Game)>,
+    pub fetched_at: Option<Instant>,
+    // league and game ids of the games that are close in their final minutes, they
+    // flash in the strip
+    pub clutch: HashSet<(String, u32)>,
+    // league and game ids of the followed games a clutch notification was sent for,
+    // once per game
+    pub notified: HashSet<(String, u32)>,
+}
+
+impl Followed {
+    pub fn live(&self) -> impl Iterator<Item = &(usize, Game)> {
+        self.games.iter().filter(|(_, game)| game.is_live())
+    }
+
+    // Polled on the clutch interval while one of them is close near its end, on the
+    // refresh interval while one is live, rarely otherwise
+    pub fn due(&self, interval: Duration, clutch_interval: Duration) -> bool {
+        let interval = if !self.clutch.is_empty() {
+            clutch_interval.min(interval)
+        } else if self.live().next().is_some() {
+            interval
+        } else {
+            IDLE_INTERVAL
+        };
+        self.fetched_at.is_none_or(|fetched_at| fetched_at.elapsed() >= interval)
+    }
+}
+
+// Followed games that finished a period since the previous fetch, with the number
+// of the period that ended
+pub fn period_ends<'a>(old: &[(usize, Game)], new: &'a [(usize, Game)]) -> Vec<(usize, &'a Game, u32)> {
+    new.iter()
+        .filter_map(|(league, game)| {
+            let (_, before) = old.iter().find(|(old_league, old)| old_league == league && old.id == game.id)?;
+            let ended = (before.period > 0 && (game.period > before.period || game.is_final() && !before.is_final())).then_some(before.period)?;
+            Some((*league, game, ended))
+        })
+        .collect()
+}
+
+// Games of the favorite team among the games of a league
+pub fn of_team<'a>(games: Vec<Game>, league: &'a str, team: &'a str) -> impl Iterator<Item = Game> + 'a {
+    games.into_iter().filter(move |game| ids::plays(league, team, game))
+}
+
+// One line above every view with the score and clock of each live followed game,
+// close games in their final minutes flash and are marked
+pub fn ui(followed: &Followed, providers: &[Arc<dyn Provider>], spoilers: &Spoilers, theme: &Theme, f: &mut Frame, area: Rect) {
+    let flash = (SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() / 500).is_multiple_of(2);
+    let mut spans = vec![Span::styled(" LIVE ", theme.live_tag)];
+    for (league, game) in followed.live() {
+        let provider = providers[*league].as_ref();
+        let text = format!(
+            "{} {} {} {} {}",
+            provider.name(),
+            game.home_team.abbreviation,
+            spoilers.score(provider, game),
+            game.visitor_team.abbreviation,
+            provider.clock(game)
+        );
+        spans.push(Span::raw("  "));
+        // close games are marked with a '!' besides flashing
+        let clutch = followed.clutch.contains(&(provider.id().to_string(), game.id));
+        let text = if clutch { format!("! {}", text) } else { text };
+        spans.push(match clutch {
+            true if flash => Span::styled(text, theme.bad.add_modifier(Modifier::REVERSED | Modifier::BOLD)),
+            true => Span::styled(text, theme.bad.add_modifier(Modifier::BOLD)),
+            false => Span::raw(text),
+        });
+    }
+    f.render_widget(Paragraph::new(Line::from(spans)).style(theme.live), area);
+}
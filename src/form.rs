@@ -0,0 +1,60 @@
+use crate::Game;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+// Results of the teams by abbreviation, oldest first as 'W', 'D' or 'L'
+pub struct Form {
+    results: HashMap<String, Vec<char>>,
+}
+
+impl Form {
+    // From the finished games, played in the order given
+    pub fn compute(games: &[Game]) -> Form {
+        let mut results: HashMap<String, Vec<char>> = HashMap::new();
+        for game in games.iter().filter(|game| game.is_final()) {
+            let sides = [
+                (&game.home_team.abbreviation, game.home_team_score.cmp(&game.visitor_team_score)),
+                (&game.visitor_team.abbreviation, game.visitor_team_score.cmp(&game.home_team_score)),
+            ];
+            for (team, result) in sides {
+                let result = match result {
+                    Ordering::Greater => 'W',
+                    Ordering::Equal => 'D',
+                    Ordering::Less => 'L',
+                };
+                results.entry(team.clone()).or_default().push(result);
+            }
+        }
+        Form { results }
+    }
+
+    pub fn results(&self, team: &str) -> Option<&[char]> {
+        self.results.get(team).map(Vec::as_slice)
+    }
+
+    // e.g. "W5, 7-3"
+    pub fn summary(&self, team: &str) -> Option<String> {
+        let results = self.results(team)?;
+        Some(format!("{}, {}", streak(results)?, last_ten(results)))
+    }
+}
+
+// Latest result and how many times in a row it happened, e.g. "W5"
+pub fn streak(results: &[char]) -> Option<String> {
+    let last = *results.last()?;
+    let count = results.iter().rev().take_while(|result| **result == last).count();
+    // overtime losses are a streak of their own
+    let label = if last == 'O' { "OT".to_string() } else { last.to_string() };
+    Some(format!("{}{}", label, count))
+}
+
+// Wins and losses of the last ten games, draws in the middle where there are some,
+// e.g. "7-3" or "5-2-3". Overtime losses count as losses.
+pub fn last_ten(results: &[char]) -> String {
+    let last = &results[results.len().saturating_sub(10)..];
+    let count = |result: &[char]| last.iter().filter(|last| result.contains(last)).count();
+    match count(&['D']) {
+        0 => format!("{}-{}", count(&['W']), count(&['L', 'O'])),
+        draws => format!("{}-{}-{}", count(&['W']), draws, count(&['L', 'O'])),
+    }
+}
@@ -0,0 +1,61 @@
+use crate::theme::Theme;
+use chrono::NaiveDate;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    prelude::{Frame, Line, Rect},
+    widgets::{Clear, Paragraph},
+};
+
+const PROMPT_WIDTH: u16 = 36;
+
+// Small input box for typing the day to jump to
+#[derive(Default)]
+pub struct GotoPrompt {
+    input: String,
+    error: Option<String>,
+}
+
+// What the prompt wants the app to do after a key press
+pub enum GotoOutcome {
+    Pending,
+    Jump(NaiveDate),
+    Close,
+}
+
+impl GotoPrompt {
+    pub fn handle_key(&mut self, key: KeyEvent) -> GotoOutcome {
+        match key.code {
+            KeyCode::Char(c) if c.is_ascii_digit() || c == '-' => {
+                self.input.push(c);
+                self.error = None;
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+                self.error = None;
+            }
+            KeyCode::Enter => match NaiveDate::parse_from_str(self.input.trim(), "%Y-%m-%d") {
+                Ok(date) => return GotoOutcome::Jump(date),
+                Err(_) => self.error = Some("Not a date, use YYYY-MM-DD".to_string()),
+            },
+            KeyCode::Esc => return GotoOutcome::Close,
+            _ => {}
+        }
+        GotoOutcome::Pending
+    }
+}
+
+pub fn ui(prompt: &GotoPrompt, theme: &Theme, f: &mut Frame, area: Rect) {
+    let width = PROMPT_WIDTH.min(area.width);
+    let height = 4.min(area.height);
+    let spot = Rect { x: area.x + (area.width - width) / 2, y: area.y + (area.height - height) / 2, width, height };
+
+    let error = match &prompt.error {
+        Some(error) => Line::styled(error.as_str(), theme.bad),
+        None => Line::styled("YYYY-MM-DD", theme.muted),
+    };
+    let lines = vec![Line::from(prompt.input.as_str()), error];
+
+    f.render_widget(Clear, spot);
+    f.render_widget(Paragraph::new(lines).block(theme.block("Go to date (esc: cancel)")), spot);
+    f.set_cursor(spot.x + 1 + prompt.input.chars().count() as u16, spot.y + 1);
+}
@@ -0,0 +1,137 @@
+use crate::{clock, ids};
+use crate::{Game, Team};
+use serde::{Deserialize, Serialize};
+
+// How the scoreboard puts the games of a day under headers
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Grouping {
+    #[default]
+    None,
+    Conference,
+    Division,
+    StartTime,
+    // live games first, then upcoming ones, then finals
+    Status,
+}
+
+impl Grouping {
+    pub fn next(self) -> Grouping {
+        match self {
+            Grouping::None => Grouping::Conference,
+            Grouping::Conference => Grouping::Division,
+            Grouping::Division => Grouping::StartTime,
+            Grouping::StartTime => Grouping::Status,
+            Grouping::Status => Grouping::None,
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Grouping::None => "none",
+            Grouping::Conference => "conference",
+            Grouping::Division => "division",
+            Grouping::StartTime => "start time",
+            Grouping::Status => "status",
+        }
+    }
+
+    // Header a game goes under and the rank of that header, None when the league
+    // does not say where its teams play or when the game starts
+    fn group(self, game: &Game) -> Option<(i64, String)> {
+        let (side, between): (fn(&Team) -> &str, &str) = match self {
+            Grouping::None => return None,
+            Grouping::Conference => (|team| team.conference.trim(), "Interconference"),
+            Grouping::Division => (|team| team.division.trim(), "Interdivision"),
+            // in the user's time zone and in order of time, also past midnight
+            Grouping::StartTime => {
+                let start = clock::starts_at(game)?;
+                return Some((start.timestamp(), start.format("%H:%M").to_string()));
+            }
+            Grouping::Status if game.is_live() => return Some((0, "Live".to_string())),
+            Grouping::Status if game.is_final() => return Some((2, "Final".to_string())),
+            Grouping::Status => return Some((1, "Upcoming".to_string())),
+        };
+        let (home, visitor) = (side(&game.home_team), side(&game.visitor_team));
+        if home.is_empty() || visitor.is_empty() {
+            return None;
+        }
+        // matchups between groups go last
+        Some(if home == visitor { (0, home.to_string()) } else { (1, between.to_string()) })
+    }
+
+    // Index of the first game of every group with its header
+    pub fn headers(self, games: &[Game]) -> Vec<(usize, String)> {
+        let mut headers: Vec<(usize, String)> = Vec::new();
+        for (index, game) in games.iter().enumerate() {
+            if let Some((_, label)) = self.group(game) {
+                if headers.last().is_none_or(|(_, last)| *last != label) {
+                    headers.push((index, label));
+                }
+            }
+        }
+        headers
+    }
+
+    // Games of a group next to each other, groups in the order of their rank and
+    // header. The order within a group is kept.
+    pub fn arrange(self, games: &mut [Game]) {
+        if self == Grouping::None {
+            return;
+        }
+        games.sort_by_cached_key(|game| {
+            let group = self.group(game);
+            (group.is_none(), group)
+        });
+    }
+}
+
+// Order of the games of a day, within their group when they are grouped
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub enum GameSort {
+    #[default]
+    StartTime,
+    // live games first, then upcoming ones, then finals
+    Status,
+    // closest games first, games that did not start last
+    Margin,
+    // games of the favorite team first
+    Favorites,
+}
+
+impl GameSort {
+    const ALL: [GameSort; 4] = [GameSort::StartTime, GameSort::Status, GameSort::Margin, GameSort::Favorites];
+
+    pub fn cycle(self, delta: isize) -> GameSort {
+        let index = GameSort::ALL.iter().position(|sort| *sort == self).unwrap_or_default() as isize + delta;
+        GameSort::ALL[index.rem_euclid(GameSort::ALL.len() as isize) as usize]
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            GameSort::StartTime => "time",
+            GameSort::Status => "status",
+            GameSort::Margin => "margin",
+            GameSort::Favorites => "favorites",
+        }
+    }
+
+    // Stable, ties stay in start time order
+    pub fn sort(self, games: &mut [Game], league: &str, favorite: Option<&str>) {
+        // games without a start time go last
+        games.sort_by_cached_key(|game| {
+            let start = clock::starts_at(game);
+            (start.is_none(), start)
+        });
+        match self {
+            GameSort::StartTime => {}
+            GameSort::Status => games.sort_by_key(|game| (!game.is_live(), game.is_final())),
+            GameSort::Margin => {
+                games.sort_by_key(|game| (game.period == 0, game.home_team_score.abs_diff(game.visitor_team_score)))
+            }
+            GameSort::Favorites => games.sort_by_key(|game| {
+                !favorite.is_some_and(|team| ids::plays(league, team, game))
+            }),
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use crate::theme::Theme;
+use crate::{archive, Game, Team};
+use ratatui::{
+    prelude::{Alignment, Constraint, Frame, Line, Rect},
+    widgets::{Cell, Paragraph, Row, Scrollbar, ScrollbarOrientation, ScrollbarState, Table, TableState},
+};
+use std::cmp::Ordering;
+
+// Earlier meetings of the two teams of a game, newest first, opened from the games
+pub struct HeadToHead {
+    pub home: Team,
+    pub visitor: Team,
+    // meetings of the game's season, the series is tallied from them
+    pub season: Vec<Game>,
+    // meetings of earlier seasons from the archive
+    pub earlier: Vec<Game>,
+    pub state: TableState,
+}
+
+impl HeadToHead {
+    // Finished games between the teams played before the game
+    pub fn new(game: &Game, season: Vec<Game>, earlier: Vec<Game>) -> HeadToHead {
+        let meetings = |games: Vec<Game>| {
+            let mut games: Vec<Game> = games
+                .into_iter()
+                .filter(|other| other.id != game.id && other.is_final() && other.date < game.date && meets(game, other))
+                .collect();
+            games.sort_by(|a, b| b.date.cmp(&a.date));
+            games.dedup_by_key(|other| other.id);
+            games
+        };
+        let (season, earlier) = (meetings(season), meetings(earlier));
+        let selected = (!season.is_empty() || !earlier.is_empty()).then_some(0);
+        HeadToHead {
+            home: game.home_team.clone(),
+            visitor: game.visitor_team.clone(),
+            season,
+            earlier,
+            state: TableState::default().with_selected(selected),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.season.len() + self.earlier.len()
+    }
+
+    pub fn select(&mut self, delta: isize) {
+        if self.len() == 0 {
+            return;
+        }
+        let selected = self.state.selected().unwrap_or_default() as isize + delta;
+        self.state.select(Some(selected.clamp(0, self.len() as isize - 1) as usize));
+    }
+
+    // Wins of the game's home team, of the visitors and draws in the season's meetings
+    pub fn series(&self) -> (usize, usize, usize) {
+        let mut series = (0, 0, 0);
+        for game in &self.season {
+            let home_scored = match game.home_team.abbreviation == self.home.abbreviation {
+                true => (game.home_team_score, game.visitor_team_score),
+                false => (game.visitor_team_score, game.home_team_score),
+            };
+            match home_scored.0.cmp(&home_scored.1) {
+                Ordering::Greater => series.0 += 1,
+                Ordering::Less => series.1 += 1,
+                Ordering::Equal => series.2 += 1,
+            }
+        }
+        series
+    }
+}
+
+// Both games are between the same two teams, whoever was at home
+fn meets(game: &Game, other: &Game) -> bool {
+    let teams = |game: &Game| {
+        let mut teams = [game.home_team.abbreviation.clone(), game.visitor_team.abbreviation.clone()];
+        teams.sort();
+        teams
+    };
+    teams(game) == teams(other)
+}
+
+pub fn ui(h2h: &mut HeadToHead, theme: &Theme, f: &mut Frame, area: Rect) {
+    let (home, visitor, draws) = h2h.series();
+    let mut series = format!("season series {} {}, {} {}", h2h.home.abbreviation, home, h2h.visitor.abbreviation, visitor);
+    if draws > 0 {
+        series.push_str(&format!(", {} drawn", draws));
+    }
+    let title = format!("{} vs {}: {} (back: esc)", h2h.home.full_name, h2h.visitor.full_name, series);
+    let block = theme.block(title);
+
+    if h2h.len() == 0 {
+        f.render_widget(Paragraph::new("No earlier meetings.").block(block), area);
+        return;
+    }
+
+    // meetings of earlier seasons are dimmed below this season's
+    let row = |game: &Game| {
+        Row::new(vec![
+            Cell::from(archive::game_date(game).map(|date| date.format("%Y-%m-%d").to_string()).unwrap_or_default()),
+            Cell::from(Line::from(game.home_team.full_name.clone()).alignment(Alignment::Right)),
+            Cell::from(Line::from(format!("{} - {}", game.home_team_score, game.visitor_team_score)).alignment(Alignment::Center)),
+            Cell::from(game.visitor_team.full_name.clone()),
+        ])
+    };
+    let rows: Vec<Row> = h2h.season.iter().map(row).chain(h2h.earlier.iter().map(|game| row(game).style(theme.muted))).collect();
+
+    let widths = [Constraint::Length(10), Constraint::Fill(1), Constraint::Length(9), Constraint::Fill(1)];
+    let table = Table::new(rows, widths)
+        .header(Row::new(vec!["Date", "Home", "Score", "Away"]).style(theme.header))
+        .column_spacing(2)
+        .highlight_style(theme.selected)
+        .block(block);
+    f.render_stateful_widget(table, area, &mut h2h.state);
+
+    if theme.plain {
+        return;
+    }
+    let mut scrollbar = ScrollbarState::new(h2h.len()).position(h2h.state.selected().unwrap_or_default());
+    f.render_stateful_widget(Scrollbar::new(ScrollbarOrientation::VerticalRight), area, &mut scrollbar);
+}
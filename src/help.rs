@@ -0,0 +1,25 @@
+use crate::theme::Theme;
+use ratatui::{
+    prelude::{Constraint, Frame, Rect},
+    widgets::{Clear, Row, Table},
+};
+
+// Width of the key column, longer key lists are cut
+const KEYS_WIDTH: u16 = 14;
+
+// Centered popup listing the keybindings as (keys, description) pairs
+pub fn ui(bindings: &[(String, String)], theme: &Theme, f: &mut Frame, area: Rect) {
+    let longest = bindings.iter().map(|(_, description)| description.chars().count()).max().unwrap_or_default() as u16;
+    let width = (KEYS_WIDTH + longest + 4).min(area.width);
+    let height = (bindings.len() as u16 + 3).min(area.height);
+    let spot = Rect { x: area.x + (area.width - width) / 2, y: area.y + (area.height - height) / 2, width, height };
+
+    let rows = bindings.iter().map(|(keys, description)| Row::new(vec![keys.clone(), description.clone()]));
+    let table = Table::new(rows, [Constraint::Length(KEYS_WIDTH), Constraint::Fill(1)])
+        .header(Row::new(vec!["Key", "Action"]).style(theme.header))
+        .column_spacing(1)
+        .block(theme.block("Help (close: ? or esc)"));
+
+    f.render_widget(Clear, spot);
+    f.render_widget(table, spot);
+}
@@ -0,0 +1,69 @@
+use crate::config::home_dir;
+use anyhow::{anyhow, Context, Result};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
+
+// One recorded command line, without the program name
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Entry {
+    // local time the command was run, YYYY-MM-DD HH:MM
+    pub at: String,
+    pub args: Vec<String>,
+}
+
+impl Entry {
+    // The arguments as they would be typed, quoted where they contain spaces
+    pub fn command_line(&self) -> String {
+        let args: Vec<String> = self
+            .args
+            .iter()
+            .map(|arg| if arg.contains(char::is_whitespace) || arg.is_empty() { format!("{:?}", arg) } else { arg.clone() })
+            .collect();
+        args.join(" ")
+    }
+}
+
+// $XDG_STATE_HOME/rust_sports_cli/history, falling back to ~/.local/state
+pub fn history_path() -> PathBuf {
+    let base = std::env::var_os("XDG_STATE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home_dir().join(".local").join("state"));
+
+    base.join("rust_sports_cli").join("history")
+}
+
+// Append a command line as one JSON line, the file is never rewritten
+pub fn record(args: Vec<String>) -> Result<()> {
+    let path = history_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).with_context(|| format!("Could not create directory {}", parent.display()))?;
+    }
+    let mut file = std::fs::File::options()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Could not open history file {}", path.display()))?;
+    writeln!(file, "{}", serde_json::to_string(&Entry { at: Local::now().format("%Y-%m-%d %H:%M").to_string(), args })?)?;
+    Ok(())
+}
+
+// Recorded command lines, oldest first. Unreadable lines are skipped.
+pub fn load() -> Vec<Entry> {
+    std::fs::read_to_string(history_path())
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+// Command line number n as listed by `sports history`, counting from 1
+pub fn get(n: usize) -> Result<Entry> {
+    let entries = load();
+    let count = entries.len();
+    n.checked_sub(1)
+        .and_then(|index| entries.into_iter().nth(index))
+        .ok_or_else(|| anyhow!("No history entry {}, there are {}", n, count))
+}
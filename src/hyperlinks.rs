@@ -0,0 +1,80 @@
+use crate::config::Config;
+use crate::decorator::Link;
+use crossterm::{
+    cursor::MoveTo,
+    queue,
+    style::{Attribute, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor},
+};
+use ratatui::{buffer::Buffer, layout::Rect, style::Style};
+use std::io::Write;
+
+// Labels made clickable with OSC 8 hyperlinks. The ui draws the labels as text and
+// asks for them here, once the frame is drawn they are written again wrapped in the
+// escape sequences, which ratatui would count as wide text.
+pub struct Hyperlinks {
+    // lines of the last frame that show the labels of the links
+    pub wanted: Vec<(Rect, Vec<Link>)>,
+    // where the labels ended up, with the style they were drawn in
+    placed: Vec<(u16, u16, Link, Style)>,
+}
+
+impl Hyperlinks {
+    // None unless hyperlinks are turned on or the terminal is known to support them
+    pub fn detect(config: &Config) -> Option<Hyperlinks> {
+        config.hyperlinks.unwrap_or_else(supported).then(|| Hyperlinks { wanted: Vec::new(), placed: Vec::new() })
+    }
+
+    // Find the labels in the finished frame, labels cut off or covered by a popup are left out
+    pub fn locate(&mut self, buffer: &Buffer) {
+        self.placed.clear();
+        for (line, links) in &self.wanted {
+            let y = line.y;
+            let cells: Vec<&str> = (line.left()..line.right()).map(|x| buffer.get(x, y).symbol()).collect();
+            let mut from = 0;
+            for link in links {
+                let label: Vec<String> = link.label.chars().map(String::from).collect();
+                if label.is_empty() || label.len() > cells.len() {
+                    continue;
+                }
+                let found = (from..=cells.len() - label.len()).find(|start| {
+                    label.iter().enumerate().all(|(offset, symbol)| cells[start + offset] == symbol)
+                });
+                let Some(start) = found else {
+                    continue;
+                };
+                let x = line.x + start as u16;
+                self.placed.push((x, y, link.clone(), buffer.get(x, y).style()));
+                from = start + label.len();
+            }
+        }
+    }
+
+    // Write the located labels again as hyperlinks, after the frame was drawn. Cells that
+    // ratatui redraws lose the link, so this runs after every frame.
+    pub fn place(&self) -> std::io::Result<()> {
+        let mut out = std::io::stderr();
+        for (x, y, link, style) in &self.placed {
+            queue!(out, MoveTo(*x, *y), SetAttribute(Attribute::Underlined))?;
+            if let Some(fg) = style.fg {
+                queue!(out, SetForegroundColor(fg.into()))?;
+            }
+            if let Some(bg) = style.bg {
+                queue!(out, SetBackgroundColor(bg.into()))?;
+            }
+            write!(out, "\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", link.url, link.label)?;
+            queue!(out, ResetColor, SetAttribute(Attribute::Reset))?;
+        }
+        out.flush()
+    }
+}
+
+// Terminals that announce themselves and are known to open OSC 8 links
+fn supported() -> bool {
+    let var = |name: &str| std::env::var(name).unwrap_or_default();
+    let program = ["iTerm.app", "WezTerm", "vscode", "ghostty", "Hyper"].contains(&var("TERM_PROGRAM").as_str());
+    let term = ["kitty", "alacritty", "foot"].iter().any(|name| var("TERM").contains(name));
+    let vte = var("VTE_VERSION").parse::<u32>().is_ok_and(|version| version >= 5000);
+    let windows = std::env::var_os("WT_SESSION").is_some();
+    let konsole = std::env::var_os("KONSOLE_VERSION").is_some();
+    program || term || vte || windows || konsole
+}
@@ -0,0 +1,67 @@
+use crate::archive;
+use crate::clock;
+use crate::Game;
+use chrono::{Duration, Utc};
+
+// Games have no end time, a calendar entry blocks this long
+const GAME_HOURS: i64 = 3;
+
+// Upcoming games of the league as an iCalendar file. Start times are written in UTC
+// so calendars show them in their own time zone, games without one are all-day events.
+pub fn calendar(league: &str, cache_key: &str, games: &[Game]) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//rust_sports_cli//sports//EN".to_string(),
+        format!("X-WR-CALNAME:{}", text(league)),
+    ];
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    for game in games {
+        // games without a day can not be placed in a calendar
+        let times = match (clock::starts_at(game), archive::game_date(game)) {
+            (Some(start), _) => {
+                let start = start.with_timezone(&Utc);
+                vec![
+                    format!("DTSTART:{}", start.format("%Y%m%dT%H%M%SZ")),
+                    format!("DTEND:{}", (start + Duration::hours(GAME_HOURS)).format("%Y%m%dT%H%M%SZ")),
+                ]
+            }
+            (None, Some(date)) => vec![
+                format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")),
+                format!("DTEND;VALUE=DATE:{}", (date + Duration::days(1)).format("%Y%m%d")),
+            ],
+            (None, None) => continue,
+        };
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}-{}@rust_sports_cli", cache_key, game.id));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.extend(times);
+        lines.push(format!("SUMMARY:{}", text(&format!("{} at {}", game.visitor_team.full_name, game.home_team.full_name))));
+        lines.push(format!("DESCRIPTION:{}", text(&format!("{} {} @ {}", league, game.visitor_team.abbreviation, game.home_team.abbreviation))));
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+    lines.iter().map(|line| fold(line)).collect()
+}
+
+// Escape the characters with a meaning in property values
+fn text(value: &str) -> String {
+    value.replace('\\', "\\\\").replace(';', "\\;").replace(',', "\\,").replace('\n', "\\n")
+}
+
+// Lines longer than 75 bytes continue on the next line after a space, as RFC 5545 asks.
+// Lines end with CRLF.
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
@@ -0,0 +1,120 @@
+use crate::config::home_dir;
+use crate::players::Player;
+use crate::{Game, Team};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+// Teams and players of every league as the providers know them. The same team has
+// other ids and abbreviations at other providers, e.g. balldontlie's GSW is ESPN's
+// GS, the full name is what they agree on. Learned from every fetch and kept
+// between runs, so a favorite team matches whichever provider sent the game.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct Ids {
+    // by league id, then by the normalized full name
+    pub teams: BTreeMap<String, BTreeMap<String, Entity>>,
+    pub players: BTreeMap<String, BTreeMap<String, Entity>>,
+}
+
+// One team or player with everything it was seen as
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct Entity {
+    pub name: String,
+    // the ids of different providers may clash, they only tell what was seen
+    pub ids: BTreeSet<u32>,
+    // of a team, or of the teams a player was listed with
+    pub abbreviations: BTreeSet<String>,
+}
+
+// $XDG_STATE_HOME/rust_sports_cli/ids.json, falling back to ~/.local/state
+pub fn ids_path() -> PathBuf {
+    let base = std::env::var_os("XDG_STATE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home_dir().join(".local").join("state"));
+
+    base.join("rust_sports_cli").join("ids.json")
+}
+
+// A missing or unreadable table starts empty and is learned again
+pub fn load() -> Ids {
+    std::fs::read_to_string(ids_path()).ok().and_then(|text| serde_json::from_str(&text).ok()).unwrap_or_default()
+}
+
+fn save(ids: &Ids) -> Result<()> {
+    let path = ids_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).with_context(|| format!("Could not create directory {}", parent.display()))?;
+    }
+    std::fs::write(&path, serde_json::to_string_pretty(ids)?).with_context(|| format!("Could not write {}", path.display()))
+}
+
+fn table() -> &'static Mutex<Ids> {
+    static TABLE: OnceLock<Mutex<Ids>> = OnceLock::new();
+    TABLE.get_or_init(|| Mutex::new(load()))
+}
+
+// Lowercase letters and digits, e.g. "LA Clippers" and "L.A. Clippers" are one team
+fn key(name: &str) -> String {
+    name.chars().filter(char::is_ascii_alphanumeric).map(|c| c.to_ascii_lowercase()).collect()
+}
+
+// Add what was not seen yet, the file is only written when something is new
+fn learn(kind: fn(&mut Ids) -> &mut BTreeMap<String, BTreeMap<String, Entity>>, league: &str, seen: Vec<(&str, u32, &str)>) {
+    let Ok(mut ids) = table().lock() else {
+        return;
+    };
+    let mut changed = false;
+    for (name, id, abbreviation) in seen {
+        if key(name).is_empty() {
+            continue;
+        }
+        let entity = kind(&mut ids).entry(league.to_string()).or_default().entry(key(name)).or_default();
+        if entity.name.is_empty() {
+            entity.name = name.to_string();
+        }
+        changed |= entity.ids.insert(id);
+        if !abbreviation.is_empty() {
+            changed |= entity.abbreviations.insert(abbreviation.to_string());
+        }
+    }
+    // a table that can not be written is still used for this run
+    if changed {
+        let _ = save(&ids);
+    }
+}
+
+pub fn learn_teams<'a>(league: &str, teams: impl IntoIterator<Item = &'a Team>) {
+    let seen = teams.into_iter().map(|team| (team.full_name.as_str(), team.id, team.abbreviation.as_str())).collect();
+    learn(|ids| &mut ids.teams, league, seen);
+}
+
+pub fn learn_games(league: &str, games: &[Game]) {
+    learn_teams(league, games.iter().flat_map(|game| [&game.home_team, &game.visitor_team]));
+}
+
+pub fn learn_players(league: &str, players: &[Player]) {
+    let seen = players.iter().map(|player| (player.name.as_str(), player.id, player.team.as_str())).collect();
+    learn(|ids| &mut ids.players, league, seen);
+}
+
+// The team is the one with the abbreviation, at any provider of the league
+pub fn is(league: &str, abbreviation: &str, team: &Team) -> bool {
+    if team.abbreviation.eq_ignore_ascii_case(abbreviation) {
+        return true;
+    }
+    let Ok(ids) = table().lock() else {
+        return false;
+    };
+    let entity = ids.teams.get(league).and_then(|teams| teams.get(&key(&team.full_name)));
+    entity.is_some_and(|entity| entity.abbreviations.iter().any(|known| known.eq_ignore_ascii_case(abbreviation)))
+}
+
+// The team with the abbreviation plays the game, at home or away
+pub fn plays(league: &str, abbreviation: &str, game: &Game) -> bool {
+    is(league, abbreviation, &game.home_team) || is(league, abbreviation, &game.visitor_team)
+}
@@ -0,0 +1,544 @@
+use crate::app::{jump_to, jump_to_live, load_calendar_month, open_details, open_head_to_head, open_player, open_schedule, reload_if_moved, season_label, set_providers, show_view, switch_league, toggle_matrix, toggle_tournament, warm_cache, App, View};
+use crate::background::refresh;
+use crate::calendar::{Calendar, CalendarOutcome};
+use crate::goto::{GotoOutcome, GotoPrompt};
+use crate::keymap::Action;
+use crate::palette::{Palette, PaletteCommand, PaletteOutcome};
+use crate::setup::SetupOutcome;
+use crate::theme::Theme;
+use crate::{config, export, provider, scroll};
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use crossterm::event::{self, Event, Event::Key, Event::Mouse, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use std::path::PathBuf;
+use std::time::Instant;
+
+// App update function
+pub fn update(app: &mut App) -> Result<()> {
+    if event::poll(std::time::Duration::from_millis(250))? {
+        handle_event(app, event::read()?)?;
+    }
+    Ok(())
+}
+
+// One terminal event, read apart from it so tests can drive the app without a terminal
+fn handle_event(app: &mut App, event: Event) -> Result<()> {
+    if let Mouse(mouse) = event {
+        handle_mouse(app, mouse);
+    }
+    if let Key(key) = event {
+        if key.kind == event::KeyEventKind::Press {
+            if let Some(setup) = &mut app.setup {
+                match setup.handle_key(key) {
+                    SetupOutcome::Submit(api_key) => submit_api_key(app, api_key)?,
+                    SetupOutcome::Cancel => app.should_quit = true,
+                    SetupOutcome::Pending => {}
+                }
+                return Ok(());
+            }
+
+            if app.help {
+                if matches!(key.code, KeyCode::Esc | KeyCode::Char('?')) {
+                    app.help = false;
+                }
+                return Ok(());
+            }
+
+            if let Some(goto) = &mut app.goto {
+                match goto.handle_key(key) {
+                    GotoOutcome::Jump(date) => {
+                        app.goto = None;
+                        jump_to(app, date);
+                    }
+                    GotoOutcome::Close => app.goto = None,
+                    GotoOutcome::Pending => {}
+                }
+                return Ok(());
+            }
+
+            if let Some(calendar) = &mut app.calendar {
+                match calendar.handle_key(key) {
+                    CalendarOutcome::Jump(date) => {
+                        app.calendar = None;
+                        jump_to(app, date);
+                    }
+                    CalendarOutcome::Close => app.calendar = None,
+                    CalendarOutcome::Pending => load_calendar_month(app),
+                }
+                return Ok(());
+            }
+
+            if let Some(palette) = &mut app.palette {
+                match palette.handle_key(key) {
+                    PaletteOutcome::Run(command) => {
+                        app.palette = None;
+                        let shown = (app.day, app.league, app.provider().cache_key());
+                        run_palette_command(app, command);
+                        reload_if_moved(app, shown);
+                    }
+                    PaletteOutcome::Close => app.palette = None,
+                    PaletteOutcome::Pending => {}
+                }
+                return Ok(());
+            }
+
+            // the player search takes typed text, only tab switching and esc reach the keymap
+            if app.view == View::Players && !matches!(key.code, KeyCode::Tab | KeyCode::BackTab | KeyCode::Esc) {
+                if app.players.handle_key(key) {
+                    open_player(app);
+                }
+                return Ok(());
+            }
+
+            // digits make up the count of the next motion, '0' only continues one
+            if let Some(digit) = key_digit(key).filter(|digit| *digit > 0 || app.count.is_some()) {
+                let count = app.count.map_or(0, |(count, _)| count);
+                app.count = Some((count.saturating_mul(10).saturating_add(digit), Instant::now()));
+                return Ok(());
+            }
+
+            let action = app.keymap.resolve(app.provider().id(), key);
+            let count = match app.count {
+                Some((count, _)) if takes_count(action) => {
+                    app.count = None;
+                    count
+                }
+                Some(_) => {
+                    flush_count(app);
+                    1
+                }
+                None => 1,
+            };
+            run_action(app, action, count);
+        }
+    }
+    Ok(())
+}
+
+// Run the action of a key, `count` times where that makes sense
+fn run_action(app: &mut App, action: Option<Action>, count: usize) {
+    // only refetch when a different day or set of games is asked for
+    let shown = (app.day, app.league, app.provider().cache_key());
+
+    match action {
+        Some(Action::NextWeek) => app.day += Duration::days(7 * count as i64),
+        Some(Action::NextDay) => app.day += Duration::days(count as i64),
+        Some(Action::PrevDay) => app.day -= Duration::days(count as i64),
+        Some(Action::PrevWeek) => app.day -= Duration::days(7 * count as i64),
+        Some(Action::Today) => {
+            app.jumps.record(app.day);
+            app.day = Utc::now();
+        }
+        Some(Action::JumpToLive) => jump_to_live(app),
+        Some(Action::JumpBack) => {
+            if let Some(day) = app.jumps.back(app.day) {
+                app.day = day;
+            }
+        }
+        Some(Action::JumpForward) => {
+            if let Some(day) = app.jumps.forward(app.day) {
+                app.day = day;
+            }
+        }
+        Some(Action::SelectNext) => select(app, count as isize),
+        Some(Action::SelectPrev) => select(app, -(count as isize)),
+        Some(Action::OpenDetails) if app.view == View::Games => open_details(app),
+        Some(Action::OpenDetails) if app.view == View::Teams => open_schedule(app),
+        Some(Action::OpenDetails) => {}
+        Some(Action::Back) => {
+            if app.details.is_none() && app.tournament.is_none() && app.head_to_head.is_none() && app.matrix.is_none() && app.teams.schedule.is_none() {
+                app.view = View::Games;
+            }
+            app.details = None;
+            app.tournament = None;
+            app.head_to_head = None;
+            app.matrix = None;
+            app.teams.schedule = None;
+            app.opening = None;
+        }
+        Some(Action::ScrollDown) => scroll(app, scroll::STEP as isize),
+        Some(Action::ScrollUp) => scroll(app, -(scroll::STEP as isize)),
+        Some(Action::View(view)) => show_view(app, view),
+        Some(Action::NextView) => show_view(app, app.view.cycle(1)),
+        Some(Action::PrevView) => show_view(app, app.view.cycle(-1)),
+        Some(Action::SortNext) => sort(app, 1),
+        Some(Action::SortPrev) => sort(app, -1),
+        Some(Action::SortReverse) => reverse_sort(app),
+        Some(Action::SwitchTeam) => {
+            if let Some(details) = app.details.as_mut().filter(|_| app.view == View::Games) {
+                details.switch_team();
+            }
+        }
+        Some(Action::ShotChart) => {
+            if let Some(details) = app.details.as_mut().filter(|_| app.view == View::Games) {
+                if details.shots.is_empty() {
+                    app.toasts.info("No shot locations available for this game.");
+                } else {
+                    details.toggle_shots();
+                }
+            }
+        }
+        Some(Action::Tournament) => toggle_tournament(app),
+        Some(Action::HeadToHead) if app.view == View::Games => open_head_to_head(app),
+        Some(Action::HeadToHead) if app.view == View::Standings => toggle_matrix(app),
+        Some(Action::HeadToHead) => {}
+        Some(Action::Graphics) => app.graphics = !app.graphics,
+        Some(Action::Cards) => app.cards = !app.cards,
+        Some(Action::Spoilers) => {
+            app.spoilers.toggle();
+            app.toasts.info(if app.spoilers.hidden { "Scores hidden" } else { "Scores shown" });
+        }
+        Some(Action::Reveal) if app.view == View::Games => {
+            let selected = app.games_state.selected().and_then(|index| app.game_data.as_ref()?.data.get(index));
+            if let Some(game) = app.details.as_ref().map(|details| &details.game).or(selected) {
+                app.spoilers.reveal(game);
+            }
+        }
+        Some(Action::Reveal) => {}
+        Some(Action::Group) => {
+            app.grouping = app.grouping.next();
+            app.rearrange_games();
+            app.toasts.info(format!("Grouped by {}", app.grouping.title()));
+        }
+        Some(Action::NextLeague) => switch_league(app, (app.league + 1) % app.providers.len()),
+        Some(Action::Calendar) => {
+            app.tournament = None;
+            app.calendar = Some(Calendar::new(app.day.date_naive()));
+            load_calendar_month(app);
+        }
+        Some(Action::GoToDate) => {
+            app.tournament = None;
+            app.goto = Some(GotoPrompt::default());
+        }
+        Some(Action::Palette) => app.palette = Some(open_palette(app)),
+        Some(Action::WarmCache) => warm_cache(app),
+        Some(Action::CancelJob) if app.view == View::Jobs => app.jobs.cancel_selected(),
+        Some(Action::CancelJob) => {}
+        Some(Action::Refresh) => refresh(app, false),
+        Some(Action::RefreshAll) => refresh(app, true),
+        Some(Action::Help) => app.help = true,
+        Some(Action::Quit) => app.should_quit = true,
+        Some(Action::League(action)) => run_league_action(app, action),
+        None => {}
+    }
+
+    // moves over several days can be walked back like jumps
+    let moved_days = matches!(action, Some(Action::NextDay | Action::PrevDay | Action::NextWeek | Action::PrevWeek));
+    if moved_days && count > 1 {
+        app.jumps.record(shown.0);
+    }
+    reload_if_moved(app, shown);
+}
+
+// A count waits this long for its motion, a lone view digit then switches the view
+const COUNT_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(600);
+
+// Actions that take a count prefix, e.g. 5j moves five days
+fn takes_count(action: Option<Action>) -> bool {
+    matches!(
+        action,
+        Some(Action::NextDay | Action::PrevDay | Action::NextWeek | Action::PrevWeek | Action::SelectNext | Action::SelectPrev)
+    )
+}
+
+fn key_digit(key: KeyEvent) -> Option<usize> {
+    match key.code {
+        KeyCode::Char(c) if key.modifiers.difference(KeyModifiers::SHIFT).is_empty() => c.to_digit(10).map(|digit| digit as usize),
+        _ => None,
+    }
+}
+
+// Digits typed before a motion are its count. A single digit without a motion
+// runs the digit's own binding once the count timed out or another key came.
+fn flush_count(app: &mut App) {
+    let Some((count, _)) = app.count.take() else {
+        return;
+    };
+    if let Some(digit) = char::from_digit(count as u32, 10).filter(|_| count < 10) {
+        let action = app.keymap.resolve(app.provider().id(), KeyEvent::from(KeyCode::Char(digit)));
+        run_action(app, action, 1);
+    }
+}
+
+pub fn expire_count(app: &mut App) {
+    if app.count.is_some_and(|(_, at)| at.elapsed() >= COUNT_TIMEOUT) {
+        flush_count(app);
+    }
+}
+
+// Within this time a second click on the same game opens it
+const DOUBLE_CLICK: std::time::Duration = std::time::Duration::from_millis(400);
+
+// The wheel moves the selection of the shown list, a click selects a game and a
+// second click on it opens it. Popups take no mouse input.
+fn handle_mouse(app: &mut App, mouse: MouseEvent) {
+    if app.setup.is_some() || app.help || app.goto.is_some() || app.palette.is_some() || app.calendar.is_some() {
+        return;
+    }
+    match mouse.kind {
+        MouseEventKind::ScrollDown => select(app, 1),
+        MouseEventKind::ScrollUp => select(app, -1),
+        MouseEventKind::Down(MouseButton::Left) if app.view == View::Games => {
+            let position = ratatui::layout::Position { x: mouse.column, y: mouse.row };
+            let Some(index) = app.game_rows.iter().find(|(area, _)| area.contains(position)).map(|(_, index)| *index) else {
+                return;
+            };
+            let double = app.last_click.is_some_and(|(at, last)| last == index && at.elapsed() < DOUBLE_CLICK);
+            app.games_state.select(Some(index));
+            if double {
+                app.last_click = None;
+                open_details(app);
+            } else {
+                app.last_click = Some((Instant::now(), index));
+            }
+        }
+        _ => {}
+    }
+}
+
+// Teams to complete are those of the loaded team list and the shown games
+fn open_palette(app: &App) -> Palette {
+    let mut teams: Vec<String> = app.teams.teams.iter().flatten().map(|team| team.abbreviation.clone()).collect();
+    for game in app.game_data.iter().flat_map(|game_data| &game_data.data) {
+        teams.extend([game.home_team.abbreviation.clone(), game.visitor_team.abbreviation.clone()]);
+    }
+    teams.sort();
+    teams.dedup();
+    let leagues = app.providers.iter().map(|provider| provider.id().to_string()).collect();
+    Palette::new(teams, leagues, Theme::names(&app.config))
+}
+
+fn run_palette_command(app: &mut App, command: PaletteCommand) {
+    match command {
+        PaletteCommand::Date(date) => jump_to(app, date),
+        PaletteCommand::Season(season) => {
+            app.jumps.record(app.day);
+            app.season = season;
+            match season {
+                Some(season) => app.toasts.info(format!("Browsing the {} season", season_label(app.provider(), season))),
+                None => app.toasts.info("Browsing all seasons"),
+            }
+        }
+        PaletteCommand::Team(abbreviation) => {
+            show_view(app, View::Teams);
+            let index = app.teams.teams.iter().flatten().position(|team| team.abbreviation.eq_ignore_ascii_case(&abbreviation));
+            match index {
+                Some(index) => {
+                    app.teams.state.select(Some(index));
+                    open_schedule(app);
+                }
+                None => app.toasts.warning(format!("No {} team {}.", app.provider().name(), abbreviation)),
+            }
+        }
+        PaletteCommand::League(id) => match app.providers.iter().position(|provider| provider.id() == id) {
+            Some(league) => switch_league(app, league),
+            None => app.toasts.warning(format!("Unknown league {}.", id)),
+        },
+        PaletteCommand::ExportCsv(output) => {
+            let Some(game_data) = &app.game_data else {
+                app.toasts.warning("No games to export.");
+                return;
+            };
+            let name = format!("sports-{}-{}.csv", app.provider().id(), app.day.format("%Y-%m-%d"));
+            let output = output.unwrap_or_else(|| PathBuf::from(name));
+            match export::write_csv(app.provider().name(), &game_data.data, &output) {
+                Ok(()) => app.toasts.info(format!("Exported {} games to {}", game_data.data.len(), output.display())),
+                Err(e) => app.toasts.error(format!("{:#}", e)),
+            }
+        }
+        PaletteCommand::Theme(name) => match Theme::named(&name, &app.config) {
+            Ok(theme) => {
+                app.toasts.info(format!("Theme {}", theme.name));
+                app.theme = theme;
+            }
+            Err(e) => app.toasts.warning(format!("{:#}", e)),
+        },
+        PaletteCommand::Quit => app.should_quit = true,
+    }
+}
+
+// Validate the key entered in the setup wizard, persist it and load the scoreboard
+fn submit_api_key(app: &mut App, api_key: String) -> Result<()> {
+    let setup = app.setup.as_mut().expect("setup wizard is active");
+
+    // through the run's client, so --record and --replay cover the wizard too
+    match provider::nba::validate_api_key(&*provider::api::client(), &api_key) {
+        Ok(true) => {
+            app.config.api_key = Some(api_key);
+            if let Err(e) = app.config.save() {
+                setup.error = Some(format!("{:#}", e));
+                return Ok(());
+            }
+            app.config_modified = config::config_modified();
+            app.setup = None;
+            set_providers(app, provider::build_providers(&app.config));
+            app.load_day();
+        }
+        Ok(false) => setup.error = Some("The API key was rejected, please check it and try again.".to_string()),
+        Err(e) => setup.error = Some(format!("Could not validate the API key: {}", e)),
+    }
+    Ok(())
+}
+
+// League actions may change provider settings, which are kept in the config
+fn run_league_action(app: &mut App, action: &str) {
+    let mut config = app.config.clone();
+    let Some(day) = app.providers[app.league].league_action(action, app.day, &mut config) else {
+        return;
+    };
+    app.day = day;
+
+    app.providers = provider::build_providers(&config);
+    app.config = config;
+    match app.config.save() {
+        Ok(()) => app.config_modified = config::config_modified(),
+        Err(e) => app.toasts.error(format!("Config not saved: {:#}", e)),
+    }
+}
+
+// Up and down move the selection of the shown view
+fn select(app: &mut App, delta: isize) {
+    if let Some(h2h) = &mut app.head_to_head {
+        h2h.select(delta);
+        return;
+    }
+    if let Some(matrix) = &mut app.matrix {
+        matrix.scroll_rows(delta);
+        return;
+    }
+    match app.view {
+        View::Games => app.select_game(delta),
+        View::Teams => app.teams.select(delta),
+        View::Jobs => app.jobs.select(delta),
+        View::Standings => scroll(app, delta),
+        View::Players | View::Week => {}
+    }
+}
+
+// Move the rows of the standings, or the focused box score, under their header
+fn scroll(app: &mut App, delta: isize) {
+    if let Some(matrix) = &mut app.matrix {
+        matrix.scroll_rows(delta);
+        return;
+    }
+    match (app.view, &app.standings, &mut app.details) {
+        (View::Standings, Some(standings), _) => {
+            app.standings_scroll = app.standings_scroll.saturating_add_signed(delta).min(standings.len().saturating_sub(1));
+        }
+        (View::Games, _, Some(details)) => details.scroll(delta),
+        _ => {}
+    }
+}
+
+// Left and right sort the standings, or the box score of the opened game
+fn sort(app: &mut App, delta: isize) {
+    if let Some(matrix) = &mut app.matrix {
+        matrix.scroll_columns(delta);
+        return;
+    }
+    match (app.view, &app.standings, &mut app.details) {
+        (View::Standings, Some(standings), _) => app.standings_sort.cycle(standings.columns().len(), delta),
+        (View::Games, _, Some(details)) => details.cycle_sort(delta),
+        (View::Games, _, None) => {
+            app.game_sort = app.game_sort.cycle(delta);
+            app.rearrange_games();
+        }
+        _ => {}
+    }
+}
+
+fn reverse_sort(app: &mut App) {
+    match (app.view, &mut app.details) {
+        (View::Standings, _) => app.standings_sort.reversed ^= true,
+        (View::Games, Some(details)) => details.sort.reversed ^= true,
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::tests::{nba_app, settle};
+    use chrono::{DateTime, TimeZone};
+    use crossterm::event::KeyEventKind;
+    use rust_sports_cli::provider::api::Canned;
+    use std::sync::Arc;
+
+    const GAMES: &str = include_str!("../tests/data/nba/games.json");
+    const STATS: &str = include_str!("../tests/data/nba/stats.json");
+
+    fn day() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 15, 12, 0, 0).unwrap()
+    }
+
+    fn app() -> App {
+        let client = Canned::new()
+            .with("https://api.balldontlie.io/v1/games?dates[]=2024-01-15", GAMES)
+            .with("https://api.balldontlie.io/v1/stats?game_ids[]=1037593&per_page=100", STATS);
+        nba_app(Arc::new(client), day())
+    }
+
+    fn press(app: &mut App, code: KeyCode) {
+        let key = KeyEvent::new_with_kind(code, KeyModifiers::NONE, KeyEventKind::Press);
+        handle_event(app, Key(key)).unwrap();
+    }
+
+    fn selected(app: &App) -> Option<u32> {
+        let index = app.games_state.selected()?;
+        Some(app.game_data.as_ref()?.data.get(index)?.id)
+    }
+
+    #[test]
+    fn opens_and_closes_the_box_score_of_the_selected_game() {
+        let mut app = app();
+        assert_eq!(app.game_data.as_ref().map(|game_data| game_data.data.len()), Some(2));
+        while selected(&app) != Some(1037593) {
+            press(&mut app, KeyCode::Down);
+        }
+
+        press(&mut app, KeyCode::Enter);
+        // fetched in the background, the key is answered right away
+        assert!(app.details.is_none());
+        settle(&mut app);
+        let details = app.details.as_ref().expect("the box score is shown");
+        assert_eq!(details.game.id, 1037593);
+        assert_eq!(details.players.len(), 4);
+
+        press(&mut app, KeyCode::Esc);
+        assert!(app.details.is_none());
+        assert_eq!(app.view, View::Games);
+    }
+
+    #[test]
+    fn a_box_score_left_before_it_came_in_stays_closed() {
+        let mut app = app();
+        while selected(&app) != Some(1037593) {
+            press(&mut app, KeyCode::Down);
+        }
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Esc);
+        settle(&mut app);
+        assert!(app.details.is_none());
+    }
+
+    #[test]
+    fn a_count_moves_several_days_and_starts_loading() {
+        let mut app = app();
+        press(&mut app, KeyCode::Char('3'));
+        assert!(app.count.is_some());
+        press(&mut app, KeyCode::Char('j'));
+        assert_eq!(app.day, day() + Duration::days(3));
+        assert!(app.count.is_none());
+        assert!(app.loading);
+    }
+
+    #[test]
+    fn the_help_swallows_keys_until_closed() {
+        let mut app = app();
+        app.help = true;
+        press(&mut app, KeyCode::Char('j'));
+        assert_eq!(app.day, day());
+        press(&mut app, KeyCode::Char('?'));
+        assert!(!app.help);
+    }
+}
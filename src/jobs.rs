@@ -0,0 +1,156 @@
+use crate::theme::Theme;
+use anyhow::Result;
+use ratatui::{
+    prelude::{Constraint, Frame, Rect},
+    widgets::{Cell, Paragraph, Row, Table, TableState},
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+// Width of the text progress bar in the jobs table
+const BAR_WIDTH: usize = 20;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum JobState {
+    Running,
+    Done,
+    Failed(String),
+    Cancelled,
+}
+
+#[derive(Clone, Debug)]
+struct Status {
+    done: usize,
+    total: usize,
+    state: JobState,
+}
+
+// Handed to the work of a job to report progress and notice cancellation
+pub struct JobContext {
+    status: Arc<Mutex<Status>>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl JobContext {
+    pub fn set_total(&self, total: usize) {
+        if let Ok(mut status) = self.status.lock() {
+            status.total = total;
+        }
+    }
+
+    pub fn advance(&self) {
+        if let Ok(mut status) = self.status.lock() {
+            status.done += 1;
+        }
+    }
+
+    // Work checks this between steps and stops early once it is set
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+}
+
+struct Job {
+    name: String,
+    status: Arc<Mutex<Status>>,
+    cancel: Arc<AtomicBool>,
+    // set once the end of the job was reported to the user
+    reported: bool,
+}
+
+impl Job {
+    fn status(&self) -> Status {
+        self.status.lock().map(|status| status.clone()).unwrap_or(Status { done: 0, total: 0, state: JobState::Running })
+    }
+}
+
+// Long running operations like cache warming or exports, each on its own thread
+#[derive(Default)]
+pub struct Jobs {
+    jobs: Vec<Job>,
+    pub state: TableState,
+}
+
+impl Jobs {
+    pub fn spawn(&mut self, name: impl Into<String>, work: impl FnOnce(&JobContext) -> Result<()> + Send + 'static) {
+        let status = Arc::new(Mutex::new(Status { done: 0, total: 0, state: JobState::Running }));
+        let cancel = Arc::new(AtomicBool::new(false));
+        let context = JobContext { status: status.clone(), cancel: cancel.clone() };
+
+        std::thread::spawn(move || {
+            let state = match work(&context) {
+                Err(e) => JobState::Failed(format!("{:#}", e)),
+                Ok(()) if context.is_cancelled() => JobState::Cancelled,
+                Ok(()) => JobState::Done,
+            };
+            if let Ok(mut status) = context.status.lock() {
+                status.state = state;
+            }
+        });
+
+        self.jobs.push(Job { name: name.into(), status, cancel, reported: false });
+        if self.state.selected().is_none() {
+            self.state.select(Some(0));
+        }
+    }
+
+    pub fn cancel_selected(&mut self) {
+        if let Some(job) = self.state.selected().and_then(|index| self.jobs.get(index)) {
+            job.cancel.store(true, Ordering::Relaxed);
+        }
+    }
+
+    pub fn select(&mut self, delta: isize) {
+        if self.jobs.is_empty() {
+            return;
+        }
+        let selected = self.state.selected().unwrap_or_default() as isize + delta;
+        self.state.select(Some(selected.clamp(0, self.jobs.len() as isize - 1) as usize));
+    }
+
+    // Jobs that ended since the last call, with how they ended
+    pub fn finished(&mut self) -> Vec<(String, JobState)> {
+        let mut finished = Vec::new();
+        for job in &mut self.jobs {
+            let state = job.status().state;
+            if state != JobState::Running && !job.reported {
+                job.reported = true;
+                finished.push((job.name.clone(), state));
+            }
+        }
+        finished
+    }
+}
+
+pub fn ui(jobs: &mut Jobs, theme: &Theme, f: &mut Frame, area: Rect) {
+    let block = theme.block("Jobs (select: up|down, cancel: del)");
+    if jobs.jobs.is_empty() {
+        f.render_widget(Paragraph::new("No jobs yet. Warm the cache of the browsed month with W.").block(block), area);
+        return;
+    }
+
+    let rows = jobs.jobs.iter().map(|job| {
+        let status = job.status();
+        let filled = (status.done * BAR_WIDTH).checked_div(status.total).unwrap_or_default().min(BAR_WIDTH);
+        let progress = match theme.plain {
+            true => format!("{}/{}", status.done, status.total),
+            false => format!("{}{} {}/{}", "█".repeat(filled), "░".repeat(BAR_WIDTH - filled), status.done, status.total),
+        };
+        let (state, style) = match &status.state {
+            JobState::Running if job.cancel.load(Ordering::Relaxed) => ("cancelling".to_string(), theme.warning),
+            JobState::Running => ("running".to_string(), theme.info),
+            JobState::Done => ("done".to_string(), theme.good),
+            JobState::Failed(error) => (format!("failed: {}", error), theme.bad),
+            JobState::Cancelled => ("cancelled".to_string(), theme.warning),
+        };
+        Row::new(vec![Cell::from(job.name.clone()), Cell::from(progress), Cell::from(state).style(style)])
+    });
+
+    let widths = [Constraint::Fill(1), Constraint::Length(BAR_WIDTH as u16 + 12), Constraint::Fill(1)];
+    let table = Table::new(rows, widths)
+        .header(Row::new(vec!["Job", "Progress", "State"]).style(theme.header))
+        .column_spacing(2)
+        .highlight_style(theme.selected)
+        .block(block);
+    f.render_stateful_widget(table, area, &mut jobs.state);
+}
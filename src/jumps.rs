@@ -0,0 +1,36 @@
+use chrono::{DateTime, Utc};
+
+// Most days remembered in each direction
+const MAX_JUMPS: usize = 100;
+
+// Days browsed before a jump, walked back with ctrl-o and forward again with ctrl-i
+#[derive(Default)]
+pub struct JumpList {
+    back: Vec<DateTime<Utc>>,
+    forward: Vec<DateTime<Utc>>,
+}
+
+impl JumpList {
+    // A new jump from the day, the days jumped back from are forgotten
+    pub fn record(&mut self, from: DateTime<Utc>) {
+        if self.back.last() != Some(&from) {
+            self.back.push(from);
+        }
+        if self.back.len() > MAX_JUMPS {
+            self.back.remove(0);
+        }
+        self.forward.clear();
+    }
+
+    pub fn back(&mut self, current: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let day = self.back.pop()?;
+        self.forward.push(current);
+        Some(day)
+    }
+
+    pub fn forward(&mut self, current: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let day = self.forward.pop()?;
+        self.back.push(current);
+        Some(day)
+    }
+}
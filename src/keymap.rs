@@ -0,0 +1,239 @@
+use anyhow::{bail, Result};
+use crate::View;
+use crossterm::event::KeyCode::{self, BackTab, Char, Delete, Down, Enter, Esc, Left, PageDown, PageUp, Right, Tab, Up};
+use crossterm::event::{KeyEvent, KeyModifiers};
+use std::collections::{BTreeMap, HashMap};
+
+// Everything a key press can trigger, in the order the help lists them
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Action {
+    NextDay,
+    PrevDay,
+    NextWeek,
+    PrevWeek,
+    Today,
+    // Walk the days browsed before jumps back and forward again
+    JumpBack,
+    JumpForward,
+    // Go to the live game of a favorite team, or the closest live game of the league
+    JumpToLive,
+    // Pick the day from a month calendar
+    Calendar,
+    // Type the day to jump to
+    GoToDate,
+    SelectNext,
+    SelectPrev,
+    OpenDetails,
+    Back,
+    // Scroll the standings or the focused box score under their header
+    ScrollDown,
+    ScrollUp,
+    // Switch to a tab of the tab bar
+    View(View),
+    NextView,
+    PrevView,
+    // Sort the standings or box score by the next or previous column, or flip the order
+    SortNext,
+    SortPrev,
+    SortReverse,
+    // Move the box score focus to the other team
+    SwitchTeam,
+    // Show or hide the shot chart of the opened game
+    ShotChart,
+    Tournament,
+    // Earlier meetings of the selected game's teams, or the records of all teams against each other
+    HeadToHead,
+    // Put the games under conference, division, start time or status headers, or not at all
+    Group,
+    // Hide or show the scores of started games, or reveal the selected one while hidden
+    Spoilers,
+    Reveal,
+    // Draw the standings ladder and bracket instead of the tables
+    Graphics,
+    // Lay the games out as a grid of cards instead of the table
+    Cards,
+    // Refetch the shown view, or everything loaded for the league
+    Refresh,
+    RefreshAll,
+    // Backfill the browsed month into the disk cache as a background job
+    WarmCache,
+    CancelJob,
+    NextLeague,
+    // Type a command like `date 2024-02-14` or `team BOS`
+    Palette,
+    // Show the keybindings over everything else
+    Help,
+    Quit,
+    // League specific action, handled by the provider that registered it
+    League(&'static str),
+}
+
+// Global bindings plus one binding context per provider. League bindings are
+// only active while their provider is shown and may not shadow a global key.
+pub struct KeyMap {
+    global: HashMap<KeyCode, Action>,
+    // keys pressed with ctrl, e.g. 'o' for ctrl-o
+    control: HashMap<char, Action>,
+    contexts: HashMap<&'static str, HashMap<KeyCode, Action>>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        let global = HashMap::from([
+            (Char('h'), Action::NextWeek),
+            (Char('j'), Action::NextDay),
+            (Char('k'), Action::PrevDay),
+            (Char('l'), Action::PrevWeek),
+            (Char('t'), Action::Today),
+            (Char('n'), Action::JumpToLive),
+            (Char('m'), Action::Calendar),
+            (Char('g'), Action::GoToDate),
+            (Down, Action::SelectNext),
+            (Up, Action::SelectPrev),
+            (Enter, Action::OpenDetails),
+            (Esc, Action::Back),
+            (PageDown, Action::ScrollDown),
+            (PageUp, Action::ScrollUp),
+            (Char('1'), Action::View(View::Games)),
+            (Char('2'), Action::View(View::Standings)),
+            (Char('3'), Action::View(View::Players)),
+            (Char('4'), Action::View(View::Teams)),
+            (Char('5'), Action::View(View::Jobs)),
+            (Char('6'), Action::View(View::Week)),
+            (Tab, Action::NextView),
+            (BackTab, Action::PrevView),
+            (Char('s'), Action::View(View::Standings)),
+            (Right, Action::SortNext),
+            (Left, Action::SortPrev),
+            (Char('o'), Action::SortReverse),
+            (Char('x'), Action::SwitchTeam),
+            (Char('v'), Action::ShotChart),
+            (Char('b'), Action::Tournament),
+            (Char('a'), Action::HeadToHead),
+            (Char('C'), Action::Group),
+            (Char('H'), Action::Spoilers),
+            (Char('e'), Action::Reveal),
+            (Char('G'), Action::Graphics),
+            (Char('V'), Action::Cards),
+            (Char('L'), Action::NextLeague),
+            (Char('r'), Action::Refresh),
+            (Char('R'), Action::RefreshAll),
+            (Char('W'), Action::WarmCache),
+            (Delete, Action::CancelJob),
+            (Char(':'), Action::Palette),
+            (Char('?'), Action::Help),
+            (Char('q'), Action::Quit),
+        ]);
+
+        // terminals send ctrl-i as tab, so jumping forward is on ctrl-n as well
+        let control = HashMap::from([('o', Action::JumpBack), ('i', Action::JumpForward), ('n', Action::JumpForward)]);
+
+        KeyMap { global, control, contexts: HashMap::new() }
+    }
+}
+
+impl KeyMap {
+    pub fn register(&mut self, context: &'static str, key: KeyCode, action: Action) -> Result<()> {
+        if let Some(global) = self.global.get(&key) {
+            bail!("{:?} is already bound to {:?}, it can not be used for {:?} in {}", key, global, action, context);
+        }
+
+        self.contexts.entry(context).or_default().insert(key, action);
+        Ok(())
+    }
+
+    // Every action reachable in a context with all keys bound to it, e.g. ("1|s", View(Standings))
+    pub fn help(&self, context: &str) -> Vec<(String, Action)> {
+        let mut bindings: HashMap<KeyCode, Action> = self.global.clone();
+        if let Some(league) = self.contexts.get(context) {
+            bindings.extend(league);
+        }
+
+        let mut keys: BTreeMap<Action, Vec<String>> = BTreeMap::new();
+        for (key, action) in bindings {
+            keys.entry(action).or_default().push(key_label(key));
+        }
+        for (key, action) in &self.control {
+            keys.entry(*action).or_default().push(format!("C-{}", key));
+        }
+        keys.into_iter()
+            .map(|(action, mut labels)| {
+                labels.sort();
+                (labels.join("|"), action)
+            })
+            .collect()
+    }
+
+    // The active provider's bindings win over the global ones
+    pub fn resolve(&self, context: &str, key: KeyEvent) -> Option<Action> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            return match key.code {
+                Char(c) => self.control.get(&c.to_ascii_lowercase()).copied(),
+                _ => None,
+            };
+        }
+        let key = key.code;
+        self.contexts
+            .get(context)
+            .and_then(|bindings| bindings.get(&key))
+            .or_else(|| self.global.get(&key))
+            .copied()
+    }
+}
+
+impl Action {
+    // What the help says the action does, league actions are described by their provider
+    pub fn description(&self) -> String {
+        let description = match self {
+            Action::NextDay => "one day forward",
+            Action::PrevDay => "one day back",
+            Action::NextWeek => "one week forward",
+            Action::PrevWeek => "one week back",
+            Action::Today => "today",
+            Action::JumpBack => "back to the day before the last jump",
+            Action::JumpForward => "forward again after jumping back",
+            Action::JumpToLive => "live game of a favorite team, or the closest live game",
+            Action::Calendar => "pick a day from the calendar",
+            Action::GoToDate => "go to a date",
+            Action::SelectNext => "select next",
+            Action::SelectPrev => "select previous",
+            Action::OpenDetails => "open game details, team schedule or player",
+            Action::Back => "back",
+            Action::ScrollDown => "scroll the standings or box score down",
+            Action::ScrollUp => "scroll the standings or box score up",
+            Action::View(view) => return format!("show {}", view.title().to_lowercase()),
+            Action::NextView => "next view",
+            Action::PrevView => "previous view",
+            Action::SortNext => "sort games by next order, tables by next column",
+            Action::SortPrev => "sort games by previous order, tables by previous column",
+            Action::SortReverse => "reverse sort order",
+            Action::SwitchTeam => "switch box score team",
+            Action::ShotChart => "show or hide the shot chart",
+            Action::Tournament => "tournament tables and bracket",
+            Action::HeadToHead => "earlier meetings of the selected game's teams, or every team's record against the others in the standings",
+            Action::Group => "group games by conference, division, start time or status",
+            Action::Spoilers => "hide or show scores",
+            Action::Reveal => "reveal the selected game's score",
+            Action::Graphics => "draw ladder and bracket instead of tables",
+            Action::Cards => "show games as cards or as a table",
+            Action::Refresh => "refresh the shown view",
+            Action::RefreshAll => "refresh everything loaded",
+            Action::WarmCache => "warm the cache of the browsed month",
+            Action::CancelJob => "cancel the selected job",
+            Action::NextLeague => "next league",
+            Action::Palette => "command line: date, team, league, export, quit",
+            Action::Help => "show or hide this help",
+            Action::Quit => "quit",
+            Action::League(action) => action,
+        };
+        description.to_string()
+    }
+}
+
+// Short label of a key for the navigation help
+pub fn key_label(key: KeyCode) -> String {
+    match key {
+        Char(c) => c.to_string(),
+        other => format!("{:?}", other),
+    }
+}
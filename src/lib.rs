@@ -0,0 +1,43 @@
+//! Fetching, parsing and storing the games of the leagues the sports TUI shows,
+//! for other programs like bots or dashboards. Every league is a
+//! [`provider::Provider`], [`provider::build_providers`] sets them up from a
+//! [`config::Config`] and [`archive::Archive`] keeps every fetched game on disk.
+//!
+//! ```no_run
+//! let config = rust_sports_cli::config::Config::load()?;
+//! for provider in rust_sports_cli::provider::build_providers(&config) {
+//!     let games = provider.fetch_day(chrono::Utc::now()).map(|day| day.data).unwrap_or_default();
+//!     println!("{}: {} games today", provider.name(), games.len());
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+pub mod alert;
+pub mod archive;
+pub mod cache;
+pub mod clock;
+pub mod config;
+#[cfg(all(feature = "dbus", target_os = "linux"))]
+pub mod dbus;
+pub mod decorator;
+pub mod details;
+pub mod elo;
+pub mod form;
+pub mod grouping;
+pub mod ids;
+pub mod lz;
+pub mod model;
+pub mod notify;
+pub mod players;
+pub mod provider;
+#[cfg(feature = "redis")]
+pub mod redis;
+pub mod scroll;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod standings;
+pub mod store;
+pub mod theme;
+pub mod tournament;
+
+pub use model::{Game, GameData, Meta, Pitcher, Team};
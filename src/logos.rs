@@ -0,0 +1,109 @@
+use crate::cache::cache_dir;
+use crate::config::Config;
+use crate::Team;
+use crossterm::{cursor::MoveTo, queue};
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+const LOGO_URL: &str = "https://a.espncdn.com/i/teamlogos";
+
+// Team logos drawn with the kitty graphics protocol. The terminal reads the PNG
+// itself, so nothing has to be decoded here. Sixel needs decoded pixels and is
+// not supported, those terminals get colored abbreviations instead.
+pub struct Logos {
+    dir: PathBuf,
+    // logos being downloaded, they are drawn once they are on disk
+    pending: Arc<Mutex<HashSet<PathBuf>>>,
+    // where the ui left room for logos in the last frame
+    pub spots: Vec<(Team, u16, u16)>,
+    // what was placed last, images stay on screen until they are deleted
+    shown: Vec<(PathBuf, u16, u16)>,
+}
+
+impl Logos {
+    // None unless logos are turned on and the terminal speaks the kitty protocol
+    pub fn detect(config: &Config) -> Option<Logos> {
+        let kitty = std::env::var_os("KITTY_WINDOW_ID").is_some()
+            || std::env::var("TERM").is_ok_and(|term| term.contains("kitty"))
+            || std::env::var("TERM_PROGRAM").is_ok_and(|program| program == "WezTerm" || program == "ghostty");
+        (config.team_logos && kitty).then(|| Logos { dir: cache_dir(config).join("logos"), pending: Arc::default(), spots: Vec::new(), shown: Vec::new() })
+    }
+
+    // PNG of a team on disk, downloaded in the background the first time it is asked for
+    fn path(&self, league: &str, team: &Team) -> Option<PathBuf> {
+        // ESPN names logos by abbreviation in the US leagues and by team id in soccer
+        let name = match league {
+            "nba" | "nhl" | "mlb" => team.abbreviation.to_lowercase(),
+            "soccer" => team.id.to_string(),
+            _ => return None,
+        };
+        let path = self.dir.join(league).join(format!("{}.png", name));
+        if path.exists() {
+            return Some(path);
+        }
+
+        let mut pending = self.pending.lock().ok()?;
+        if pending.insert(path.clone()) {
+            let url = format!("{}/{}/500/{}.png", LOGO_URL, league, name);
+            std::thread::spawn(move || download(&url, &path));
+        }
+        None
+    }
+
+    // Draw the logos into the spots of the last frame, after it was drawn
+    pub fn place(&mut self, league: &str) -> std::io::Result<()> {
+        let placements: Vec<(PathBuf, u16, u16)> =
+            self.spots.iter().filter_map(|(team, x, y)| Some((self.path(league, team)?, *x, *y))).collect();
+        if placements == self.shown {
+            return Ok(());
+        }
+
+        let mut out = std::io::stderr();
+        // delete every image placed before, then place the new ones two cells wide
+        write!(out, "\x1b_Ga=d,q=2\x1b\\")?;
+        for (path, x, y) in &placements {
+            queue!(out, MoveTo(*x, *y))?;
+            write!(out, "\x1b_Ga=T,f=100,t=f,c=2,r=1,C=1,q=2;{}\x1b\\", base64(path.to_string_lossy().as_bytes()))?;
+        }
+        out.flush()?;
+        self.shown = placements;
+        Ok(())
+    }
+}
+
+// Failed downloads are simply tried again on the next start
+fn download(url: &str, path: &PathBuf) {
+    let Ok(response) = reqwest::blocking::get(url).and_then(|response| response.error_for_status()) else {
+        return;
+    };
+    let Ok(bytes) = response.bytes() else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    // renamed into place so a half written file is never drawn
+    let partial = path.with_extension("partial");
+    if std::fs::write(&partial, &bytes).is_ok() {
+        let _ = std::fs::rename(&partial, path);
+    }
+}
+
+// The protocol takes the file name base64 encoded
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::new();
+    for chunk in bytes.chunks(3) {
+        let value = chunk.iter().enumerate().fold(0u32, |value, (index, byte)| value | (*byte as u32) << (16 - 8 * index));
+        for index in 0..4 {
+            if index <= chunk.len() {
+                encoded.push(ALPHABET[(value >> (18 - 6 * index) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
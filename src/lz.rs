@@ -0,0 +1,151 @@
+// LZ77 compression in the layout of LZ4 blocks, without a dependency. JSON of
+// games repeats team names and keys over and over, which it shrinks a lot.
+//
+// Every sequence is a token with the number of literals in the high and the match
+// length minus four in the low nibble, where 15 continues in bytes of 255 and a
+// last smaller one, the literals, the distance back to the match as two bytes and
+// the rest of the match length. The last sequence has only literals.
+
+const MIN_MATCH: usize = 4;
+
+// Farthest a match can be behind, the distance has to fit into two bytes
+const WINDOW: usize = u16::MAX as usize;
+
+pub fn compress(input: &[u8]) -> Vec<u8> {
+    let mut output = Vec::with_capacity(input.len() / 4);
+    // last position of four bytes by their hash
+    let mut table = vec![usize::MAX; 1 << 16];
+    let (mut pos, mut literals) = (0, 0);
+
+    while pos + MIN_MATCH <= input.len() {
+        let slot = &mut table[hash(&input[pos..pos + MIN_MATCH])];
+        let candidate = std::mem::replace(slot, pos);
+        if candidate == usize::MAX || pos - candidate > WINDOW || input[candidate..candidate + MIN_MATCH] != input[pos..pos + MIN_MATCH] {
+            pos += 1;
+            continue;
+        }
+        let length = MIN_MATCH + input[pos + MIN_MATCH..].iter().zip(&input[candidate + MIN_MATCH..]).take_while(|(a, b)| a == b).count();
+        sequence(&mut output, &input[literals..pos], Some((pos - candidate, length)));
+        pos += length;
+        literals = pos;
+    }
+    sequence(&mut output, &input[literals..], None);
+    output
+}
+
+// None for input that was not compressed by compress
+pub fn decompress(input: &[u8]) -> Option<Vec<u8>> {
+    let mut output = Vec::with_capacity(input.len() * 4);
+    let mut pos = 0;
+    loop {
+        let token = *input.get(pos)?;
+        pos += 1;
+        let literals = length(input, &mut pos, (token >> 4) as usize)?;
+        output.extend_from_slice(input.get(pos..pos + literals)?);
+        pos += literals;
+        if pos == input.len() {
+            return Some(output);
+        }
+
+        let offset = u16::from_le_bytes([*input.get(pos)?, *input.get(pos + 1)?]) as usize;
+        pos += 2;
+        let matched = MIN_MATCH + length(input, &mut pos, (token & 15) as usize)?;
+        if offset == 0 || offset > output.len() {
+            return None;
+        }
+        // byte by byte, a match may overlap what it copies
+        let start = output.len() - offset;
+        for index in start..start + matched {
+            output.push(output[index]);
+        }
+    }
+}
+
+fn hash(bytes: &[u8]) -> usize {
+    let word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    (word.wrapping_mul(2654435761) >> 16) as usize
+}
+
+fn sequence(output: &mut Vec<u8>, literals: &[u8], matched: Option<(usize, usize)>) {
+    let match_length = matched.map_or(0, |(_, length)| length - MIN_MATCH);
+    output.push(((literals.len().min(15) as u8) << 4) | match_length.min(15) as u8);
+    extend_length(output, literals.len());
+    output.extend_from_slice(literals);
+    if let Some((offset, _)) = matched {
+        output.extend_from_slice(&(offset as u16).to_le_bytes());
+        extend_length(output, match_length);
+    }
+}
+
+fn extend_length(output: &mut Vec<u8>, length: usize) {
+    if length < 15 {
+        return;
+    }
+    let mut rest = length - 15;
+    while rest >= 255 {
+        output.push(255);
+        rest -= 255;
+    }
+    output.push(rest as u8);
+}
+
+fn length(input: &[u8], pos: &mut usize, nibble: usize) -> Option<usize> {
+    let mut length = nibble;
+    if nibble == 15 {
+        loop {
+            let byte = *input.get(*pos)?;
+            *pos += 1;
+            length += byte as usize;
+            if byte != 255 {
+                break;
+            }
+        }
+    }
+    Some(length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(input: &[u8]) {
+        let compressed = compress(input);
+        assert_eq!(decompress(&compressed).as_deref(), Some(input));
+    }
+
+    #[test]
+    fn round_trips() {
+        round_trip(b"");
+        round_trip(b"abc");
+        round_trip(b"abcdabcdabcdabcd");
+        // literal runs and matches longer than 15 and 255 bytes
+        round_trip(&(0..=255u8).cycle().take(5000).collect::<Vec<_>>());
+        round_trip(&[7; 70_000]);
+        // matches farther back than the window
+        let mut rng = 1u64;
+        let noise: Vec<u8> = (0..80_000)
+            .map(|_| {
+                rng ^= rng << 13;
+                rng ^= rng >> 7;
+                rng ^= rng << 17;
+                rng as u8
+            })
+            .collect();
+        round_trip(&[&noise[..], &noise[..]].concat());
+    }
+
+    #[test]
+    fn shrinks_repeated_json() {
+        let json = r#"{"home_team":{"abbreviation":"BOS","full_name":"Boston Celtics"},"status":"Final"}"#.repeat(200);
+        let compressed = compress(json.as_bytes());
+        assert!(compressed.len() * 10 < json.len(), "{} of {}", compressed.len(), json.len());
+    }
+
+    #[test]
+    fn rejects_corrupted_input() {
+        let compressed = compress(&b"abcdefgh".repeat(100));
+        assert_eq!(decompress(&compressed[..compressed.len() - 3]), None);
+        // a match reaching back before the start
+        assert_eq!(decompress(&[0x04, 0xff, 0x00, 0x10]), None);
+    }
+}
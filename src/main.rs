@@ -1,100 +1,126 @@
+mod announce;
+mod app;
+mod background;
+mod bench;
+mod calendar;
+mod cli;
+mod columns;
+mod help;
+mod history;
+mod hyperlinks;
+mod input;
+mod ical;
+mod export;
+mod followed;
+mod goto;
+mod h2h;
+mod jobs;
+mod jumps;
+mod keymap;
+#[cfg(feature = "logos")]
+mod logos;
+mod matrix;
+mod overlay;
+mod palette;
+mod poll;
+mod refresh;
+mod report;
+mod schedule;
+mod schema;
+mod setup;
+mod spoilers;
+mod status;
+mod sync;
+mod teams;
+mod toast;
+mod ui;
+mod week;
+
+use rust_sports_cli::{alert, archive, cache, clock, config, decorator, details, elo, form, grouping, ids, notify, players, provider, scroll, standings, store, theme, tournament};
+use rust_sports_cli::{Game, GameData, Team};
+
 use anyhow::Result;
+use app::{clamp_to_season, reload_config_if_changed, report_jobs, search_players_if_due, App, View};
+use background::{apply_refreshed, auto_refresh, refresh_followed};
+use chrono::{Duration, Utc};
+use clap::Parser;
+use cli::{run_command, Cli, Command};
+use config::Config;
 use crossterm::{
-    event::{self, Event::Key, KeyCode::Char},
+    event::{DisableMouseCapture, EnableMouseCapture},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use ratatui::{
-    prelude::{CrosstermBackend, Terminal, Frame},
-    widgets::Paragraph,
-};
-use ratatui::widgets::{Block, Borders};
-use chrono::{DateTime, Duration, Utc};
-use serde::{Deserialize, Serialize};
+use input::{expire_count, update};
+use ratatui::prelude::{CrosstermBackend, Rect, Terminal};
+use setup::SetupState;
+use ui::ui;
 
 fn startup() -> Result<()> {
     enable_raw_mode()?;
-    execute!(std::io::stderr(), EnterAlternateScreen)?;
+    execute!(std::io::stderr(), EnterAlternateScreen, EnableMouseCapture)?;
     Ok(())
 }
 
 fn shutdown() -> Result<()> {
-    execute!(std::io::stderr(), LeaveAlternateScreen)?;
+    execute!(std::io::stderr(), DisableMouseCapture, LeaveAlternateScreen)?;
     disable_raw_mode()?;
     Ok(())
 }
 
-// App state
-struct App {
-    day: DateTime<Utc>,
-    should_quit: bool,
-    game_data: Option<GameData>,
-}
-
-// App ui render function
-fn ui(app: &App, f: &mut Frame) {
-    let date = app.day.format("%Y-%m-%d").to_string();
-
-    if app.game_data.is_some() {
-        let game_data = &app.game_data.as_ref().unwrap().data;
-
-        let mut text = String::new();
-
-        for game in game_data {
-            let line = format!("{} {}:{} {}\n", game.home_team.abbreviation, game.home_team_score, game.visitor_team_score, game.visitor_team.abbreviation);
-            text.push_str(&line);
-        }
-
-        text.push_str("\nNavigation:\n");
-        text.push_str("one day: j|k\n");
-        text.push_str("one week: h|l\n");
-        text.push_str("today: t\n");
-        text.push_str("quit: q");
-
-        if app.day <= Utc::now() {
-            f.render_widget(Paragraph::new(text).block(Block::default().title(format!("NBA Game results of: {}", date)).borders(Borders::ALL)), f.size());
-        } else {
-            f.render_widget(Paragraph::new("").block(Block::default().title(format!("{} is in the future.", date)).borders(Borders::ALL)), f.size());
-        }
-    }
-}
-
-// App update function
-fn update(app: &mut App) -> Result<()> {
-    if event::poll(std::time::Duration::from_millis(250))? {
-        if let Key(key) = event::read()? {
-            if key.kind == event::KeyEventKind::Press {
-                match key.code {
-                    Char('h') => app.day += Duration::days(7),
-                    Char('j') => app.day += Duration::days(1),
-                    Char('k') => app.day -= Duration::days(1),
-                    Char('l') => app.day -= Duration::days(7),
-                    Char('t') => app.day = Utc::now(),
-                    Char('q') => app.should_quit = true,
-                    _ => {}
-                }
-                app.game_data = get_nba_data(app.day)
-            }
-        }
-    }
-    Ok(())
-}
-
-fn run() -> Result<()> {
+fn run(config: Config, force_setup: bool, season: Option<i32>) -> Result<()> {
     // ratatui terminal
     let mut t = Terminal::new(CrosstermBackend::new(std::io::stderr()))?;
 
-    // application state
-    let mut app = App { day: Utc::now(), should_quit: false, game_data: get_nba_data(Utc::now() - Duration::days(1)) };
+    // application state, the wizard runs on first launch or when asked for
+    let setup = (force_setup || config.api_key().is_none()).then(SetupState::default);
+    let providers = provider::build_providers(&config);
+    let mut app = App::new(config, providers, setup, season)?;
+    if app.setup.is_none() {
+        // a picked season starts on the day of it closest to today
+        let day = match app.season {
+            Some(_) => {
+                clamp_to_season(&mut app);
+                app.day
+            }
+            None => Utc::now() - Duration::days(1),
+        };
+        let game_data = app.fetch_day(day);
+        app.set_game_data(game_data);
+    }
 
     loop {
         // application update
         update(&mut app)?;
+        reload_config_if_changed(&mut app)?;
+        search_players_if_due(&mut app);
+        expire_count(&mut app);
+        auto_refresh(&mut app);
+        refresh_followed(&mut app);
+        apply_refreshed(&mut app);
+        report_jobs(&mut app);
+        if let Err(e) = app.notifier.flush() {
+            app.toasts.error(format!("Could not notify: {:#}", e));
+        }
+        app.toasts.expire();
 
         // application render
         t.draw(|f| {
-            ui(&app, f);
+            ui(&mut app, f);
+            // above the status bar
+            let area = f.size();
+            toast::ui(&app.toasts, &app.theme, f, Rect { height: area.height.saturating_sub(1), ..area });
+            if let Some(hyperlinks) = &mut app.hyperlinks {
+                hyperlinks.locate(f.buffer_mut());
+            }
         })?;
+        if let Some(hyperlinks) = &app.hyperlinks {
+            hyperlinks.place()?;
+        }
+        #[cfg(feature = "logos")]
+        if let Some(logos) = &mut app.logos {
+            logos.place(app.providers[app.league].id())?;
+        }
 
         // application exit
         if app.should_quit {
@@ -106,10 +132,35 @@ fn run() -> Result<()> {
 }
 
 fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    // read config before touching the terminal so errors are printed normally
+    let mut config = Config::load()?;
+    config.accessible |= cli.accessible;
+    config.offline = cli.offline;
+    if let Some(dir) = &cli.record {
+        provider::vcr::init(provider::vcr::Mode::Record(dir.clone()))?;
+    }
+    if let Some(dir) = &cli.replay {
+        provider::vcr::init(provider::vcr::Mode::Replay(dir.clone()))?;
+    }
+
+    // commands worth repeating are recorded before they run, so failed ones can be retried
+    if matches!(cli.command, Some(Command::Export { .. } | Command::Cache { .. } | Command::Sync { .. })) {
+        if let Err(error) = history::record(std::env::args().skip(1).collect()) {
+            eprintln!("Could not record history: {:#}", error);
+        }
+    }
+
+    // subcommands that do not need the TUI
+    if run_command(&cli, &config)? {
+        return Ok(());
+    }
+
     // setup terminal
     startup()?;
 
-    let result = run();
+    let result = run(config, matches!(cli.command, Some(Command::Setup)), cli.season);
 
     // teardown terminal before unwrapping Result of app run
     shutdown()?;
@@ -118,77 +169,3 @@ fn main() -> Result<()> {
 
     Ok(())
 }
-
-#[derive(Serialize, Deserialize, Debug)]
-struct Team {
-    id: u32,
-    abbreviation: String,
-    city: String,
-    conference: String,
-    division: String,
-    full_name: String,
-    name: String,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-struct Game {
-    id: u32,
-    date: String,
-    home_team: Team,
-    home_team_score: u32,
-    period: u32,
-    postseason: bool,
-    season: u32,
-    status: String,
-    time: Option<String>,
-    visitor_team: Team,
-    visitor_team_score: u32,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-struct Meta {
-    current_page: u32,
-    next_page: Option<u32>,
-    per_page: u32,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-struct GameData {
-    data: Vec<Game>,
-    meta: Meta,
-}
-
-
-fn get_nba_data(date_time: DateTime<Utc>) -> Option<GameData> {
-    let client = reqwest::blocking::Client::new();
-
-    let date = date_time.format("%Y-%m-%d").to_string();
-
-    let query = format!("?dates[]={}", date);
-
-    // Build the request with the query parameters
-    let response = client
-        .get(format!("{}{}", "https://www.balldontlie.io/api/v1/games/", query))
-        .send();
-
-
-    // Parse the response body as JSON, String, etc.
-    let json_response = response.expect("Could not read data").text().ok()?;
-
-    let game_data = parse_json(json_response);
-
-    Some(game_data)
-}
-
-fn parse_json(json_data: String) -> GameData {
-    let result: Result<GameData, serde_json::Error> = serde_json::from_str(&json_data);
-
-    match result {
-        Ok(game_data) => {
-            game_data
-        }
-        Err(e) => {
-            panic!("Error parsing JSON: {:?}", e)
-        }
-    }
-}
\ No newline at end of file
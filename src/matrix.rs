@@ -0,0 +1,110 @@
+use crate::scroll;
+use crate::theme::Theme;
+use crate::Game;
+use ratatui::{
+    prelude::{Alignment, Constraint, Frame, Line, Rect},
+    style::Modifier,
+    widgets::{Cell, Paragraph, Row, Table},
+};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+// Width of the team column and of a record, e.g. "12-3" or "2-1-1"
+const TEAM_WIDTH: u16 = 5;
+const RECORD_WIDTH: u16 = 6;
+
+// Record of every team against every other team of the season, opened from the
+// standings. Rows are the teams, columns their opponents, both scroll.
+pub struct Matrix {
+    // abbreviations in alphabetical order
+    teams: Vec<String>,
+    // wins, draws and losses of a team against an opponent
+    records: HashMap<(String, String), (u32, u32, u32)>,
+    pub row: usize,
+    pub column: usize,
+}
+
+impl Matrix {
+    // From the finished games of the season
+    pub fn compute(games: &[Game]) -> Matrix {
+        let mut records: HashMap<(String, String), (u32, u32, u32)> = HashMap::new();
+        let mut seen = HashSet::new();
+        for game in games.iter().filter(|game| game.is_final()) {
+            // days from the cache and the archive may overlap
+            if !seen.insert(game.id) {
+                continue;
+            }
+            let (home, visitor) = (&game.home_team.abbreviation, &game.visitor_team.abbreviation);
+            let result = game.home_team_score.cmp(&game.visitor_team_score);
+            for (team, opponent, result) in [(home, visitor, result), (visitor, home, result.reverse())] {
+                let record = records.entry((team.clone(), opponent.clone())).or_default();
+                match result {
+                    Ordering::Greater => record.0 += 1,
+                    Ordering::Equal => record.1 += 1,
+                    Ordering::Less => record.2 += 1,
+                }
+            }
+        }
+        let mut teams: Vec<String> = records.keys().map(|(team, _)| team.clone()).collect();
+        teams.sort();
+        teams.dedup();
+        Matrix { teams, records, row: 0, column: 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.teams.is_empty()
+    }
+
+    pub fn scroll_rows(&mut self, delta: isize) {
+        self.row = self.row.saturating_add_signed(delta).min(self.teams.len().saturating_sub(1));
+    }
+
+    pub fn scroll_columns(&mut self, delta: isize) {
+        self.column = self.column.saturating_add_signed(delta).min(self.teams.len().saturating_sub(1));
+    }
+}
+
+pub fn ui(matrix: &Matrix, title: &str, theme: &Theme, f: &mut Frame, area: Rect) {
+    let block = theme.block(format!("{} (rows: up|down, opponents: left|right, back: esc)", title));
+    if matrix.is_empty() {
+        f.render_widget(Paragraph::new("No finished games of the season in the cache or the archive.").block(block), area);
+        return;
+    }
+
+    // as many opponents as fit, from the scrolled to one
+    let fit = (area.width.saturating_sub(2 + TEAM_WIDTH) / (RECORD_WIDTH + 1)).max(1) as usize;
+    let opponents: Vec<&String> = matrix.teams.iter().skip(matrix.column).take(fit).collect();
+
+    let record = |team: &String, opponent: &String| {
+        let Some(&(wins, draws, losses)) = matrix.records.get(&(team.clone(), opponent.clone())) else {
+            let text = if team == opponent { "" } else { "·" };
+            return Cell::from(Line::from(text).alignment(Alignment::Center)).style(theme.muted);
+        };
+        let text = match draws {
+            0 => format!("{}-{}", wins, losses),
+            _ => format!("{}-{}-{}", wins, draws, losses),
+        };
+        // a heatmap of who dominates whom, sweeps stand out
+        let style = match wins.cmp(&losses) {
+            Ordering::Greater => theme.good,
+            Ordering::Less => theme.bad,
+            Ordering::Equal => theme.muted,
+        };
+        let style = if (wins == 0 || losses == 0) && draws == 0 { style.add_modifier(Modifier::BOLD) } else { style };
+        Cell::from(Line::from(text).alignment(Alignment::Center)).style(style)
+    };
+
+    let rows: Vec<Row> = matrix
+        .teams
+        .iter()
+        .map(|team| {
+            let cells = std::iter::once(Cell::from(team.clone()).style(theme.header)).chain(opponents.iter().map(|opponent| record(team, opponent)));
+            Row::new(cells.collect::<Vec<_>>())
+        })
+        .collect();
+    let header = std::iter::once(Cell::from("")).chain(opponents.iter().map(|opponent| Cell::from(Line::from(opponent.as_str()).alignment(Alignment::Center))));
+    let widths = std::iter::once(Constraint::Length(TEAM_WIDTH)).chain(opponents.iter().map(|_| Constraint::Length(RECORD_WIDTH)));
+
+    let table = Table::new(rows, widths).header(Row::new(header.collect::<Vec<_>>()).style(theme.header)).column_spacing(1).block(block);
+    scroll::render(table, matrix.teams.len(), matrix.row, theme, f, area);
+}
@@ -0,0 +1,181 @@
+use ratatui::style::{Color, Style};
+use serde::{Deserialize, Serialize};
+
+/// A team as a provider sends it, ids and abbreviations are the provider's own
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Team {
+    pub id: u32,
+    pub abbreviation: String,
+    pub city: String,
+    pub conference: String,
+    pub division: String,
+    pub full_name: String,
+    pub name: String,
+    /// primary color as hex, from providers that know it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// the team's page on the provider's website
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+}
+
+impl Team {
+    /// Abbreviation style in the team's color, plain if it has none
+    pub fn style(&self) -> Style {
+        let rgb = self.color.as_deref().filter(|color| color.len() == 6).and_then(|color| u32::from_str_radix(color, 16).ok());
+        match rgb {
+            Some(rgb) => Style::default().fg(Color::Rgb((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8)),
+            None => Style::default(),
+        }
+    }
+}
+
+/// Season numbers are kept as the provider formats them, e.g. "3.21"
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Pitcher {
+    pub name: String,
+    pub wins: String,
+    pub losses: String,
+    pub era: String,
+}
+
+impl Pitcher {
+    /// e.g. "Gerrit Cole (10-5, 3.21)"
+    pub fn label(&self) -> String {
+        format!("{} ({}-{}, {})", self.name, self.wins, self.losses, self.era)
+    }
+}
+
+/// One game, scheduled, live or final, in the layout of the balldontlie API that
+/// every provider fills in
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Game {
+    pub id: u32,
+    pub date: String,
+    /// start timestamp, missing on older balldontlie responses
+    #[serde(default)]
+    pub datetime: Option<String>,
+    pub home_team: Team,
+    pub home_team_score: u32,
+    pub period: u32,
+    pub postseason: bool,
+    pub season: u32,
+    pub status: String,
+    pub time: Option<String>,
+    pub visitor_team: Team,
+    pub visitor_team_score: u32,
+    /// set by providers that mix several competitions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub competition: Option<String>,
+    /// tournament group or knockout round, e.g. "Group A" or "Quarterfinals"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stage: Option<String>,
+    /// probable starters of upcoming baseball games
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub home_pitcher: Option<Pitcher>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visitor_pitcher: Option<Pitcher>,
+    /// leading scorer of each team, e.g. "L. James 32 PTS"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub home_leader: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visitor_leader: Option<String>,
+    /// TV channels, comma separated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub broadcast: Option<String>,
+    /// betting line, e.g. "NYY -150, O/U 8.5"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub odds: Option<String>,
+    /// the game's page on the provider's website, e.g. ESPN's Gamecast
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+    /// points per quarter, only sent once a quarter has started
+    #[serde(default)]
+    pub home_q1: Option<u32>,
+    #[serde(default)]
+    pub home_q2: Option<u32>,
+    #[serde(default)]
+    pub home_q3: Option<u32>,
+    #[serde(default)]
+    pub home_q4: Option<u32>,
+    #[serde(default)]
+    pub visitor_q1: Option<u32>,
+    #[serde(default)]
+    pub visitor_q2: Option<u32>,
+    #[serde(default)]
+    pub visitor_q3: Option<u32>,
+    #[serde(default)]
+    pub visitor_q4: Option<u32>,
+    /// points per overtime period, only sent once it has started
+    #[serde(default)]
+    pub home_ot1: Option<u32>,
+    #[serde(default)]
+    pub home_ot2: Option<u32>,
+    #[serde(default)]
+    pub home_ot3: Option<u32>,
+    #[serde(default)]
+    pub visitor_ot1: Option<u32>,
+    #[serde(default)]
+    pub visitor_ot2: Option<u32>,
+    #[serde(default)]
+    pub visitor_ot3: Option<u32>,
+    /// points of every period from providers not limited to quarters, e.g. innings
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub home_periods: Vec<u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub visitor_periods: Vec<u32>,
+}
+
+/// Pagination of the balldontlie API, other providers send everything at once
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Meta {
+    pub next_cursor: Option<u32>,
+    pub per_page: u32,
+}
+
+/// The games of a day, as they are fetched and cached
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GameData {
+    pub data: Vec<Game>,
+    pub meta: Meta,
+}
+
+impl Game {
+    /// Finished, the score does not change anymore
+    pub fn is_final(&self) -> bool {
+        self.status == "Final"
+    }
+
+    /// (home, visitor) points of every started quarter
+    pub fn linescore(&self) -> Vec<(u32, u32)> {
+        if !self.home_periods.is_empty() {
+            return self.home_periods.iter().copied().zip(self.visitor_periods.iter().copied()).collect();
+        }
+        [
+            (self.home_q1, self.visitor_q1),
+            (self.home_q2, self.visitor_q2),
+            (self.home_q3, self.visitor_q3),
+            (self.home_q4, self.visitor_q4),
+            (self.home_ot1, self.visitor_ot1),
+            (self.home_ot2, self.visitor_ot2),
+            (self.home_ot3, self.visitor_ot3),
+        ]
+        .into_iter()
+        .map_while(|(home, visitor)| Some((home?, visitor?)))
+        .collect()
+    }
+
+    /// Scheduled games have not started a period yet
+    pub fn is_live(&self) -> bool {
+        self.period > 0 && !self.is_final()
+    }
+}
+
+impl GameData {
+    /// Wrap games from providers without pagination
+    pub fn from_games(games: Vec<Game>) -> GameData {
+        let per_page = games.len() as u32;
+        GameData { data: games, meta: Meta { next_cursor: None, per_page } }
+    }
+}
+
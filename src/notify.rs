@@ -0,0 +1,261 @@
+use crate::config::Config;
+use anyhow::{anyhow, Context, Result};
+use chrono::{Local, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::process::{Command, Stdio};
+
+// Channel every notification goes to when no routes are configured
+const DESKTOP: &str = "desktop";
+
+// What a notification is about, routes pick their channels by it
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Event {
+    PeriodEnd,
+    Final,
+    Digest,
+    Clutch,
+    // a followed game went to overtime
+    Overtime,
+    // a configured alert rule became true
+    Alert,
+}
+
+pub struct Notification {
+    pub event: Event,
+    // abbreviations of the teams it is about
+    pub teams: Vec<String>,
+    pub title: String,
+    pub body: String,
+}
+
+// Where notifications can be sent, named in the config
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Channel {
+    Desktop,
+    Telegram { bot_token: String, chat_id: String },
+    // gets the notification as a JSON object with event, teams, title and body
+    Webhook { url: String },
+}
+
+// Notifications of the events about the teams go to the channels, empty events
+// or teams match all of them
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Route {
+    pub events: Vec<Event>,
+    pub teams: Vec<String>,
+    pub channels: Vec<String>,
+}
+
+impl Route {
+    fn matches(&self, notification: &Notification) -> bool {
+        let event = self.events.is_empty() || self.events.contains(&notification.event);
+        let team = self.teams.is_empty()
+            || self.teams.iter().any(|team| notification.teams.iter().any(|other| other.eq_ignore_ascii_case(team)));
+        event && team
+    }
+}
+
+// Local times between which notifications are held back, e.g. "23:00" to "07:00"
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QuietHours {
+    pub start: String,
+    pub end: String,
+}
+
+impl QuietHours {
+    fn parse(&self) -> Result<(NaiveTime, NaiveTime)> {
+        let time = |text: &str| NaiveTime::parse_from_str(text, "%H:%M").with_context(|| format!("Invalid quiet hours time {}, use HH:MM", text));
+        Ok((time(&self.start)?, time(&self.end)?))
+    }
+}
+
+// Whether the time is in the quiet hours, which may span midnight
+fn is_quiet((start, end): (NaiveTime, NaiveTime), now: NaiveTime) -> bool {
+    match start <= end {
+        true => start <= now && now < end,
+        false => now >= start || now < end,
+    }
+}
+
+// Desktop notifications through the platform's notifier, or the configured
+// command which gets the title and the body as its two arguments. Routes send
+// them to other channels as well.
+pub struct Notifier {
+    command: Option<String>,
+    channels: HashMap<String, Channel>,
+    routes: Vec<Route>,
+    quiet_hours: Option<(NaiveTime, NaiveTime)>,
+    // held back during the quiet hours, sent as one digest after
+    queued: Vec<Notification>,
+    // whether the digest has to reach the desktop as well
+    queued_desktop: bool,
+}
+
+impl Notifier {
+    pub fn from_config(config: &Config) -> Result<Notifier> {
+        Ok(Notifier {
+            command: config.notify_command.clone(),
+            channels: config.notify_channels.clone(),
+            routes: config.notify_routes.clone(),
+            quiet_hours: config.quiet_hours.as_ref().map(QuietHours::parse).transpose()?,
+            queued: Vec::new(),
+            queued_desktop: false,
+        })
+    }
+
+    // Settings of an edited config, what was held back is kept
+    pub fn reconfigure(&mut self, config: &Config) -> Result<()> {
+        let (queued, queued_desktop) = (std::mem::take(&mut self.queued), self.queued_desktop);
+        *self = Notifier { queued, queued_desktop, ..Notifier::from_config(config)? };
+        Ok(())
+    }
+
+    fn quiet(&self) -> bool {
+        self.quiet_hours.is_some_and(|hours| is_quiet(hours, Local::now().time()))
+    }
+
+    // Held back during the quiet hours, sent right away otherwise
+    pub fn notify(&mut self, notification: Notification) -> Result<()> {
+        self.notify_to(notification, true, false)
+    }
+
+    // Shown on the desktop, and sent along the routes as well if asked for
+    pub fn notify_desktop(&mut self, notification: Notification, routed: bool) -> Result<()> {
+        self.notify_to(notification, routed, true)
+    }
+
+    fn notify_to(&mut self, notification: Notification, routed: bool, desktop: bool) -> Result<()> {
+        if self.quiet() {
+            self.queued.push(notification);
+            self.queued_desktop |= desktop;
+            return Ok(());
+        }
+        self.dispatch(&notification, routed, desktop)
+    }
+
+    // Once the quiet hours are over, everything held back goes out as one digest
+    pub fn flush(&mut self) -> Result<()> {
+        if self.queued.is_empty() || self.quiet() {
+            return Ok(());
+        }
+        let queued = std::mem::take(&mut self.queued);
+        let desktop = std::mem::take(&mut self.queued_desktop);
+        let mut teams: Vec<String> = queued.iter().flat_map(|notification| notification.teams.clone()).collect();
+        teams.sort();
+        teams.dedup();
+        let body = queued.iter().map(|notification| format!("{}: {}", notification.title, notification.body)).collect::<Vec<_>>().join("\n");
+        self.dispatch(&Notification { event: Event::Digest, teams, title: "During the quiet hours".to_string(), body }, true, desktop)
+    }
+
+    // Sent to the channels of every matching route, each channel once
+    fn dispatch(&self, notification: &Notification, routed: bool, desktop: bool) -> Result<()> {
+        let mut names: Vec<&str> = match self.routes.is_empty() {
+            _ if !routed => Vec::new(),
+            true => vec![DESKTOP],
+            false => self
+                .routes
+                .iter()
+                .filter(|route| route.matches(notification))
+                .flat_map(|route| route.channels.iter().map(String::as_str))
+                .collect(),
+        };
+        if desktop {
+            names.push(DESKTOP);
+        }
+        names.sort();
+        names.dedup();
+
+        let mut failed = None;
+        for name in names {
+            let channel = match (self.channels.get(name), name) {
+                (Some(channel), _) => channel.clone(),
+                (None, DESKTOP) => Channel::Desktop,
+                (None, _) => {
+                    failed = Some(anyhow!("Unknown notification channel {}", name));
+                    continue;
+                }
+            };
+            if let Err(e) = self.send(&channel, notification) {
+                failed = Some(e);
+            }
+        }
+        failed.map_or(Ok(()), Err)
+    }
+
+    fn send(&self, channel: &Channel, notification: &Notification) -> Result<()> {
+        let (url, payload) = match channel {
+            Channel::Desktop => return self.desktop(&notification.title, &notification.body),
+            Channel::Telegram { bot_token, chat_id } => (
+                format!("https://api.telegram.org/bot{}/sendMessage", bot_token),
+                serde_json::json!({ "chat_id": chat_id, "text": format!("{}\n{}", notification.title, notification.body) }),
+            ),
+            Channel::Webhook { url } => (
+                url.clone(),
+                serde_json::json!({
+                    "event": notification.event,
+                    "teams": notification.teams,
+                    "title": notification.title,
+                    "body": notification.body,
+                }),
+            ),
+        };
+        // posted in the background, the UI does not wait for the network
+        std::thread::spawn(move || {
+            let client = reqwest::blocking::Client::new();
+            let _ = client.post(url).json(&payload).send();
+        });
+        Ok(())
+    }
+
+    fn desktop(&self, title: &str, body: &str) -> Result<()> {
+        #[cfg(all(feature = "dbus", target_os = "linux"))]
+        if self.command.is_none() {
+            return crate::dbus::notify(title, body);
+        }
+        let mut command = match &self.command {
+            Some(program) => {
+                let mut command = Command::new(program);
+                command.args([title, body]);
+                command
+            }
+            None => platform_command(title, body),
+        };
+        let program = command.get_program().to_string_lossy().into_owned();
+        let mut child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .with_context(|| format!("Could not run {}", program))?;
+        std::thread::spawn(move || child.wait());
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn platform_command(title: &str, body: &str) -> Command {
+    // the texts are passed as arguments so they need no escaping inside the script
+    let mut command = Command::new("osascript");
+    command.args([
+        "-e",
+        "on run argv",
+        "-e",
+        "display notification (item 2 of argv) with title (item 1 of argv)",
+        "-e",
+        "end run",
+        title,
+        body,
+    ]);
+    command
+}
+
+#[cfg(not(target_os = "macos"))]
+fn platform_command(title: &str, body: &str) -> Command {
+    let mut command = Command::new("notify-send");
+    command.args(["--app-name=sports", title, body]);
+    command
+}
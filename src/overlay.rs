@@ -0,0 +1,76 @@
+use crate::provider::Provider;
+use crate::spoilers::Spoilers;
+use crate::{Game, Team};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Arc;
+
+// The followed games as a file for stream overlays at watch parties, e.g. an OBS
+// text source or a browser source polling it. Rewritten at every refresh of the
+// followed games, TOML when the path ends in .toml and JSON otherwise.
+#[derive(Serialize)]
+struct Overlay {
+    updated: String,
+    games: Vec<OverlayGame>,
+}
+
+#[derive(Serialize)]
+struct OverlayGame {
+    league: String,
+    id: u32,
+    home: Side,
+    visitor: Side,
+    // e.g. "Q3 4:12", "Final" or the start time
+    clock: String,
+    live: bool,
+    finished: bool,
+}
+
+#[derive(Serialize)]
+struct Side {
+    abbreviation: String,
+    name: String,
+    // left out while scores are hidden
+    score: Option<u32>,
+}
+
+pub fn write(path: &Path, games: &[(usize, Game)], providers: &[Arc<dyn Provider>], spoilers: &Spoilers) -> Result<()> {
+    let games = games
+        .iter()
+        .map(|(league, game)| {
+            let provider = providers[*league].as_ref();
+            let shown = spoilers.shows(game);
+            let side = |team: &Team, score| Side {
+                abbreviation: team.abbreviation.clone(),
+                name: team.full_name.clone(),
+                score: shown.then_some(score),
+            };
+            OverlayGame {
+                league: provider.id().to_string(),
+                id: game.id,
+                home: side(&game.home_team, game.home_team_score),
+                visitor: side(&game.visitor_team, game.visitor_team_score),
+                clock: provider.clock(game),
+                live: game.is_live(),
+                finished: game.is_final(),
+            }
+        })
+        .collect();
+    let overlay = Overlay { updated: Utc::now().to_rfc3339(), games };
+    let text = match path.extension().is_some_and(|extension| extension == "toml") {
+        true => toml::to_string_pretty(&overlay)?,
+        false => serde_json::to_string_pretty(&overlay)?,
+    };
+
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).with_context(|| format!("Could not create directory {}", parent.display()))?;
+    }
+    // replaced at once so an overlay never reads half a file
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".partial");
+    std::fs::write(&partial, text)
+        .and_then(|()| std::fs::rename(&partial, path))
+        .with_context(|| format!("Could not write {}", path.display()))
+}
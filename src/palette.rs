@@ -0,0 +1,166 @@
+use crate::theme::Theme;
+use chrono::{Duration, Local, NaiveDate};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    prelude::{Frame, Line, Rect},
+    widgets::{Clear, Paragraph},
+};
+use std::path::PathBuf;
+
+const PALETTE_WIDTH: u16 = 60;
+
+// Names of the commands, completed with tab
+const COMMANDS: [&str; 7] = ["date", "season", "team", "league", "theme", "export", "quit"];
+const DATE_WORDS: [&str; 3] = ["today", "yesterday", "tomorrow"];
+const EXPORT_FORMATS: [&str; 1] = ["csv"];
+
+// What a command line asks for
+pub enum PaletteCommand {
+    Date(NaiveDate),
+    // browse only the season starting in the year, None browses all of them again
+    Season(Option<i32>),
+    Team(String),
+    League(String),
+    Theme(String),
+    // export the browsed day, to the given file or one named after the league and day
+    ExportCsv(Option<PathBuf>),
+    Quit,
+}
+
+pub enum PaletteOutcome {
+    Pending,
+    Run(PaletteCommand),
+    Close,
+}
+
+// Ex-style command line opened with ':' for actions without a key of their own
+pub struct Palette {
+    input: String,
+    // why the command was not run, or the candidates of an ambiguous completion
+    message: Option<(String, bool)>,
+    // arguments to complete, taken from what the app had loaded when the palette was opened
+    teams: Vec<String>,
+    leagues: Vec<String>,
+    themes: Vec<String>,
+}
+
+impl Palette {
+    pub fn new(teams: Vec<String>, leagues: Vec<String>, themes: Vec<String>) -> Palette {
+        Palette { input: String::new(), message: None, teams, leagues, themes }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> PaletteOutcome {
+        match key.code {
+            KeyCode::Char(c) => {
+                self.input.push(c);
+                self.message = None;
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+                self.message = None;
+            }
+            KeyCode::Tab => self.complete(),
+            KeyCode::Enter => match self.parse() {
+                Ok(command) => return PaletteOutcome::Run(command),
+                Err(error) => self.message = Some((error, true)),
+            },
+            KeyCode::Esc => return PaletteOutcome::Close,
+            _ => {}
+        }
+        PaletteOutcome::Pending
+    }
+
+    fn candidates(&self, command: &str) -> Vec<String> {
+        match command {
+            "date" => DATE_WORDS.iter().map(|word| word.to_string()).collect(),
+            "season" => vec!["off".to_string()],
+            "team" => self.teams.clone(),
+            "league" => self.leagues.clone(),
+            "theme" => self.themes.clone(),
+            "export" => EXPORT_FORMATS.iter().map(|format| format.to_string()).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    // Complete the word at the end of the line, to the common prefix when several match
+    fn complete(&mut self) {
+        let (head, word, candidates) = match self.input.split_once(' ') {
+            None => (String::new(), self.input.clone(), COMMANDS.iter().map(|command| command.to_string()).collect()),
+            Some((command, argument)) if !argument.contains(' ') => (format!("{} ", command), argument.to_string(), self.candidates(command)),
+            Some(_) => return,
+        };
+        let matches: Vec<&String> =
+            candidates.iter().filter(|candidate| candidate.to_lowercase().starts_with(&word.to_lowercase())).collect();
+
+        match matches.as_slice() {
+            [] => self.message = Some(("No completions".to_string(), true)),
+            [only] => {
+                self.input = format!("{}{}", head, only);
+                if head.is_empty() {
+                    self.input.push(' ');
+                }
+                self.message = None;
+            }
+            several => {
+                let prefix = several.iter().skip(1).fold(several[0].clone(), |prefix, candidate| common_prefix(&prefix, candidate));
+                if prefix.len() > word.len() {
+                    self.input = format!("{}{}", head, prefix);
+                }
+                let names: Vec<&str> = several.iter().map(|candidate| candidate.as_str()).collect();
+                self.message = Some((names.join(" "), false));
+            }
+        }
+    }
+
+    fn parse(&self) -> Result<PaletteCommand, String> {
+        let mut words = self.input.split_whitespace();
+        let command = words.next().unwrap_or_default();
+        let argument = words.next();
+        match (command, argument) {
+            ("date", Some(date)) => parse_date(date).map(PaletteCommand::Date).ok_or_else(|| "Not a date, use YYYY-MM-DD".to_string()),
+            ("season", Some("off")) => Ok(PaletteCommand::Season(None)),
+            ("season", Some(season)) => season.parse().map(|season| PaletteCommand::Season(Some(season))).map_err(|_| "Not a season, use the year it starts in or off".to_string()),
+            ("team", Some(team)) => Ok(PaletteCommand::Team(team.to_uppercase())),
+            ("league", Some(league)) => Ok(PaletteCommand::League(league.to_lowercase())),
+            ("theme", Some(theme)) => Ok(PaletteCommand::Theme(theme.to_string())),
+            ("export", Some("csv")) => Ok(PaletteCommand::ExportCsv(words.next().map(PathBuf::from))),
+            ("export", _) => Err("Usage: export csv [file]".to_string()),
+            ("quit", None) => Ok(PaletteCommand::Quit),
+            ("date" | "season" | "team" | "league" | "theme", None) => Err(format!("Usage: {} <{}>", command, command)),
+            ("", _) => Err("Type a command, tab completes".to_string()),
+            _ => Err(format!("Unknown command {}", command)),
+        }
+    }
+}
+
+fn parse_date(date: &str) -> Option<NaiveDate> {
+    let today = Local::now().date_naive();
+    match date {
+        "today" => Some(today),
+        "yesterday" => Some(today - Duration::days(1)),
+        "tomorrow" => Some(today + Duration::days(1)),
+        _ => NaiveDate::parse_from_str(date, "%Y-%m-%d").ok(),
+    }
+}
+
+fn common_prefix(a: &str, b: &str) -> String {
+    a.chars().zip(b.chars()).take_while(|(a, b)| a.eq_ignore_ascii_case(b)).map(|(a, _)| a).collect()
+}
+
+// Two lines at the bottom of the area, the command line and what went wrong or the completions
+pub fn ui(palette: &Palette, theme: &Theme, f: &mut Frame, area: Rect) {
+    let width = PALETTE_WIDTH.min(area.width);
+    let height = 4.min(area.height);
+    let spot = Rect { x: area.x + (area.width - width) / 2, y: area.y + area.height - height, width, height };
+
+    let message = match &palette.message {
+        Some((error, true)) => Line::styled(error.as_str(), theme.bad),
+        Some((candidates, false)) => Line::from(candidates.as_str()),
+        None => Line::styled(COMMANDS.join(" "), theme.muted),
+    };
+    let lines = vec![Line::from(format!(":{}", palette.input)), message];
+
+    f.render_widget(Clear, spot);
+    f.render_widget(Paragraph::new(lines).block(theme.block("Command (tab: complete, esc: cancel)")), spot);
+    f.set_cursor(spot.x + 2 + palette.input.chars().count() as u16, spot.y + 1);
+}
@@ -0,0 +1,235 @@
+use crate::theme::Theme;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    prelude::{Constraint, Direction, Frame, Layout, Rect},
+    widgets::{Paragraph, Row, Table, TableState},
+};
+use std::time::{Duration, Instant};
+
+// Typing pauses at least this long before the players endpoint is asked
+const DEBOUNCE: Duration = Duration::from_millis(300);
+// Shorter queries match too many players to be useful
+const MIN_QUERY_LEN: usize = 2;
+// Letters of a name sent to the endpoint, the rest is matched fuzzily here so
+// typos past the start of a name still find the player
+const SEARCH_TERM_LEN: usize = 3;
+
+// A player found by name, as far as the league's API knows them
+#[derive(Clone, Debug, Default)]
+pub struct Player {
+    pub id: u32,
+    pub name: String,
+    pub position: String,
+    pub team: String,
+}
+
+// Per game averages of a player's season
+#[derive(Clone, Debug, Default)]
+pub struct SeasonAverages {
+    pub season: u32,
+    pub games_played: u32,
+    pub min: String,
+    pub pts: f64,
+    pub reb: f64,
+    pub ast: f64,
+    pub stl: f64,
+    pub blk: f64,
+    pub fg_pct: f64,
+    pub fg3_pct: f64,
+    pub ft_pct: f64,
+}
+
+// State of the player lookup view: the typed name, the players found for it
+// and the season numbers of the opened one
+#[derive(Default)]
+pub struct PlayersState {
+    pub input: String,
+    // when the input last changed, None once it was searched for
+    edited_at: Option<Instant>,
+    // None until a search ran, Some(empty) if nothing matched
+    pub results: Option<Vec<Player>>,
+    pub state: TableState,
+    pub error: Option<String>,
+    pub season: Option<(Player, Option<SeasonAverages>)>,
+}
+
+impl PlayersState {
+    // Keys typed into the search field, returns true when the selected player is opened
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Char(c) => {
+                self.input.push(c);
+                self.edited();
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+                self.edited();
+            }
+            KeyCode::Down => self.select(1),
+            KeyCode::Up => self.select(-1),
+            KeyCode::Enter => return self.selected().is_some(),
+            _ => {}
+        }
+        false
+    }
+
+    fn edited(&mut self) {
+        self.edited_at = Some(Instant::now());
+        self.error = None;
+    }
+
+    // The query to search for once typing paused, too short queries just clear the results
+    pub fn due_query(&mut self) -> Option<String> {
+        if self.edited_at?.elapsed() < DEBOUNCE {
+            return None;
+        }
+        self.edited_at = None;
+
+        let query = self.input.trim().to_string();
+        if query.chars().count() < MIN_QUERY_LEN {
+            self.set_results(None);
+            return None;
+        }
+        Some(query)
+    }
+
+    // Rank the players the endpoint found by how well they match the query,
+    // results for an outdated query are ignored
+    pub fn set_matches(&mut self, query: &str, candidates: Vec<Player>) {
+        if query != self.input.trim() {
+            return;
+        }
+        let mut scored: Vec<(i64, Player)> = candidates
+            .into_iter()
+            .filter_map(|player| Some((fuzzy_score(query, &player.name)?, player)))
+            .collect();
+        scored.sort_by(|(a, a_player), (b, b_player)| b.cmp(a).then(a_player.name.cmp(&b_player.name)));
+        self.set_results(Some(scored.into_iter().map(|(_, player)| player).collect()));
+    }
+
+    pub fn set_results(&mut self, results: Option<Vec<Player>>) {
+        let count = results.as_ref().map(Vec::len).unwrap_or_default();
+        self.results = results;
+        self.state = TableState::default().with_selected((count > 0).then_some(0));
+        self.season = None;
+    }
+
+    pub fn selected(&self) -> Option<&Player> {
+        self.results.as_ref()?.get(self.state.selected()?)
+    }
+
+    fn select(&mut self, delta: isize) {
+        let count = self.results.as_ref().map(Vec::len).unwrap_or_default();
+        if count == 0 {
+            return;
+        }
+        let selected = self.state.selected().unwrap_or_default() as isize + delta;
+        self.state.select(Some(selected.clamp(0, count as isize - 1) as usize));
+    }
+}
+
+// What is sent to the players endpoint for a query: the start of its longest word
+pub fn search_term(query: &str) -> String {
+    let word = query.split_whitespace().max_by_key(|word| word.chars().count()).unwrap_or_default();
+    word.chars().take(SEARCH_TERM_LEN).collect()
+}
+
+// Subsequence match of the query in the name, ignoring case and spaces. Matches
+// at the start of a word and runs of consecutive letters score higher, gaps cost.
+// None if the name does not contain all letters of the query in order.
+pub fn fuzzy_score(query: &str, name: &str) -> Option<i64> {
+    let name: Vec<char> = name.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+
+    for wanted in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = position + name[position..].iter().position(|c| *c == wanted)?;
+        score += match previous {
+            Some(previous) if found == previous + 1 => 5,
+            _ if found == 0 || name[found - 1] == ' ' => 3,
+            Some(previous) => 1 - (found - previous - 1).min(5) as i64,
+            None => 1 - found.min(5) as i64,
+        };
+        previous = Some(found);
+        position = found + 1;
+    }
+    Some(score)
+}
+
+pub fn ui(players: &mut PlayersState, league: &str, theme: &Theme, f: &mut Frame, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(3)])
+        .split(area);
+
+    let title = format!("{} player search (open: enter, back: esc)", league);
+    f.render_widget(
+        Paragraph::new(players.input.as_str()).block(theme.block(title)),
+        chunks[0],
+    );
+    // Cursor right after the typed text inside the input block
+    f.set_cursor(chunks[0].x + 1 + players.input.chars().count() as u16, chunks[0].y + 1);
+
+    let (list_area, season_area) = match &players.season {
+        Some(_) => {
+            let panes = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Min(0), Constraint::Length(32)])
+                .split(chunks[1]);
+            (panes[0], Some(panes[1]))
+        }
+        None => (chunks[1], None),
+    };
+
+    let message = match (&players.error, &players.results) {
+        (Some(error), _) => Some(error.clone()),
+        (None, None) => None,
+        (None, Some(results)) if results.is_empty() => Some(format!("No players found for \"{}\".", players.input.trim())),
+        (None, Some(_)) => None,
+    };
+    let block = theme.block("Players");
+    if let Some(message) = message {
+        f.render_widget(Paragraph::new(message).block(block), list_area);
+    } else {
+        let rows = players.results.iter().flatten().map(|player| {
+            Row::new(vec![player.name.clone(), player.position.clone(), player.team.clone()])
+        });
+        let widths = [Constraint::Fill(1), Constraint::Length(5), Constraint::Length(5)];
+        let table = Table::new(rows, widths)
+            .header(Row::new(vec!["Name", "Pos", "Team"]).style(theme.header))
+            .column_spacing(2)
+            .highlight_style(theme.selected)
+            .block(block);
+        f.render_stateful_widget(table, list_area, &mut players.state);
+    }
+
+    if let (Some(area), Some((player, season))) = (season_area, &players.season) {
+        render_season(player, season.as_ref(), theme, f, area);
+    }
+}
+
+fn render_season(player: &Player, season: Option<&SeasonAverages>, theme: &Theme, f: &mut Frame, area: Rect) {
+    let block = theme.block(player.name.clone());
+    let Some(season) = season else {
+        f.render_widget(Paragraph::new("No season numbers available.").block(block), area);
+        return;
+    };
+
+    let rows = [
+        ("Season", format!("{}-{:02}", season.season, (season.season + 1) % 100)),
+        ("Games", season.games_played.to_string()),
+        ("Minutes", season.min.clone()),
+        ("Points", format!("{:.1}", season.pts)),
+        ("Rebounds", format!("{:.1}", season.reb)),
+        ("Assists", format!("{:.1}", season.ast)),
+        ("Steals", format!("{:.1}", season.stl)),
+        ("Blocks", format!("{:.1}", season.blk)),
+        ("FG%", format!("{:.1}", season.fg_pct * 100.0)),
+        ("3P%", format!("{:.1}", season.fg3_pct * 100.0)),
+        ("FT%", format!("{:.1}", season.ft_pct * 100.0)),
+    ];
+    let rows = rows.into_iter().map(|(label, value)| Row::new(vec![label.to_string(), value]));
+    let table = Table::new(rows, [Constraint::Length(10), Constraint::Fill(1)]).block(block);
+    f.render_widget(table, area);
+}
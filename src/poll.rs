@@ -0,0 +1,102 @@
+use crate::clock;
+use crate::Game;
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// A quiet game is polled at most this many times less often than the interval
+const MAX_BACKOFF: u32 = 4;
+
+// xorshift64*, plenty to spread polls. Seeded from the clock unless a seed is given,
+// e.g. to replay the same intervals.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn seeded(seed: u64) -> Rng {
+        // the state may never be zero
+        Rng(seed.max(1))
+    }
+
+    pub fn from_clock() -> Rng {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|since| since.as_nanos() as u64).unwrap_or_default();
+        Rng::seeded(nanos ^ u64::from(std::process::id()))
+    }
+
+    // Uniform in [0, 1)
+    pub fn next_f64(&mut self) -> f64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        (self.0.wrapping_mul(0x2545_f491_4f6c_dd1d) >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+// Interval of every live game by id, from how often its score or period actually
+// changed: it grows by half the base interval with every refresh that changed
+// nothing and is halved, down to the base, when something did. The games of a day
+// come with one request, so the day is due with its busiest game.
+#[derive(Default)]
+pub struct Adaptive {
+    intervals: HashMap<u32, Duration>,
+}
+
+impl Adaptive {
+    pub fn observe(&mut self, base: Duration, old: &[Game], new: &[Game]) {
+        let intervals = std::mem::take(&mut self.intervals);
+        for game in new.iter().filter(|game| game.is_live()) {
+            let before = old.iter().find(|old| old.id == game.id);
+            let changed = before.is_none_or(|before| {
+                (before.home_team_score, before.visitor_team_score, before.period) != (game.home_team_score, game.visitor_team_score, game.period)
+            });
+            let interval = intervals.get(&game.id).copied().unwrap_or(base);
+            let interval = match changed {
+                true => (interval / 2).max(base),
+                false => (interval + base / 2).min(base * MAX_BACKOFF),
+            };
+            self.intervals.insert(game.id, interval);
+        }
+    }
+
+    // The interval of the busiest live game, the base for games not seen yet
+    pub fn interval(&self, base: Duration, games: &[Game]) -> Duration {
+        games
+            .iter()
+            .filter(|game| game.is_live())
+            .map(|game| self.intervals.get(&game.id).copied().unwrap_or(base))
+            .min()
+            .unwrap_or(base)
+    }
+}
+
+// Time until the next refresh of live games. Scores change while games are in play,
+// so the interval only grows by the break factor once every live game is in a break.
+// The jitter then moves it by up to that share either way.
+pub fn interval(base: Duration, games: &[Game], break_factor: u32, jitter: f64, rng: &mut Rng) -> Duration {
+    let mut live = games.iter().filter(|game| game.is_live()).peekable();
+    let resting = live.peek().is_some() && live.all(clock::in_break);
+    let interval = if resting { base * break_factor } else { base };
+    interval.mul_f64(1.0 + jitter * (rng.next_f64() * 2.0 - 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_draws_same_intervals() {
+        let (mut a, mut b) = (Rng::seeded(42), Rng::seeded(42));
+        let base = Duration::from_secs(30);
+        for _ in 0..100 {
+            assert_eq!(interval(base, &[], 3, 0.1, &mut a), interval(base, &[], 3, 0.1, &mut b));
+        }
+    }
+
+    #[test]
+    fn jitter_keeps_interval_within_its_share() {
+        let mut rng = Rng::seeded(7);
+        let base = Duration::from_secs(30);
+        for _ in 0..1000 {
+            let drawn = interval(base, &[], 3, 0.5, &mut rng);
+            assert!(drawn >= base / 2 && drawn <= base * 3 / 2, "{:?}", drawn);
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use super::vcr::{self, Mode};
+use reqwest::blocking::{Client, Request, RequestBuilder, Response};
+use reqwest::{IntoUrl, Url};
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+/// What the providers send their requests through: the network, the network with a
+/// recording of every response, or the fixture files of an earlier recording
+pub trait ApiClient: Send + Sync {
+    fn execute(&self, request: Request) -> Option<Response>;
+
+    fn send(&self, request: RequestBuilder) -> Option<Response> {
+        self.execute(request.build().ok()?)
+    }
+}
+
+impl ApiClient for Client {
+    fn execute(&self, request: Request) -> Option<Response> {
+        Client::execute(self, request).ok()
+    }
+}
+
+/// Answers from JSON bodies given per URL, for tests of the providers without a
+/// network. Requests for other URLs fail, like ones without a network.
+#[derive(Default)]
+pub struct Canned {
+    bodies: HashMap<String, String>,
+}
+
+impl Canned {
+    pub fn new() -> Canned {
+        Canned::default()
+    }
+
+    /// The body of every GET of the URL, query included
+    pub fn with(mut self, url: &str, body: &str) -> Canned {
+        let key = Url::parse(url).map_or_else(|_| url.to_string(), |url| canned_key(&url));
+        self.bodies.insert(key, body.to_string());
+        self
+    }
+}
+
+// The URL with its query decoded, a query built with .query() escapes the brackets
+// of e.g. game_ids[] while one written into the URL keeps them
+fn canned_key(url: &Url) -> String {
+    let query: Vec<String> = url.query_pairs().map(|(name, value)| format!("{}={}", name, value)).collect();
+    let mut url = url.clone();
+    url.set_query(None);
+    match query.is_empty() {
+        true => url.to_string(),
+        false => format!("{}?{}", url, query.join("&")),
+    }
+}
+
+impl ApiClient for Canned {
+    fn execute(&self, request: Request) -> Option<Response> {
+        let body = self.bodies.get(&canned_key(request.url()))?;
+        let response = http::Response::builder().header("content-type", "application/json").body(body.clone()).ok()?;
+        Some(Response::from(response))
+    }
+}
+
+/// One client for the whole run, picked by --record and --replay
+pub fn client() -> Arc<dyn ApiClient> {
+    static CLIENT: OnceLock<Arc<dyn ApiClient>> = OnceLock::new();
+    CLIENT
+        .get_or_init(|| match vcr::mode() {
+            None => Arc::new(Client::new()),
+            Some(Mode::Record(dir)) => Arc::new(vcr::Recorder::new(dir.clone())),
+            Some(Mode::Replay(dir)) => Arc::new(vcr::Fixtures::new(dir.clone())),
+        })
+        .clone()
+}
+
+/// A GET request to send through the client, building one needs no connection
+pub fn get(url: impl IntoUrl) -> RequestBuilder {
+    static BUILDER: OnceLock<Client> = OnceLock::new();
+    BUILDER.get_or_init(Client::new).get(url)
+}
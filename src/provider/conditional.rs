@@ -0,0 +1,68 @@
+use super::api::ApiClient;
+use reqwest::blocking::RequestBuilder;
+use reqwest::header::{HeaderMap, HeaderName, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+// URLs remembered at once, the live scoreboard is the one polled again and again
+const LIMIT: usize = 64;
+
+// Validators of the last response of a URL, with what it parsed into
+struct Validated {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    parsed: Box<dyn Any + Send>,
+}
+
+// Set for the rest of the run to send every request without validators
+static BYPASSED: AtomicBool = AtomicBool::new(false);
+
+/// Ask for the whole response every time from now on, e.g. to benchmark full fetches
+/// rather than revalidations
+pub fn bypass() {
+    BYPASSED.store(true, Ordering::Relaxed);
+}
+
+fn responses() -> &'static Mutex<HashMap<String, Validated>> {
+    static RESPONSES: OnceLock<Mutex<HashMap<String, Validated>>> = OnceLock::new();
+    RESPONSES.get_or_init(Default::default)
+}
+
+// GET JSON with If-None-Match and If-Modified-Since from the previous response of
+// the same URL. When the server answers 304 the previous result is returned as it
+// was parsed, nothing is downloaded or parsed again.
+pub fn get_json<T: DeserializeOwned + Clone + Send + 'static>(client: &dyn ApiClient, request: RequestBuilder) -> Option<T> {
+    let mut request = request.build().ok()?;
+    let url = request.url().to_string();
+    let bypassed = BYPASSED.load(Ordering::Relaxed);
+    if let Some(previous) = responses().lock().ok()?.get(&url).filter(|_| !bypassed) {
+        let validators = [(IF_NONE_MATCH, &previous.etag), (IF_MODIFIED_SINCE, &previous.last_modified)];
+        for (name, value) in validators {
+            if let Some(value) = value.as_deref().and_then(|value| value.parse().ok()) {
+                request.headers_mut().insert(name, value);
+            }
+        }
+    }
+
+    let response = client.execute(request)?;
+    if response.status() == StatusCode::NOT_MODIFIED {
+        return responses().lock().ok()?.get(&url)?.parsed.downcast_ref::<T>().cloned();
+    }
+    let header = |headers: &HeaderMap, name: HeaderName| headers.get(name).and_then(|value| value.to_str().ok()).map(String::from);
+    let (etag, last_modified) = (header(response.headers(), ETAG), header(response.headers(), LAST_MODIFIED));
+    let parsed: T = response.json().ok()?;
+
+    // servers without validators always send the whole response
+    if !bypassed && (etag.is_some() || last_modified.is_some()) {
+        let mut responses = responses().lock().ok()?;
+        if responses.len() >= LIMIT && !responses.contains_key(&url) {
+            responses.clear();
+        }
+        responses.insert(url, Validated { etag, last_modified, parsed: Box::new(parsed.clone()) });
+    }
+    Some(parsed)
+}
@@ -0,0 +1,460 @@
+use super::{api, conditional};
+use crate::details::{GameDetails, Leader, Shot};
+use crate::{Game, Pitcher, Team};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Deserialize;
+
+// Shared client for ESPN's public scoreboard API, which serves every league
+// that has no dedicated API of its own
+const API_URL: &str = "https://site.api.espn.com/apis/site/v2/sports";
+
+#[derive(Deserialize, Debug, Clone)]
+struct Scoreboard {
+    #[serde(default)]
+    events: Vec<Event>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct Event {
+    id: String,
+    date: String,
+    #[serde(default)]
+    season: Option<Season>,
+    #[serde(default)]
+    competitions: Vec<Competition>,
+    #[serde(default)]
+    links: Vec<Link>,
+}
+
+// Page on espn.com, e.g. the Gamecast of an event or the clubhouse of a team
+#[derive(Deserialize, Debug, Clone)]
+struct Link {
+    href: String,
+    #[serde(default)]
+    rel: Vec<String>,
+}
+
+// The link for browsers with the relation, e.g. "summary" or "clubhouse"
+fn page(links: &[Link], rel: &str) -> Option<String> {
+    let desktop = |link: &&Link| link.rel.iter().any(|kind| kind == "desktop") && link.rel.iter().any(|kind| kind == rel);
+    links.iter().find(desktop).map(|link| link.href.clone())
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct Season {
+    year: u32,
+    #[serde(rename = "type", default)]
+    kind: u32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct Competition {
+    #[serde(default)]
+    competitors: Vec<Competitor>,
+    status: Status,
+    #[serde(default)]
+    notes: Vec<Note>,
+    #[serde(default)]
+    broadcasts: Vec<Broadcast>,
+    #[serde(default)]
+    odds: Vec<Odds>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct Broadcast {
+    #[serde(default)]
+    names: Vec<String>,
+}
+
+// Betting line, e.g. details "NYY -150" with an over/under of 8.5
+#[derive(Deserialize, Debug, Clone)]
+struct Odds {
+    #[serde(default)]
+    details: String,
+    #[serde(rename = "overUnder", default)]
+    over_under: Option<f64>,
+}
+
+// Tournament stage of a game, e.g. "Group A" or "Quarterfinals"
+#[derive(Deserialize, Debug, Clone)]
+struct Note {
+    #[serde(default)]
+    headline: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct Competitor {
+    #[serde(rename = "homeAway")]
+    home_away: String,
+    #[serde(default)]
+    score: Option<String>,
+    team: EspnTeam,
+    // points per period, halves in soccer
+    #[serde(default)]
+    linescores: Vec<LineScore>,
+    // probable starting pitchers of upcoming baseball games
+    #[serde(default)]
+    probables: Vec<Probable>,
+    // game leaders of the team, the first category is the scoring one
+    #[serde(default)]
+    leaders: Vec<Category>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct Probable {
+    athlete: Athlete,
+    #[serde(default)]
+    statistics: Vec<Statistic>,
+}
+
+// A season stat of a probable pitcher, e.g. W, L or ERA
+#[derive(Deserialize, Debug, Clone)]
+struct Statistic {
+    #[serde(default)]
+    abbreviation: String,
+    #[serde(rename = "displayValue", default)]
+    display_value: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct LineScore {
+    #[serde(default)]
+    value: f64,
+}
+
+// Game summary, the team leaders and the location of shots are used
+#[derive(Deserialize, Debug, Clone)]
+struct Summary {
+    #[serde(default)]
+    leaders: Vec<TeamLeaders>,
+    // play by play, basketball plays carry a court coordinate
+    #[serde(default)]
+    plays: Vec<Play>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct Play {
+    #[serde(rename = "shootingPlay", default)]
+    shooting_play: bool,
+    #[serde(rename = "scoringPlay", default)]
+    scoring_play: bool,
+    #[serde(default)]
+    team: Option<TeamRef>,
+    #[serde(default)]
+    coordinate: Option<Coordinate>,
+    // free throws are shooting plays without a location on the court
+    #[serde(rename = "type", default)]
+    kind: Option<PlayType>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct TeamRef {
+    id: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct PlayType {
+    #[serde(default)]
+    text: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct Coordinate {
+    x: f64,
+    y: f64,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct TeamLeaders {
+    team: EspnTeam,
+    #[serde(default)]
+    leaders: Vec<Category>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct Category {
+    #[serde(rename = "displayName", default)]
+    display_name: String,
+    #[serde(default)]
+    abbreviation: String,
+    #[serde(default)]
+    leaders: Vec<CategoryLeader>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct CategoryLeader {
+    #[serde(rename = "displayValue", default)]
+    display_value: String,
+    athlete: Athlete,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct Athlete {
+    #[serde(rename = "displayName", default)]
+    display_name: String,
+    #[serde(rename = "shortName", default)]
+    short_name: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct EspnTeam {
+    id: String,
+    #[serde(default)]
+    abbreviation: String,
+    #[serde(rename = "displayName", default)]
+    display_name: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    location: String,
+    // primary team color as hex, e.g. "007a33"
+    #[serde(default)]
+    color: Option<String>,
+    #[serde(default)]
+    links: Vec<Link>,
+}
+
+// Team list, nested as sports[].leagues[].teams[].team
+#[derive(Deserialize, Debug, Clone)]
+struct TeamList {
+    #[serde(default)]
+    sports: Vec<TeamListSport>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct TeamListSport {
+    #[serde(default)]
+    leagues: Vec<TeamListLeague>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct TeamListLeague {
+    #[serde(default)]
+    teams: Vec<TeamListEntry>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct TeamListEntry {
+    team: EspnTeam,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct Status {
+    #[serde(rename = "displayClock", default)]
+    display_clock: String,
+    #[serde(default)]
+    period: u32,
+    #[serde(rename = "type")]
+    kind: StatusType,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct StatusType {
+    state: String,
+    #[serde(rename = "shortDetail", default)]
+    short_detail: String,
+}
+
+// Games of a single day, `sport` is the ESPN path like "soccer/eng.1"
+pub fn fetch_day(sport: &str, day: DateTime<Utc>) -> Option<Vec<Game>> {
+    let events = fetch(sport, &day.format("%Y%m%d").to_string())?;
+    Some(events.into_iter().filter_map(to_game).collect())
+}
+
+// All games between two dates, inclusive. Fetched a month at a time since a
+// request returns at most 1000 events, less than a full NHL or MLB season.
+// Preseason games (season type 1) never count for standings or tournaments.
+pub fn fetch_range(sport: &str, start: NaiveDate, end: NaiveDate) -> Option<Vec<Game>> {
+    let mut games = Vec::new();
+    let mut from = start;
+
+    while from <= end {
+        let to = (from + Duration::days(30)).min(end);
+        let events = fetch(sport, &format!("{}-{}", from.format("%Y%m%d"), to.format("%Y%m%d")))?;
+        let regular = events.into_iter().filter(|event| event.season.as_ref().is_none_or(|season| season.kind != 1));
+        games.extend(regular.filter_map(to_game));
+        from = to + Duration::days(1);
+    }
+
+    Some(games)
+}
+
+// Top performers of a game from its summary, ESPN has no full box score for every sport
+pub fn fetch_details(sport: &str, game: &Game) -> Option<GameDetails> {
+    let request = api::get(format!("{}/{}/summary", API_URL, sport))
+        .query(&[("event", game.id.to_string())]);
+    let response = api::client().send(request)?;
+
+    let summary: Summary = response.json().ok()?;
+
+    let mut leaders = Vec::new();
+    for team in summary.leaders {
+        for category in team.leaders {
+            if let Some(leader) = category.leaders.into_iter().next() {
+                leaders.push(Leader {
+                    team: team.team.abbreviation.clone(),
+                    category: category.display_name,
+                    name: leader.athlete.display_name,
+                    value: leader.display_value,
+                });
+            }
+        }
+    }
+
+    let shots = summary.plays.iter().filter_map(|play| to_shot(game, play)).collect();
+
+    Some(GameDetails { game: game.clone(), leaders, shots, ..GameDetails::default() })
+}
+
+// Field goal attempts with a location on the court, ESPN sends placeholders far
+// outside of it for plays without one
+fn to_shot(game: &Game, play: &Play) -> Option<Shot> {
+    if !play.shooting_play || play.kind.as_ref().is_some_and(|kind| kind.text.contains("Free Throw")) {
+        return None;
+    }
+    let coordinate = play.coordinate.as_ref().filter(|coordinate| (0.0..=50.0).contains(&coordinate.x) && (0.0..=47.0).contains(&coordinate.y))?;
+    let team_id = &play.team.as_ref()?.id;
+    let team = [&game.home_team, &game.visitor_team].into_iter().find(|team| &team.id.to_string() == team_id)?;
+    Some(Shot { team: team.abbreviation.clone(), x: coordinate.x, y: coordinate.y, made: play.scoring_play })
+}
+
+// Every team of a league, ESPN does not say which conference or division they play in
+pub fn fetch_teams(sport: &str) -> Option<Vec<Team>> {
+    let response = api::client().send(api::get(format!("{}/{}/teams", API_URL, sport)))?;
+
+    let list: TeamList = response.json().ok()?;
+
+    let mut teams: Vec<Team> = list
+        .sports
+        .into_iter()
+        .flat_map(|sport| sport.leagues)
+        .flat_map(|league| league.teams)
+        .map(|entry| to_team(entry.team))
+        .collect();
+    teams.sort_by(|a, b| a.full_name.cmp(&b.full_name));
+
+    Some(teams)
+}
+
+fn fetch(sport: &str, dates: &str) -> Option<Vec<Event>> {
+    // live days are polled, unchanged scoreboards are not sent again
+    let request = api::get(format!("{}/{}/scoreboard", API_URL, sport))
+        .query(&[("dates", dates), ("limit", "1000")]);
+
+    let scoreboard: Scoreboard = conditional::get_json(&*api::client(), request)?;
+
+    Some(scoreboard.events)
+}
+
+// Map an ESPN event onto the balldontlie shaped game model
+fn to_game(event: Event) -> Option<Game> {
+    let mut competitions = event.competitions;
+    if competitions.is_empty() {
+        return None;
+    }
+    let competition = competitions.swap_remove(0);
+
+    let mut home = None;
+    let mut visitor = None;
+    for competitor in competition.competitors {
+        if competitor.home_away == "home" {
+            home = Some(competitor);
+        } else {
+            visitor = Some(competitor);
+        }
+    }
+    let (home, visitor) = (home?, visitor?);
+
+    let status = match competition.status.kind.state.as_str() {
+        "post" => "Final".to_string(),
+        "in" => competition.status.kind.short_detail.clone(),
+        // Scheduled games carry their start time, like balldontlie does
+        _ => event.date.clone(),
+    };
+
+    let stage = competition.notes.first().map(|note| note.headline.clone()).filter(|headline| !headline.is_empty());
+
+    let season = event.season.as_ref().map(|season| season.year).unwrap_or_default();
+    // ESPN season type 3 is the postseason
+    let postseason = event.season.as_ref().map(|season| season.kind == 3).unwrap_or_default();
+
+    let broadcasts: Vec<String> = competition.broadcasts.iter().flat_map(|broadcast| broadcast.names.clone()).collect();
+    let broadcast = (!broadcasts.is_empty()).then(|| broadcasts.join(", "));
+
+    let odds = competition.odds.first().filter(|odds| !odds.details.is_empty()).map(|odds| match odds.over_under {
+        Some(over_under) => format!("{}, O/U {}", odds.details, over_under),
+        None => odds.details.clone(),
+    });
+
+    let link = page(&event.links, "summary");
+    let home_pitcher = home.probables.first().map(to_pitcher);
+    let visitor_pitcher = visitor.probables.first().map(to_pitcher);
+
+    Some(Game {
+        id: event.id.parse().ok()?,
+        datetime: Some(event.date.clone()),
+        date: event.date,
+        home_team_score: score(&home),
+        visitor_team_score: score(&visitor),
+        home_team: to_team(home.team),
+        visitor_team: to_team(visitor.team),
+        period: competition.status.period,
+        postseason,
+        season,
+        status,
+        time: Some(competition.status.display_clock),
+        stage,
+        home_pitcher,
+        visitor_pitcher,
+        home_leader: top_scorer(&home.leaders),
+        visitor_leader: top_scorer(&visitor.leaders),
+        broadcast,
+        odds,
+        link,
+        home_periods: home.linescores.iter().map(|line| line.value as u32).collect(),
+        visitor_periods: visitor.linescores.iter().map(|line| line.value as u32).collect(),
+        ..Game::default()
+    })
+}
+
+fn score(competitor: &Competitor) -> u32 {
+    competitor.score.as_deref().and_then(|score| score.parse().ok()).unwrap_or_default()
+}
+
+// e.g. "L. James 32 PTS"
+fn top_scorer(leaders: &[Category]) -> Option<String> {
+    let category = leaders.first()?;
+    let leader = category.leaders.first()?;
+    Some(format!("{} {} {}", leader.athlete.short_name, leader.display_value, category.abbreviation))
+}
+
+fn to_pitcher(probable: &Probable) -> Pitcher {
+    let stat = |abbreviation: &str| {
+        probable
+            .statistics
+            .iter()
+            .find(|statistic| statistic.abbreviation == abbreviation)
+            .map(|statistic| statistic.display_value.clone())
+            .unwrap_or_default()
+    };
+
+    Pitcher {
+        name: probable.athlete.display_name.clone(),
+        wins: stat("W"),
+        losses: stat("L"),
+        era: stat("ERA"),
+    }
+}
+
+fn to_team(team: EspnTeam) -> Team {
+    Team {
+        id: team.id.parse().unwrap_or_default(),
+        abbreviation: team.abbreviation,
+        city: team.location,
+        full_name: team.display_name,
+        name: team.name,
+        color: team.color,
+        link: page(&team.links, "clubhouse"),
+        ..Team::default()
+    }
+}
@@ -0,0 +1,64 @@
+use super::{espn, Provider};
+use crate::decorator::RowDecorator;
+use crate::details::GameDetails;
+use crate::{Game, GameData, Team};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+
+const SPORT: &str = "baseball/mlb";
+
+// MLB games from ESPN
+pub struct MlbProvider;
+
+impl Provider for MlbProvider {
+    fn id(&self) -> &'static str {
+        "mlb"
+    }
+
+    fn name(&self) -> &'static str {
+        "MLB"
+    }
+
+    fn fetch_day(&self, day: DateTime<Utc>) -> Option<GameData> {
+        espn::fetch_day(SPORT, day).map(GameData::from_games)
+    }
+
+    // ESPN already names the half inning of live games, e.g. "Top 7th"
+    // extra innings keep counting
+    fn period_label(&self, index: usize) -> String {
+        (index + 1).to_string()
+    }
+
+    fn clock(&self, game: &Game) -> String {
+        if game.is_live() {
+            return game.status.clone();
+        }
+        crate::clock::period_clock(game)
+    }
+
+    fn row_decorators(&self) -> Vec<RowDecorator> {
+        vec![RowDecorator::Pitchers]
+    }
+
+    fn fetch_details(&self, game: &Game) -> Option<GameDetails> {
+        espn::fetch_details(SPORT, game)
+    }
+
+    // Spring training starts in late February, the postseason ends in early November
+    fn season_range(&self, season: i32) -> Option<(NaiveDate, NaiveDate)> {
+        Some((NaiveDate::from_ymd_opt(season, 2, 15)?, NaiveDate::from_ymd_opt(season, 11, 15)?))
+    }
+
+    fn fetch_season(&self, day: DateTime<Utc>) -> Option<Vec<Game>> {
+        let (start, end) = self.season_range(day.year())?;
+
+        espn::fetch_range(SPORT, start, end)
+    }
+
+    fn fetch_range(&self, start: NaiveDate, end: NaiveDate) -> Option<Vec<Game>> {
+        espn::fetch_range(SPORT, start, end)
+    }
+
+    fn fetch_teams(&self) -> Option<Vec<Team>> {
+        espn::fetch_teams(SPORT)
+    }
+}
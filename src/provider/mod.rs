@@ -0,0 +1,172 @@
+pub mod api;
+pub mod conditional;
+pub mod espn;
+pub mod mlb;
+pub mod nba;
+pub mod nhl;
+pub mod offline;
+pub mod olympics;
+pub mod soccer;
+pub mod vcr;
+
+use crate::archive::Archive;
+use crate::config::Config;
+use crate::decorator::RowDecorator;
+use crate::details::GameDetails;
+use crate::players::{Player, SeasonAverages};
+use crate::standings::{self, RankingRules, Standings};
+use crate::tournament::Tournament;
+use crate::{Game, GameData, Team};
+use chrono::{DateTime, NaiveDate, Utc};
+use crossterm::event::KeyCode;
+use std::sync::Arc;
+
+/// A key that only exists while the provider's league is shown
+pub struct LeagueBinding {
+    pub key: KeyCode,
+    pub action: &'static str,
+    pub description: &'static str,
+}
+
+/// A source of games for one league, shared with the threads of background refreshes
+pub trait Provider: Send + Sync {
+    /// Stable identifier, used as keymap context and in the config file
+    fn id(&self) -> &'static str;
+
+    fn name(&self) -> &'static str;
+
+    /// Directory of the provider's days in the disk cache, must change with
+    /// every setting that changes what fetch_day returns
+    fn cache_key(&self) -> String {
+        self.id().to_string()
+    }
+
+    fn fetch_day(&self, day: DateTime<Utc>) -> Option<GameData>;
+
+    fn keybindings(&self) -> Vec<LeagueBinding> {
+        Vec::new()
+    }
+
+    /// Live state of a game in the league's own notation
+    fn clock(&self, game: &Game) -> String {
+        crate::clock::period_clock(game)
+    }
+
+    /// Header of a linescore column, four quarters and overtimes by default
+    fn period_label(&self, index: usize) -> String {
+        crate::clock::period_label(index, 4)
+    }
+
+    /// Seconds left of a live game in its final period or an overtime, for leagues with a game clock
+    fn seconds_left(&self, _game: &Game) -> Option<u32> {
+        None
+    }
+
+    /// Largest margin of a close game near its end
+    fn close_margin(&self) -> u32 {
+        3
+    }
+
+    /// Score column of the scoreboard
+    fn score(&self, game: &Game) -> String {
+        format!("{} - {}", game.home_team_score, game.visitor_team_score)
+    }
+
+    /// Extra lines of the scoreboard rows, unless the user configured their own
+    fn row_decorators(&self) -> Vec<RowDecorator> {
+        Vec::new()
+    }
+
+    /// Box score and top performers of a game
+    fn fetch_details(&self, _game: &Game) -> Option<GameDetails> {
+        None
+    }
+
+    /// How the league ranks its teams
+    fn ranking_rules(&self) -> RankingRules {
+        standings::WIN_PCT
+    }
+
+    /// Every game between two dates, inclusive, e.g. to mark game days in the calendar
+    fn fetch_range(&self, _start: NaiveDate, _end: NaiveDate) -> Option<Vec<Game>> {
+        None
+    }
+
+    /// First and last day of the season starting in the year, for leagues with seasons
+    fn season_range(&self, _season: i32) -> Option<(NaiveDate, NaiveDate)> {
+        None
+    }
+
+    /// Every game of the season the day belongs to
+    fn fetch_season(&self, _day: DateTime<Utc>) -> Option<Vec<Game>> {
+        None
+    }
+
+    /// Standings as of the day, ranked from the season's games by default
+    fn fetch_standings(&self, day: DateTime<Utc>) -> Option<Standings> {
+        let games = self.fetch_season(day)?;
+        Some(standings::compute(self.ranking_rules(), &games))
+    }
+
+    /// Group stage and knockout rounds of the tournament around the day, if the league has one
+    fn fetch_tournament(&self, _day: DateTime<Utc>) -> Option<Tournament> {
+        None
+    }
+
+    /// Teams of the league for the teams view
+    fn fetch_teams(&self) -> Option<Vec<Team>> {
+        None
+    }
+
+    /// Every game of a team in the season the day belongs to, filtered from the
+    /// whole season unless the league's API can ask for a single team
+    fn fetch_schedule(&self, team: &Team, day: DateTime<Utc>) -> Option<Vec<Game>> {
+        let games = self.fetch_season(day)?;
+        Some(games.into_iter().filter(|game| game.home_team.id == team.id || game.visitor_team.id == team.id).collect())
+    }
+
+    /// Players whose name contains the search term, for leagues with a player lookup
+    fn search_players(&self, _term: &str) -> Option<Vec<Player>> {
+        None
+    }
+
+    /// A player's averages in the season the day belongs to
+    fn fetch_player_season(&self, _player: &Player, _day: DateTime<Utc>) -> Option<SeasonAverages> {
+        None
+    }
+
+    /// Active filter of the shown games, if any
+    fn filter(&self) -> Option<String> {
+        None
+    }
+
+    /// Run a league specific action, returning the day to navigate to. Actions
+    /// may change the provider's settings in the config, which is then saved.
+    fn league_action(&self, _action: &str, _day: DateTime<Utc>, _config: &mut Config) -> Option<DateTime<Utc>> {
+        None
+    }
+}
+
+/// All leagues that can be browsed, the first one is shown on startup
+pub fn build_providers(config: &Config) -> Vec<Arc<dyn Provider>> {
+    let mut providers: Vec<Arc<dyn Provider>> = vec![
+        Arc::new(nba::NbaProvider::new(config.api_key().unwrap_or_default(), api::client())),
+        Arc::new(nhl::NhlProvider),
+        Arc::new(mlb::MlbProvider),
+        Arc::new(soccer::SoccerProvider::new(&config.soccer)),
+    ];
+
+    // the Olympics join the other leagues while the Games are on, unless configured otherwise
+    if config.olympics.unwrap_or_else(|| olympics::in_period(Utc::now().date_naive())) {
+        providers.push(Arc::new(olympics::OlympicsProvider));
+    }
+
+    // nothing goes over the network when offline
+    if config.offline {
+        // App::new reports an archive that can not be opened
+        let archive = Archive::open(config).ok().flatten();
+        providers = providers.into_iter().map(|provider| Arc::new(offline::OfflineProvider::new(provider, archive.clone())) as Arc<dyn Provider>).collect();
+    }
+
+    providers
+}
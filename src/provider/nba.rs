@@ -0,0 +1,429 @@
+use super::api::{self, ApiClient};
+use super::{conditional, espn, Provider};
+use crate::details::{self, GameDetails, PlayerStats};
+use crate::players::{Player as PlayerInfo, SeasonAverages};
+use crate::standings::{self, Column, RankingRules};
+use crate::tournament::{self, Tournament};
+use crate::{Game, GameData, Team};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use serde::Deserialize;
+use std::sync::Arc;
+
+const API_URL: &str = "https://api.balldontlie.io/v1";
+
+// Cup groups are too narrow for the conference, division and streak columns
+const CUP_RULES: RankingRules = RankingRules {
+    columns: &[Column::Wins, Column::Losses, Column::Pct, Column::GamesBack],
+    ..standings::WIN_PCT
+};
+
+#[derive(Deserialize, Debug)]
+struct StatsData {
+    data: Vec<Stat>,
+}
+
+// A player's box score line from the stats endpoint, missing numbers mean DNP
+#[derive(Deserialize, Debug)]
+struct Stat {
+    min: Option<String>,
+    pts: Option<u32>,
+    reb: Option<u32>,
+    ast: Option<u32>,
+    fgm: Option<u32>,
+    fga: Option<u32>,
+    fg3m: Option<u32>,
+    fg3a: Option<u32>,
+    ftm: Option<u32>,
+    fta: Option<u32>,
+    player: Player,
+    team: Team,
+}
+
+#[derive(Deserialize, Debug)]
+struct Player {
+    first_name: String,
+    last_name: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct TeamsData {
+    data: Vec<Team>,
+}
+
+#[derive(Deserialize, Debug)]
+struct PlayersData {
+    data: Vec<PlayerEntry>,
+}
+
+// A player from the players endpoint, free agents have no team
+#[derive(Deserialize, Debug)]
+struct PlayerEntry {
+    id: u32,
+    first_name: String,
+    last_name: String,
+    #[serde(default)]
+    position: String,
+    #[serde(default)]
+    team: Option<Team>,
+}
+
+#[derive(Deserialize, Debug)]
+struct SeasonAveragesData {
+    data: Vec<SeasonAveragesEntry>,
+}
+
+#[derive(Deserialize, Debug)]
+struct SeasonAveragesEntry {
+    season: u32,
+    games_played: u32,
+    #[serde(default)]
+    min: String,
+    pts: f64,
+    reb: f64,
+    ast: f64,
+    stl: f64,
+    blk: f64,
+    fg_pct: f64,
+    fg3_pct: f64,
+    ft_pct: f64,
+}
+
+impl From<SeasonAveragesEntry> for SeasonAverages {
+    fn from(entry: SeasonAveragesEntry) -> Self {
+        SeasonAverages {
+            season: entry.season,
+            games_played: entry.games_played,
+            min: entry.min,
+            pts: entry.pts,
+            reb: entry.reb,
+            ast: entry.ast,
+            stl: entry.stl,
+            blk: entry.blk,
+            fg_pct: entry.fg_pct,
+            fg3_pct: entry.fg3_pct,
+            ft_pct: entry.ft_pct,
+        }
+    }
+}
+
+impl From<PlayerEntry> for PlayerInfo {
+    fn from(player: PlayerEntry) -> Self {
+        PlayerInfo {
+            id: player.id,
+            name: format!("{} {}", player.first_name, player.last_name),
+            position: player.position,
+            team: player.team.map(|team| team.abbreviation).unwrap_or_default(),
+        }
+    }
+}
+
+impl From<Stat> for PlayerStats {
+    fn from(stat: Stat) -> Self {
+        PlayerStats {
+            name: format!("{} {}", stat.player.first_name, stat.player.last_name),
+            team: stat.team.abbreviation,
+            min: stat.min.unwrap_or_default(),
+            pts: stat.pts.unwrap_or_default(),
+            reb: stat.reb.unwrap_or_default(),
+            ast: stat.ast.unwrap_or_default(),
+            fg: (stat.fgm.unwrap_or_default(), stat.fga.unwrap_or_default()),
+            fg3: (stat.fg3m.unwrap_or_default(), stat.fg3a.unwrap_or_default()),
+            ft: (stat.ftm.unwrap_or_default(), stat.fta.unwrap_or_default()),
+        }
+    }
+}
+
+// NBA games from the balldontlie API, sent through the client it is given
+pub struct NbaProvider {
+    api_key: String,
+    client: Arc<dyn ApiClient>,
+}
+
+impl NbaProvider {
+    pub fn new(api_key: &str, client: Arc<dyn ApiClient>) -> NbaProvider {
+        NbaProvider { api_key: api_key.to_string(), client }
+    }
+}
+
+impl Provider for NbaProvider {
+    fn id(&self) -> &'static str {
+        "nba"
+    }
+
+    fn name(&self) -> &'static str {
+        "NBA"
+    }
+
+    fn fetch_day(&self, day: DateTime<Utc>) -> Option<GameData> {
+        get_nba_data(&*self.client, day, &self.api_key)
+    }
+
+    fn seconds_left(&self, game: &Game) -> Option<u32> {
+        crate::clock::seconds_left(game, 4)
+    }
+
+    // two possessions
+    fn close_margin(&self) -> u32 {
+        6
+    }
+
+    fn fetch_details(&self, game: &Game) -> Option<GameDetails> {
+        let players = get_nba_stats(&*self.client, game.id, &self.api_key)?;
+        let leaders = details::leaders_from_players(game, &players);
+
+        Some(GameDetails { game: game.clone(), leaders, players, ..GameDetails::default() })
+    }
+
+    // NBA seasons start in October and are named after their first year
+    fn season_range(&self, season: i32) -> Option<(NaiveDate, NaiveDate)> {
+        Some((NaiveDate::from_ymd_opt(season, 10, 1)?, NaiveDate::from_ymd_opt(season + 1, 6, 30)?))
+    }
+
+    fn fetch_season(&self, day: DateTime<Utc>) -> Option<Vec<Game>> {
+        let season = if day.month() >= 10 { day.year() } else { day.year() - 1 };
+        get_nba_season(&*self.client, season, None, &self.api_key)
+    }
+
+    fn fetch_range(&self, start: NaiveDate, end: NaiveDate) -> Option<Vec<Game>> {
+        let query = vec![("start_date", start.format("%Y-%m-%d").to_string()), ("end_date", end.format("%Y-%m-%d").to_string())];
+        get_nba_games(&*self.client, query, &self.api_key)
+    }
+
+    fn fetch_schedule(&self, team: &Team, day: DateTime<Utc>) -> Option<Vec<Game>> {
+        let season = if day.month() >= 10 { day.year() } else { day.year() - 1 };
+        get_nba_season(&*self.client, season, Some(team.id), &self.api_key)
+    }
+
+    fn fetch_teams(&self) -> Option<Vec<Team>> {
+        get_nba_teams(&*self.client, &self.api_key)
+    }
+
+    fn search_players(&self, term: &str) -> Option<Vec<PlayerInfo>> {
+        get_nba_players(&*self.client, term, &self.api_key)
+    }
+
+    fn fetch_player_season(&self, player: &PlayerInfo, day: DateTime<Utc>) -> Option<SeasonAverages> {
+        let season = if day.month() >= 10 { day.year() } else { day.year() - 1 };
+        get_nba_season_averages(&*self.client, player.id, season, &self.api_key)
+    }
+
+    // balldontlie does not know about the NBA Cup, ESPN tags its games with the
+    // stage, e.g. "NBA Cup - East Group A"
+    fn fetch_tournament(&self, day: DateTime<Utc>) -> Option<Tournament> {
+        let season = if day.month() >= 10 { day.year() } else { day.year() - 1 };
+        let start = NaiveDate::from_ymd_opt(season, 11, 1)?;
+        let end = NaiveDate::from_ymd_opt(season, 12, 20)?;
+
+        let games = espn::fetch_range("basketball/nba", start, end)?
+            .into_iter()
+            .filter_map(|mut game| {
+                let stage = game.stage.take()?;
+                if !stage.contains("Cup") && !stage.contains("Tournament") {
+                    return None;
+                }
+                // keep the part that names the group or round
+                let name = stage.split(" - ").find(|part| !part.contains("Cup") && !part.contains("Tournament"));
+                game.stage = Some(name.unwrap_or(&stage).to_string());
+                Some(game)
+            })
+            .collect();
+
+        Some(tournament::build(&format!("NBA Cup {}", season), CUP_RULES, games))
+    }
+}
+
+fn get_nba_data(client: &dyn ApiClient, date_time: DateTime<Utc>, api_key: &str) -> Option<GameData> {
+    let date = date_time.format("%Y-%m-%d").to_string();
+
+    let query = format!("?dates[]={}", date);
+
+    // Build the request with the query parameters, live days are polled and an
+    // unchanged day is not sent again
+    let request = api::get(format!("{}/games{}", API_URL, query))
+        .header("Authorization", api_key);
+
+    conditional::get_json(client, request)
+}
+
+// Box score lines of both teams, a game never has more than a page of players
+fn get_nba_stats(client: &dyn ApiClient, game_id: u32, api_key: &str) -> Option<Vec<PlayerStats>> {
+    let request = api::get(format!("{}/stats", API_URL))
+        .query(&[("game_ids[]", game_id.to_string()), ("per_page", "100".to_string())])
+        .header("Authorization", api_key);
+    let response = client.send(request)?;
+
+    let stats: StatsData = response.json().ok()?;
+
+    Some(stats.data.into_iter().map(PlayerStats::from).collect())
+}
+
+// The 30 current franchises, balldontlie also lists historic teams without a division
+fn get_nba_teams(client: &dyn ApiClient, api_key: &str) -> Option<Vec<Team>> {
+    let request = api::get(format!("{}/teams", API_URL))
+        .header("Authorization", api_key);
+    let response = client.send(request)?;
+
+    let teams: TeamsData = response.json().ok()?;
+
+    Some(teams.data.into_iter().filter(|team| !team.division.trim().is_empty()).collect())
+}
+
+// Players matching a first or last name, the first page is enough for a lookup
+fn get_nba_players(client: &dyn ApiClient, query: &str, api_key: &str) -> Option<Vec<PlayerInfo>> {
+    let request = api::get(format!("{}/players", API_URL))
+        .query(&[("search", query), ("per_page", "100")])
+        .header("Authorization", api_key);
+    let response = client.send(request)?;
+
+    let players: PlayersData = response.json().ok()?;
+
+    Some(players.data.into_iter().map(PlayerInfo::from).collect())
+}
+
+// Averages of one player, empty data means the player did not play that season
+fn get_nba_season_averages(client: &dyn ApiClient, player_id: u32, season: i32, api_key: &str) -> Option<SeasonAverages> {
+    let request = api::get(format!("{}/season_averages", API_URL))
+        .query(&[("season", season.to_string()), ("player_id", player_id.to_string())])
+        .header("Authorization", api_key);
+    let response = client.send(request)?;
+
+    let averages: SeasonAveragesData = response.json().ok()?;
+
+    averages.data.into_iter().next().map(SeasonAverages::from)
+}
+
+// All games of a season, or a single team's
+fn get_nba_season(client: &dyn ApiClient, season: i32, team_id: Option<u32>, api_key: &str) -> Option<Vec<Game>> {
+    let mut query = vec![("seasons[]", season.to_string())];
+    if let Some(team_id) = team_id {
+        query.push(("team_ids[]", team_id.to_string()));
+    }
+    get_nba_games(client, query, api_key)
+}
+
+// Walk the cursor pagination over the games matching the query
+fn get_nba_games(client: &dyn ApiClient, filter: Vec<(&str, String)>, api_key: &str) -> Option<Vec<Game>> {
+    let mut games = Vec::new();
+    let mut cursor = None;
+
+    loop {
+        let mut query = filter.clone();
+        query.push(("per_page", "100".to_string()));
+        if let Some(cursor) = cursor {
+            query.push(("cursor", cursor));
+        }
+
+        let request = api::get(format!("{}/games", API_URL))
+            .query(&query)
+            .header("Authorization", api_key);
+        let response = client.send(request)?;
+
+        let page = parse_json(response.text().ok()?)?;
+        games.extend(page.data);
+
+        match page.meta.next_cursor {
+            Some(next) => cursor = Some(next.to_string()),
+            None => break,
+        }
+    }
+
+    Some(games)
+}
+
+// Ask the API for something cheap, a 401 means the key is not valid
+pub fn validate_api_key(client: &dyn ApiClient, api_key: &str) -> Result<bool> {
+    let request = api::get(format!("{}/teams", API_URL))
+        .header("Authorization", api_key)
+        .build()?;
+    let response = client.execute(request).context("no response from the API")?;
+
+    match response.status() {
+        reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => Ok(false),
+        status if status.is_success() => Ok(true),
+        status => Err(anyhow::anyhow!("unexpected response {}", status)),
+    }
+}
+
+// None for anything but games, e.g. a captive portal's page while offline
+fn parse_json(json_data: String) -> Option<GameData> {
+    serde_json::from_str(&json_data).ok()
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::api::Canned;
+    use chrono::TimeZone;
+
+    const GAMES: &str = include_str!("../../tests/data/nba/games.json");
+    const STATS: &str = include_str!("../../tests/data/nba/stats.json");
+
+    fn provider() -> NbaProvider {
+        let client = Canned::new()
+            .with(&format!("{}/games?dates[]=2024-01-15", API_URL), GAMES)
+            .with(&format!("{}/stats?game_ids[]=1037593&per_page=100", API_URL), STATS);
+        NbaProvider::new("key", Arc::new(client))
+    }
+
+    fn day() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 15, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn fetch_day_parses_the_games() {
+        let game_data = provider().fetch_day(day()).unwrap();
+        assert_eq!(game_data.data.len(), 2);
+        let game = &game_data.data[0];
+        assert_eq!((game.home_team.abbreviation.as_str(), game.visitor_team.abbreviation.as_str()), ("BOS", "LAL"));
+        assert_eq!((game.home_team_score, game.visitor_team_score), (118, 109));
+        assert!(game.is_final());
+        assert_eq!(game.linescore()[..4], [(30, 25), (28, 27), (31, 30), (29, 27)]);
+        assert!(!game_data.data[1].is_final());
+        assert_eq!(game_data.meta.next_cursor, None);
+    }
+
+    #[test]
+    fn fetch_day_fails_without_a_response() {
+        let other_day = day() + chrono::Duration::days(1);
+        assert_eq!(provider().fetch_day(other_day), None);
+    }
+
+    #[test]
+    fn validates_the_api_key_through_the_client() {
+        let client = Canned::new().with(&format!("{}/teams", API_URL), r#"{"data": []}"#);
+        assert!(validate_api_key(&client, "key").unwrap());
+        let error = validate_api_key(&Canned::new(), "key").unwrap_err();
+        assert_eq!(error.to_string(), "no response from the API");
+    }
+
+    #[test]
+    fn fetch_details_parses_the_box_score() {
+        let provider = provider();
+        let game = provider.fetch_day(day()).unwrap().data.remove(0);
+        let details = provider.fetch_details(&game).unwrap();
+        assert_eq!(details.game, game);
+        assert_eq!(details.players.len(), 4);
+        let tatum = &details.players[0];
+        assert_eq!((tatum.name.as_str(), tatum.team.as_str(), tatum.min.as_str()), ("Jayson Tatum", "BOS", "36:12"));
+        assert_eq!((tatum.pts, tatum.fg, tatum.fg3, tatum.ft), (31, (11, 20), (4, 9), (5, 6)));
+        // a player who did not play has an empty line
+        assert_eq!((details.players[3].min.as_str(), details.players[3].pts), ("", 0));
+
+        let leaders: Vec<(&str, &str, &str, &str)> = details
+            .leaders
+            .iter()
+            .map(|leader| (leader.team.as_str(), leader.category.as_str(), leader.name.as_str(), leader.value.as_str()))
+            .collect();
+        assert_eq!(
+            leaders,
+            [
+                ("BOS", "Points", "Jayson Tatum", "31"),
+                ("BOS", "Rebounds", "Jrue Holiday", "11"),
+                ("BOS", "Assists", "Jrue Holiday", "9"),
+                ("LAL", "Points", "LeBron James", "28"),
+                ("LAL", "Rebounds", "LeBron James", "12"),
+                ("LAL", "Assists", "LeBron James", "10"),
+            ]
+        );
+    }
+}
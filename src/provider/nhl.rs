@@ -0,0 +1,122 @@
+use super::{espn, Provider};
+use crate::standings::{Column, Criterion, RankingRules};
+use crate::{Game, GameData, Team};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+
+const SPORT: &str = "hockey/nhl";
+
+const REGULATION_PERIODS: u32 = 3;
+
+// Two points for a win, one for losing in overtime or the shootout
+const RULES: RankingRules = RankingRules {
+    win: 2,
+    draw: 0,
+    overtime_loss: 1,
+    regulation_periods: Some(REGULATION_PERIODS),
+    order: &[Criterion::Points, Criterion::FewerPlayed, Criterion::Wins],
+    columns: &[
+        Column::Played,
+        Column::Wins,
+        Column::Losses,
+        Column::OvertimeLosses,
+        Column::Points,
+        Column::Streak,
+        Column::LastTen,
+    ],
+};
+
+// NHL games from ESPN
+pub struct NhlProvider;
+
+impl NhlProvider {
+    // Regular season games past the third period are decided in overtime (period 4)
+    // or the shootout (period 5), playoff games only know overtimes
+    fn result_marker(game: &Game) -> Option<String> {
+        if !game.is_final() || game.period <= REGULATION_PERIODS {
+            return None;
+        }
+
+        let overtimes = game.period - REGULATION_PERIODS;
+        Some(match (game.postseason, overtimes) {
+            (_, 1) => "OT".to_string(),
+            (false, _) => "SO".to_string(),
+            (true, overtimes) => format!("{}OT", overtimes),
+        })
+    }
+}
+
+impl Provider for NhlProvider {
+    fn id(&self) -> &'static str {
+        "nhl"
+    }
+
+    fn name(&self) -> &'static str {
+        "NHL"
+    }
+
+    fn fetch_day(&self, day: DateTime<Utc>) -> Option<GameData> {
+        espn::fetch_day(SPORT, day).map(GameData::from_games)
+    }
+
+    // Finals decided after regulation, e.g. "3 - 2 OT" or "2 - 1 SO"
+    fn score(&self, game: &Game) -> String {
+        let score = format!("{} - {}", game.home_team_score, game.visitor_team_score);
+        match NhlProvider::result_marker(game) {
+            Some(marker) => format!("{} {}", score, marker),
+            None => score,
+        }
+    }
+
+    fn period_label(&self, index: usize) -> String {
+        crate::clock::period_label(index, 3)
+    }
+
+    fn seconds_left(&self, game: &Game) -> Option<u32> {
+        crate::clock::seconds_left(game, REGULATION_PERIODS)
+    }
+
+    fn close_margin(&self) -> u32 {
+        1
+    }
+
+    fn clock(&self, game: &Game) -> String {
+        if game.is_final() || !game.is_live() {
+            return crate::clock::period_clock(game);
+        }
+
+        let period = match game.period {
+            1..=3 => format!("P{}", game.period),
+            4 if !game.postseason => "OT".to_string(),
+            5 if !game.postseason => "SO".to_string(),
+            overtime => format!("{}OT", overtime - REGULATION_PERIODS),
+        };
+        match game.time.as_deref().map(str::trim).filter(|time| !time.is_empty()) {
+            Some(time) => format!("{} {}", period, time),
+            None => period,
+        }
+    }
+
+    fn ranking_rules(&self) -> RankingRules {
+        RULES
+    }
+
+    // The regular season starts in October, the playoffs end in June
+    fn season_range(&self, season: i32) -> Option<(NaiveDate, NaiveDate)> {
+        Some((NaiveDate::from_ymd_opt(season, 9, 1)?, NaiveDate::from_ymd_opt(season + 1, 6, 30)?))
+    }
+
+    fn fetch_season(&self, day: DateTime<Utc>) -> Option<Vec<Game>> {
+        let start_year = if day.month() >= 9 { day.year() } else { day.year() - 1 };
+        let (start, end) = self.season_range(start_year)?;
+
+        espn::fetch_range(SPORT, start, end)
+    }
+
+    fn fetch_range(&self, start: NaiveDate, end: NaiveDate) -> Option<Vec<Game>> {
+        espn::fetch_range(SPORT, start, end)
+    }
+
+    fn fetch_teams(&self) -> Option<Vec<Team>> {
+        espn::fetch_teams(SPORT)
+    }
+}
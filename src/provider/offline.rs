@@ -0,0 +1,123 @@
+use super::{LeagueBinding, Provider};
+use crate::archive::Archive;
+use crate::config::Config;
+use crate::decorator::RowDecorator;
+use crate::details::GameDetails;
+use crate::players::{Player, SeasonAverages};
+use crate::standings::{RankingRules, Standings};
+use crate::tournament::Tournament;
+use crate::{Game, GameData, Team};
+use chrono::{DateTime, NaiveDate, Utc};
+use std::sync::Arc;
+
+// A league without the network: games come from the archive, if it is turned on,
+// and everything else is unavailable. The league itself is unchanged.
+pub struct OfflineProvider {
+    inner: Arc<dyn Provider>,
+    archive: Option<Archive>,
+}
+
+impl OfflineProvider {
+    pub fn new(inner: Arc<dyn Provider>, archive: Option<Archive>) -> OfflineProvider {
+        OfflineProvider { inner, archive }
+    }
+}
+
+impl Provider for OfflineProvider {
+    fn id(&self) -> &'static str {
+        self.inner.id()
+    }
+
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn cache_key(&self) -> String {
+        self.inner.cache_key()
+    }
+
+    fn fetch_day(&self, day: DateTime<Utc>) -> Option<GameData> {
+        let games = self.fetch_range(day.date_naive(), day.date_naive())?;
+        (!games.is_empty()).then_some(GameData::from_games(games))
+    }
+
+    fn keybindings(&self) -> Vec<LeagueBinding> {
+        self.inner.keybindings()
+    }
+
+    fn clock(&self, game: &Game) -> String {
+        self.inner.clock(game)
+    }
+
+    fn period_label(&self, index: usize) -> String {
+        self.inner.period_label(index)
+    }
+
+    fn seconds_left(&self, game: &Game) -> Option<u32> {
+        self.inner.seconds_left(game)
+    }
+
+    fn close_margin(&self) -> u32 {
+        self.inner.close_margin()
+    }
+
+    fn score(&self, game: &Game) -> String {
+        self.inner.score(game)
+    }
+
+    fn row_decorators(&self) -> Vec<RowDecorator> {
+        self.inner.row_decorators()
+    }
+
+    fn fetch_details(&self, _game: &Game) -> Option<GameDetails> {
+        None
+    }
+
+    fn ranking_rules(&self) -> RankingRules {
+        self.inner.ranking_rules()
+    }
+
+    fn fetch_range(&self, start: NaiveDate, end: NaiveDate) -> Option<Vec<Game>> {
+        Some(self.archive.as_ref()?.range(&self.cache_key(), start, end))
+    }
+
+    fn season_range(&self, season: i32) -> Option<(NaiveDate, NaiveDate)> {
+        self.inner.season_range(season)
+    }
+
+    fn fetch_season(&self, _day: DateTime<Utc>) -> Option<Vec<Game>> {
+        None
+    }
+
+    fn fetch_standings(&self, _day: DateTime<Utc>) -> Option<Standings> {
+        None
+    }
+
+    fn fetch_tournament(&self, _day: DateTime<Utc>) -> Option<Tournament> {
+        None
+    }
+
+    fn fetch_teams(&self) -> Option<Vec<Team>> {
+        None
+    }
+
+    fn fetch_schedule(&self, _team: &Team, _day: DateTime<Utc>) -> Option<Vec<Game>> {
+        None
+    }
+
+    fn search_players(&self, _term: &str) -> Option<Vec<Player>> {
+        None
+    }
+
+    fn fetch_player_season(&self, _player: &Player, _day: DateTime<Utc>) -> Option<SeasonAverages> {
+        None
+    }
+
+    fn filter(&self) -> Option<String> {
+        self.inner.filter()
+    }
+
+    fn league_action(&self, action: &str, day: DateTime<Utc>, config: &mut Config) -> Option<DateTime<Utc>> {
+        self.inner.league_action(action, day, config)
+    }
+}
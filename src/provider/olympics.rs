@@ -0,0 +1,199 @@
+use super::{api, Provider};
+use crate::standings::{self, RankingRules, Standings, StandingsRow};
+use crate::{Game, GameData, Team};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Deserialize;
+
+// Daily schedule and medal table of the Olympic Games. Every discipline is
+// shown like a league of its own, so a single day mixes dozens of them.
+const API_URL: &str = "https://apis.codante.io/olympic-games";
+
+// Opening to closing ceremony of the Games the API has data for
+const PERIODS: [(&str, &str); 1] = [("2024-07-24", "2024-08-11")];
+
+pub fn in_period(day: NaiveDate) -> bool {
+    // ISO dates compare like the days they name
+    let day = day.format("%Y-%m-%d").to_string();
+    PERIODS.iter().any(|(start, end)| *start <= day.as_str() && day.as_str() <= *end)
+}
+
+#[derive(Deserialize, Debug)]
+struct Page<T> {
+    data: Vec<T>,
+    meta: PageMeta,
+}
+
+#[derive(Deserialize, Debug)]
+struct PageMeta {
+    current_page: u32,
+    last_page: u32,
+}
+
+#[derive(Deserialize, Debug)]
+struct Event {
+    id: u32,
+    day: String,
+    discipline_name: String,
+    #[serde(default)]
+    detailed_event_name: String,
+    start_date: String,
+    status: String,
+    #[serde(default)]
+    is_live: u8,
+    #[serde(default)]
+    competitors: Vec<Competitor>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Competitor {
+    country_id: String,
+    competitor_name: String,
+    #[serde(default)]
+    result_mark: Option<String>,
+    #[serde(default)]
+    result_position: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Country {
+    id: String,
+    gold_medals: u32,
+    silver_medals: u32,
+    bronze_medals: u32,
+}
+
+pub struct OlympicsProvider;
+
+impl Provider for OlympicsProvider {
+    fn id(&self) -> &'static str {
+        "olympics"
+    }
+
+    fn name(&self) -> &'static str {
+        "Olympics"
+    }
+
+    fn fetch_day(&self, day: DateTime<Utc>) -> Option<GameData> {
+        let date = day.format("%Y-%m-%d").to_string();
+        let events: Vec<Event> = fetch_pages("events", &[("date", date.as_str())])?;
+
+        let mut games: Vec<Game> = events.into_iter().map(to_game).collect();
+        games.sort_by(|a, b| a.date.cmp(&b.date));
+        Some(GameData::from_games(games))
+    }
+
+    // Head to head events show their result marks, races and finals only the leader
+    fn score(&self, game: &Game) -> String {
+        if game.home_team_score == 0 && game.visitor_team_score == 0 {
+            return String::new();
+        }
+        format!("{} - {}", game.home_team_score, game.visitor_team_score)
+    }
+
+    fn clock(&self, game: &Game) -> String {
+        if game.is_final() {
+            "Final".to_string()
+        } else if game.is_live() {
+            "Live".to_string()
+        } else {
+            crate::clock::start_time(game).unwrap_or_default()
+        }
+    }
+
+    fn ranking_rules(&self) -> RankingRules {
+        standings::MEDAL_TABLE
+    }
+
+    // The medal table comes ready made from the API
+    fn fetch_standings(&self, _day: DateTime<Utc>) -> Option<Standings> {
+        let countries: Vec<Country> = fetch_pages("countries", &[])?;
+
+        let rows = countries
+            .into_iter()
+            .map(|country| StandingsRow {
+                team: country.id,
+                gold: country.gold_medals,
+                silver: country.silver_medals,
+                bronze: country.bronze_medals,
+                ..StandingsRow::default()
+            })
+            .filter(|row| row.medals() > 0)
+            .collect();
+
+        Some(Standings::ranked(standings::MEDAL_TABLE, rows))
+    }
+}
+
+fn fetch_pages<T: serde::de::DeserializeOwned>(resource: &str, query: &[(&str, &str)]) -> Option<Vec<T>> {
+    let mut items = Vec::new();
+    let mut page = 1;
+
+    loop {
+        let request = api::get(format!("{}/{}", API_URL, resource))
+            .query(query)
+            .query(&[("page", page)]);
+        let response = api::client().send(request)?;
+
+        let body: Page<T> = response.json().ok()?;
+        items.extend(body.data);
+
+        if body.meta.current_page >= body.meta.last_page {
+            break;
+        }
+        page += 1;
+    }
+
+    Some(items)
+}
+
+// An event becomes a game of its discipline: the event itself is the "home"
+// side and the leading competitor the "visitor", head to head events keep
+// both competitors
+fn to_game(event: Event) -> Game {
+    let mut competitors = event.competitors;
+    competitors.sort_by_key(|competitor| {
+        competitor.result_position.as_deref().and_then(|position| position.parse::<u32>().ok()).unwrap_or(u32::MAX)
+    });
+
+    let mark = |competitor: Option<&Competitor>| {
+        competitor.and_then(|competitor| competitor.result_mark.as_deref()).and_then(|mark| mark.parse().ok()).unwrap_or_default()
+    };
+    let team = |competitor: &Competitor| Team {
+        abbreviation: competitor.country_id.clone(),
+        full_name: format!("{} ({})", competitor.competitor_name, competitor.country_id),
+        name: competitor.competitor_name.clone(),
+        ..Team::default()
+    };
+    let event_team = Team {
+        abbreviation: event.discipline_name.chars().take(3).collect::<String>().to_uppercase(),
+        full_name: event.detailed_event_name.clone(),
+        name: event.detailed_event_name.clone(),
+        ..Team::default()
+    };
+
+    let (home_team, visitor_team, home_team_score, visitor_team_score) = match competitors.as_slice() {
+        [first, second] => (team(first), team(second), mark(Some(first)), mark(Some(second))),
+        [first, ..] => (event_team, team(first), 0, 0),
+        [] => (event_team.clone(), event_team, 0, 0),
+    };
+
+    let finished = event.status == "Finished";
+    let status = if finished { "Final".to_string() } else { event.start_date.clone() };
+
+    Game {
+        id: event.id,
+        date: event.start_date.clone(),
+        datetime: Some(event.start_date),
+        home_team,
+        home_team_score,
+        period: u32::from(event.is_live > 0 || finished),
+        postseason: false,
+        season: event.day.get(..4).and_then(|year| year.parse().ok()).unwrap_or_default(),
+        status,
+        time: None,
+        visitor_team,
+        visitor_team_score,
+        competition: Some(event.discipline_name),
+        ..Game::default()
+    }
+}
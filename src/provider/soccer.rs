@@ -0,0 +1,174 @@
+use super::{espn, LeagueBinding, Provider};
+use crate::config::{Competition, Config, SoccerConfig};
+use crate::details::GameDetails;
+use crate::standings::{self, RankingRules};
+use crate::tournament::{self, Tournament};
+use crate::{Game, GameData, Team};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use crossterm::event::KeyCode::Char;
+
+// Soccer from ESPN: a domestic league plus the cups its teams play in
+pub struct SoccerProvider {
+    config: SoccerConfig,
+}
+
+impl SoccerProvider {
+    pub fn new(config: &SoccerConfig) -> SoccerProvider {
+        SoccerProvider { config: config.clone() }
+    }
+
+    // ESPN league slug of a competition, e.g. "eng.1" for the Premier League
+    fn slug(&self, competition: Competition) -> &str {
+        match competition {
+            Competition::League => &self.config.league,
+            Competition::DomesticCup => &self.config.domestic_cup,
+            Competition::EuropeanCup => &self.config.european_cup,
+        }
+    }
+}
+
+impl Provider for SoccerProvider {
+    fn id(&self) -> &'static str {
+        "soccer"
+    }
+
+    fn name(&self) -> &'static str {
+        "Soccer"
+    }
+
+    fn cache_key(&self) -> String {
+        let slugs: Vec<&str> = self.config.competitions.iter().map(|competition| self.slug(*competition)).collect();
+        format!("soccer-{}", slugs.join("+"))
+    }
+
+    fn fetch_day(&self, day: DateTime<Utc>) -> Option<GameData> {
+        let mut games = Vec::new();
+
+        for competition in &self.config.competitions {
+            let fetched = espn::fetch_day(&format!("soccer/{}", self.slug(*competition)), day)?;
+            games.extend(fetched.into_iter().map(|mut game| {
+                game.competition = Some(competition.label().to_string());
+                game
+            }));
+        }
+        games.sort_by(|a, b| a.date.cmp(&b.date));
+
+        Some(GameData::from_games(games))
+    }
+
+    fn filter(&self) -> Option<String> {
+        if self.config.competitions.len() == Competition::ALL.len() {
+            return None;
+        }
+        let labels: Vec<&str> = self.config.competitions.iter().map(Competition::label).collect();
+        Some(labels.join(", "))
+    }
+
+    // halves, extra time and the penalty shootout
+    fn period_label(&self, index: usize) -> String {
+        match index {
+            0 | 1 => (index + 1).to_string(),
+            2 | 3 => format!("ET{}", index - 1),
+            _ => "PEN".to_string(),
+        }
+    }
+
+    fn clock(&self, game: &Game) -> String {
+        crate::clock::soccer_clock(game)
+    }
+
+    // the summary endpoint works with the league slug of any competition
+    fn fetch_details(&self, game: &Game) -> Option<GameDetails> {
+        espn::fetch_details(&format!("soccer/{}", self.config.league), game)
+    }
+
+    fn ranking_rules(&self) -> RankingRules {
+        standings::LEAGUE_TABLE
+    }
+
+    // European seasons run from July to the end of June
+    fn season_range(&self, season: i32) -> Option<(NaiveDate, NaiveDate)> {
+        Some((NaiveDate::from_ymd_opt(season, 7, 1)?, NaiveDate::from_ymd_opt(season + 1, 6, 30)?))
+    }
+
+    // Only league games count for the table
+    fn fetch_season(&self, day: DateTime<Utc>) -> Option<Vec<Game>> {
+        let start_year = if day.month() >= 7 { day.year() } else { day.year() - 1 };
+        let (start, end) = self.season_range(start_year)?;
+
+        espn::fetch_range(&format!("soccer/{}", self.config.league), start, end)
+    }
+
+    // Games of every active competition, tagged like the day view
+    fn fetch_range(&self, start: NaiveDate, end: NaiveDate) -> Option<Vec<Game>> {
+        let mut games = Vec::new();
+        for competition in &self.config.competitions {
+            let fetched = espn::fetch_range(&format!("soccer/{}", self.slug(*competition)), start, end)?;
+            games.extend(fetched.into_iter().map(|mut game| {
+                game.competition = Some(competition.label().to_string());
+                game
+            }));
+        }
+        Some(games)
+    }
+
+    // League, cup and European games of the club, in every active competition
+    fn fetch_schedule(&self, team: &Team, day: DateTime<Utc>) -> Option<Vec<Game>> {
+        let start_year = if day.month() >= 7 { day.year() } else { day.year() - 1 };
+        let start = NaiveDate::from_ymd_opt(start_year, 7, 1)?;
+        let end = NaiveDate::from_ymd_opt(start_year + 1, 6, 30)?;
+
+        let games = self.fetch_range(start, end)?;
+        Some(games.into_iter().filter(|game| game.home_team.id == team.id || game.visitor_team.id == team.id).collect())
+    }
+
+    // Clubs of the configured league, cup opponents from other leagues are not listed
+    fn fetch_teams(&self) -> Option<Vec<Team>> {
+        espn::fetch_teams(&format!("soccer/{}", self.config.league))
+    }
+
+    // National team tournaments are played in one summer (or winter), club
+    // competitions follow the July to June season
+    fn fetch_tournament(&self, day: DateTime<Utc>) -> Option<Tournament> {
+        let slug = &self.config.tournament;
+        let (start, end) = if slug.starts_with("fifa.") || slug == "uefa.euro" {
+            (NaiveDate::from_ymd_opt(day.year(), 1, 1)?, NaiveDate::from_ymd_opt(day.year(), 12, 31)?)
+        } else {
+            let start_year = if day.month() >= 7 { day.year() } else { day.year() - 1 };
+            (NaiveDate::from_ymd_opt(start_year, 7, 1)?, NaiveDate::from_ymd_opt(start_year + 1, 6, 30)?)
+        };
+
+        let games = espn::fetch_range(&format!("soccer/{}", slug), start, end)?;
+        let name = match slug.as_str() {
+            "fifa.world" => "World Cup",
+            "uefa.euro" => "Euros",
+            "uefa.champions" => "Champions League",
+            other => other,
+        };
+
+        Some(tournament::build(&format!("{} {}", name, start.year()), standings::LEAGUE_TABLE, games))
+    }
+
+    fn keybindings(&self) -> Vec<LeagueBinding> {
+        vec![LeagueBinding { key: Char('c'), action: "competition", description: "competition filter" }]
+    }
+
+    // Cycle all competitions -> league -> domestic cup -> european cup -> all
+    fn league_action(&self, action: &str, day: DateTime<Utc>, config: &mut Config) -> Option<DateTime<Utc>> {
+        if action != "competition" {
+            return None;
+        }
+
+        let competitions = &mut config.soccer.competitions;
+        let next = match competitions.as_slice() {
+            [only] => Competition::ALL.iter().position(|competition| competition == only).map(|index| index + 1),
+            _ => Some(0),
+        };
+        *competitions = match next.and_then(|index| Competition::ALL.get(index)) {
+            Some(competition) => vec![*competition],
+            None => Competition::ALL.to_vec(),
+        };
+
+        Some(day)
+    }
+}
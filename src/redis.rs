@@ -0,0 +1,169 @@
+use crate::cache;
+use crate::store::Store;
+use crate::GameData;
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Utc};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::Mutex;
+use std::time::Duration;
+
+// Redis is shared with other instances, a slow one must not freeze the UI
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+// Every key starts with it, so the database can be shared with other programs
+const PREFIX: &str = "rust_sports_cli";
+
+// Where to connect, parsed from redis://[[user]:password@]host[:port][/db]
+struct Address {
+    host: String,
+    port: u16,
+    user: Option<String>,
+    password: Option<String>,
+    db: u32,
+}
+
+fn parse_url(url: &str) -> Result<Address> {
+    let rest = url.strip_prefix("redis://").ok_or_else(|| anyhow!("Redis URL {} does not start with redis://", url))?;
+    let (credentials, rest) = match rest.rsplit_once('@') {
+        Some((credentials, rest)) => (Some(credentials), rest),
+        None => (None, rest),
+    };
+    let (user, password) = match credentials.map(|credentials| credentials.split_once(':').unwrap_or(("", credentials))) {
+        Some((user, password)) => ((!user.is_empty()).then(|| user.to_string()), Some(password.to_string())),
+        None => (None, None),
+    };
+    let (host, db) = rest.split_once('/').unwrap_or((rest, ""));
+    let db = match db {
+        "" => 0,
+        db => db.parse().with_context(|| format!("Invalid Redis database {}", db))?,
+    };
+    let (host, port) = match host.rsplit_once(':') {
+        Some((host, port)) => (host, port.parse().with_context(|| format!("Invalid Redis port {}", port))?),
+        None => (host, 6379),
+    };
+    if host.is_empty() {
+        bail!("Redis URL {} has no host", url);
+    }
+    Ok(Address { host: host.to_string(), port, user, password, db })
+}
+
+enum Reply {
+    Simple,
+    Integer(i64),
+    Bulk(Option<Vec<u8>>),
+}
+
+struct Connection {
+    reader: BufReader<TcpStream>,
+    stream: TcpStream,
+}
+
+impl Connection {
+    fn open(address: &Address) -> Result<Connection> {
+        let socket = (address.host.as_str(), address.port)
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| anyhow!("Could not resolve {}", address.host))?;
+        let stream = TcpStream::connect_timeout(&socket, TIMEOUT).with_context(|| format!("Could not connect to Redis at {}:{}", address.host, address.port))?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        stream.set_write_timeout(Some(TIMEOUT))?;
+        let mut connection = Connection { reader: BufReader::new(stream.try_clone()?), stream };
+
+        if let Some(password) = &address.password {
+            match &address.user {
+                Some(user) => connection.command(&[b"AUTH", user.as_bytes(), password.as_bytes()])?,
+                None => connection.command(&[b"AUTH", password.as_bytes()])?,
+            };
+        }
+        if address.db != 0 {
+            connection.command(&[b"SELECT", address.db.to_string().as_bytes()])?;
+        }
+        Ok(connection)
+    }
+
+    // Sent as an array of bulk strings, which needs no escaping
+    fn command(&mut self, args: &[&[u8]]) -> Result<Reply> {
+        let mut request = format!("*{}\r\n", args.len()).into_bytes();
+        for arg in args {
+            request.extend(format!("${}\r\n", arg.len()).bytes());
+            request.extend_from_slice(arg);
+            request.extend(b"\r\n");
+        }
+        self.stream.write_all(&request)?;
+        self.reply()
+    }
+
+    fn reply(&mut self) -> Result<Reply> {
+        let mut line = String::new();
+        self.reader.read_line(&mut line)?;
+        let line = line.trim_end_matches("\r\n");
+        let (kind, rest) = line.split_at(line.len().min(1));
+        match kind {
+            "+" => Ok(Reply::Simple),
+            "-" => Err(anyhow!("Redis error: {}", rest)),
+            ":" => Ok(Reply::Integer(rest.parse()?)),
+            "$" => {
+                let Ok(length) = usize::try_from(rest.parse::<i64>()?) else {
+                    return Ok(Reply::Bulk(None));
+                };
+                let mut bulk = vec![0; length + 2];
+                self.reader.read_exact(&mut bulk)?;
+                bulk.truncate(length);
+                Ok(Reply::Bulk(Some(bulk)))
+            }
+            _ => Err(anyhow!("Unexpected Redis reply {:?}", line)),
+        }
+    }
+}
+
+// Finished days in Redis, shared by every instance pointing at the same server
+// and kept across restarts. The connection is opened again after any error.
+pub struct RedisStore {
+    address: Address,
+    connection: Mutex<Option<Connection>>,
+}
+
+impl RedisStore {
+    pub fn new(url: &str) -> Result<RedisStore> {
+        Ok(RedisStore { address: parse_url(url)?, connection: Mutex::new(None) })
+    }
+
+    fn key(provider: &str, day: DateTime<Utc>) -> String {
+        format!("{}:{}:{}", PREFIX, provider, day.format("%Y-%m-%d"))
+    }
+
+    fn command(&self, args: &[&[u8]]) -> Result<Reply> {
+        let mut connection = self.connection.lock().map_err(|_| anyhow!("Redis connection poisoned"))?;
+        let mut open = match connection.take() {
+            Some(open) => open,
+            None => Connection::open(&self.address)?,
+        };
+        // a failed command leaves the connection closed
+        let reply = open.command(args)?;
+        *connection = Some(open);
+        Ok(reply)
+    }
+}
+
+impl Store for RedisStore {
+    fn get(&self, provider: &str, day: DateTime<Utc>) -> Option<GameData> {
+        match self.command(&[b"GET", Self::key(provider, day).as_bytes()]).ok()? {
+            Reply::Bulk(Some(json)) => serde_json::from_slice(&json).ok(),
+            _ => None,
+        }
+    }
+
+    fn put(&self, provider: &str, day: DateTime<Utc>, game_data: &GameData) -> Result<()> {
+        if !cache::is_settled(day, game_data) {
+            return Ok(());
+        }
+        let json = serde_json::to_vec(game_data)?;
+        self.command(&[b"SET", Self::key(provider, day).as_bytes(), &json])?;
+        Ok(())
+    }
+
+    fn contains(&self, provider: &str, day: DateTime<Utc>) -> bool {
+        matches!(self.command(&[b"EXISTS", Self::key(provider, day).as_bytes()]), Ok(Reply::Integer(1..)))
+    }
+}
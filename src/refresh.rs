@@ -0,0 +1,162 @@
+use crate::details::GameDetails;
+use crate::players::{Player, SeasonAverages};
+use crate::standings::Standings;
+use crate::tournament::Tournament;
+use crate::{Game, GameData, Team};
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::HashMap;
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+// Frames of the spinner shown in the title of a block while it is refreshed
+const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+// Blocks that can be refreshed
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Target {
+    Day,
+    Details,
+    Standings,
+    Teams,
+    Schedule,
+    Players,
+    Player,
+    Tournament,
+    Calendar,
+    Week,
+    Followed,
+}
+
+// Result of a background refresh, tagged with what it was fetched for so stale
+// results can be dropped after the user moved on
+pub enum Refreshed {
+    Day { day: DateTime<Utc>, league: usize, cache_key: String, game_data: Option<GameData> },
+    // boxed, a box score is much larger than the other results
+    Details { league: usize, details: Option<Box<GameDetails>> },
+    Standings { day: DateTime<Utc>, league: usize, standings: Option<Standings> },
+    Teams { league: usize, teams: Option<Vec<Team>> },
+    Schedule { league: usize, team: Team, games: Option<Vec<Game>> },
+    Players { league: usize, query: String, players: Option<Vec<Player>> },
+    // a player's numbers of the season of the day
+    PlayerSeason { league: usize, player: Player, season: Option<SeasonAverages> },
+    Tournament { day: DateTime<Utc>, league: usize, tournament: Option<Box<Tournament>> },
+    Month { league: usize, month: (i32, u32), games: Option<Vec<Game>> },
+    Week { league: usize, start: NaiveDate, games: Option<Vec<Game>> },
+    // today's games of the favorite teams as (league, game), None when no league could be fetched
+    Followed { games: Option<Vec<(usize, Game)>> },
+}
+
+impl Target {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Target::Day => "games",
+            Target::Details => "box score",
+            Target::Standings => "standings",
+            Target::Teams => "teams",
+            Target::Schedule => "schedule",
+            Target::Players => "players",
+            Target::Player => "player",
+            Target::Tournament => "tournament",
+            Target::Calendar => "calendar",
+            Target::Week => "week",
+            Target::Followed => "followed games",
+        }
+    }
+}
+
+impl Refreshed {
+    pub fn target(&self) -> Target {
+        match self {
+            Refreshed::Day { .. } => Target::Day,
+            Refreshed::Details { .. } => Target::Details,
+            Refreshed::Standings { .. } => Target::Standings,
+            Refreshed::Teams { .. } => Target::Teams,
+            Refreshed::Schedule { .. } => Target::Schedule,
+            Refreshed::Players { .. } => Target::Players,
+            Refreshed::PlayerSeason { .. } => Target::Player,
+            Refreshed::Tournament { .. } => Target::Tournament,
+            Refreshed::Month { .. } => Target::Calendar,
+            Refreshed::Week { .. } => Target::Week,
+            Refreshed::Followed { .. } => Target::Followed,
+        }
+    }
+
+    // The fetch came back empty handed, e.g. offline or rate limited
+    pub fn failed(&self) -> bool {
+        match self {
+            Refreshed::Day { game_data, .. } => game_data.is_none(),
+            Refreshed::Details { details, .. } => details.is_none(),
+            Refreshed::Standings { standings, .. } => standings.is_none(),
+            Refreshed::Teams { teams, .. } => teams.is_none(),
+            Refreshed::Schedule { games, .. } => games.is_none(),
+            Refreshed::Players { players, .. } => players.is_none(),
+            Refreshed::PlayerSeason { season, .. } => season.is_none(),
+            Refreshed::Tournament { tournament, .. } => tournament.is_none(),
+            Refreshed::Month { games, .. } => games.is_none(),
+            Refreshed::Week { games, .. } => games.is_none(),
+            Refreshed::Followed { games } => games.is_none(),
+        }
+    }
+}
+
+// Runs refreshes and other slow fetches on their own threads, the results are picked up by the main loop
+pub struct Refresher {
+    // results are sent with the epoch they were spawned in
+    sender: Sender<(usize, Refreshed)>,
+    receiver: Receiver<(usize, Refreshed)>,
+    running: HashMap<Target, usize>,
+    epoch: usize,
+    tick: usize,
+}
+
+impl Default for Refresher {
+    fn default() -> Self {
+        let (sender, receiver) = channel();
+        Refresher { sender, receiver, running: HashMap::new(), epoch: 0, tick: 0 }
+    }
+}
+
+impl Refresher {
+    pub fn spawn(&mut self, target: Target, fetch: impl FnOnce() -> Refreshed + Send + 'static) {
+        *self.running.entry(target).or_default() += 1;
+        let (sender, epoch) = (self.sender.clone(), self.epoch);
+        std::thread::spawn(move || {
+            // the receiver only goes away when the app quits
+            let _ = sender.send((epoch, fetch()));
+        });
+    }
+
+    // Finished refreshes since the last call, also advances the spinner
+    pub fn poll(&mut self) -> Vec<Refreshed> {
+        self.tick = self.tick.wrapping_add(1);
+        let finished: Vec<Refreshed> = self.receiver.try_iter().filter(|(epoch, _)| *epoch == self.epoch).map(|(_, refreshed)| refreshed).collect();
+        for refreshed in &finished {
+            if let Some(running) = self.running.get_mut(&refreshed.target()) {
+                *running = running.saturating_sub(1);
+            }
+        }
+        finished
+    }
+
+    // Results of the refreshes running now are dropped when they come in, e.g. once
+    // the league indices they carry point to other providers
+    pub fn discard_running(&mut self) {
+        self.epoch += 1;
+        self.running.clear();
+    }
+
+    // Current spinner frame while anything is refreshed, empty otherwise
+    pub fn any_spinner(&self) -> &'static str {
+        match self.running.values().any(|running| *running > 0) {
+            true => SPINNER[self.tick % SPINNER.len()],
+            false => "",
+        }
+    }
+
+    // Current spinner frame while the target is refreshed, empty otherwise
+    pub fn spinner(&self, target: Target) -> &'static str {
+        match self.running.get(&target) {
+            Some(running) if *running > 0 => SPINNER[self.tick % SPINNER.len()],
+            _ => "",
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use crate::details::Leader;
+use crate::export::csv_field;
+use crate::standings::Standings;
+use crate::Game;
+use anyhow::Result;
+use clap::ValueEnum;
+use serde_json::{json, Value};
+
+// How the standings and leaders subcommands print what the TUI shows
+#[derive(ValueEnum, Clone, Copy, Debug, Default)]
+pub enum OutputFormat {
+    /// Aligned columns as in the TUI
+    #[default]
+    Table,
+    /// An array of objects as described by `sports schema`
+    Json,
+    /// Comma separated values with a header, the fields of the JSON objects
+    Csv,
+}
+
+// Fields of a standings row, see schema::Format::Standings
+const STANDINGS_FIELDS: [&str; 13] = [
+    "team",
+    "conference",
+    "division",
+    "wins",
+    "draws",
+    "losses",
+    "overtime_losses",
+    "points",
+    "scored",
+    "conceded",
+    "conference_record",
+    "division_record",
+    "results",
+];
+
+// Fields of a leader, see schema::Format::Leaders
+const LEADER_FIELDS: [&str; 5] = ["game_id", "team", "category", "name", "value"];
+
+pub fn standings(standings: &Standings, format: OutputFormat) -> Result<String> {
+    if let OutputFormat::Table = format {
+        let columns = standings.columns();
+        let mut header = vec!["#".to_string(), "Team".to_string()];
+        header.extend(columns.iter().map(|column| column.header().to_string()));
+        let rows = standings.rows.first().map_or_else(Vec::new, |leader| {
+            standings
+                .rows
+                .iter()
+                .enumerate()
+                .map(|(rank, row)| {
+                    let mut cells = vec![(rank + 1).to_string(), row.team.clone()];
+                    cells.extend(columns.iter().map(|column| column.value(row, leader)));
+                    cells
+                })
+                .collect()
+        });
+        return Ok(table(header, rows));
+    }
+
+    let objects = standings
+        .rows
+        .iter()
+        .map(|row| {
+            json!({
+                "team": row.team,
+                "conference": row.conference,
+                "division": row.division,
+                "wins": row.wins,
+                "draws": row.draws,
+                "losses": row.losses,
+                "overtime_losses": row.overtime_losses,
+                "points": row.points,
+                "scored": row.scored,
+                "conceded": row.conceded,
+                "conference_record": [row.conference_record.0, row.conference_record.1],
+                "division_record": [row.division_record.0, row.division_record.1],
+                "results": row.results.iter().map(char::to_string).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    structured(objects, &STANDINGS_FIELDS, format)
+}
+
+pub fn leaders(leaders: &[(Game, Leader)], format: OutputFormat) -> Result<String> {
+    if let OutputFormat::Table = format {
+        let header = ["Game", "Team", "Category", "Player", "Value"].map(str::to_string).to_vec();
+        let rows = leaders
+            .iter()
+            .map(|(game, leader)| {
+                let matchup = format!("{} vs {}", game.home_team.abbreviation, game.visitor_team.abbreviation);
+                vec![matchup, leader.team.clone(), leader.category.clone(), leader.name.clone(), leader.value.clone()]
+            })
+            .collect();
+        return Ok(table(header, rows));
+    }
+
+    let objects = leaders
+        .iter()
+        .map(|(game, leader)| {
+            json!({
+                "game_id": game.id,
+                "team": leader.team,
+                "category": leader.category,
+                "name": leader.name,
+                "value": leader.value,
+            })
+        })
+        .collect();
+    structured(objects, &LEADER_FIELDS, format)
+}
+
+// Columns padded to their widest cell, numbers stay left aligned like the TUI's tables
+fn table(header: Vec<String>, rows: Vec<Vec<String>>) -> String {
+    let widths: Vec<usize> = (0..header.len())
+        .map(|index| std::iter::once(&header).chain(&rows).map(|row| row[index].chars().count()).max().unwrap_or_default())
+        .collect();
+    std::iter::once(&header)
+        .chain(&rows)
+        .map(|row| {
+            let cells: Vec<String> = row.iter().zip(&widths).map(|(cell, width)| format!("{:<width$}", cell, width = width)).collect();
+            format!("{}\n", cells.join("  ").trim_end())
+        })
+        .collect()
+}
+
+// JSON as is, CSV with a column per field where arrays are joined, e.g. a record as 12-3
+fn structured(objects: Vec<Value>, fields: &[&str], format: OutputFormat) -> Result<String> {
+    if let OutputFormat::Json = format {
+        return Ok(format!("{}\n", serde_json::to_string_pretty(&objects)?));
+    }
+    let cell = |value: &Value| match value {
+        Value::String(text) => text.clone(),
+        Value::Array(items) => {
+            let separator = if items.iter().all(Value::is_number) { "-" } else { "" };
+            items.iter().map(|item| item.as_str().map_or_else(|| item.to_string(), str::to_string)).collect::<Vec<_>>().join(separator)
+        }
+        value => value.to_string(),
+    };
+    let mut csv = format!("{}\n", fields.join(","));
+    for object in &objects {
+        let cells: Vec<String> = fields.iter().map(|field| csv_field(&object.get(field).map(cell).unwrap_or_default())).collect();
+        csv.push_str(&format!("{}\n", cells.join(",")));
+    }
+    Ok(csv)
+}
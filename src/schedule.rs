@@ -0,0 +1,83 @@
+use crate::{clock, Game, Team};
+use crate::theme::Theme;
+use ratatui::{
+    prelude::{Constraint, Frame, Rect},
+    widgets::{Cell, Row, Scrollbar, ScrollbarOrientation, ScrollbarState, Table, TableState},
+};
+use std::cmp::Ordering;
+
+// Season schedule of one team, opened from the teams view
+pub struct Schedule {
+    pub team: Team,
+    pub games: Vec<Game>,
+    pub state: TableState,
+}
+
+impl Schedule {
+    // Games in date order, starting at the next game that is not finished
+    pub fn new(team: Team, mut games: Vec<Game>) -> Schedule {
+        games.sort_by(|a, b| a.date.cmp(&b.date));
+        let next = games.iter().position(|game| !game.is_final()).or(games.len().checked_sub(1));
+        Schedule { team, games, state: TableState::default().with_selected(next) }
+    }
+
+    pub fn select(&mut self, delta: isize) {
+        if self.games.is_empty() {
+            return;
+        }
+        let selected = self.state.selected().unwrap_or_default() as isize + delta;
+        self.state.select(Some(selected.clamp(0, self.games.len() as isize - 1) as usize));
+    }
+}
+
+pub fn ui(schedule: &mut Schedule, theme: &Theme, f: &mut Frame, area: Rect) {
+    let block = theme.block(format!("{} schedule (back: esc)", schedule.team.full_name));
+    let team_id = schedule.team.id;
+    let with_competition = schedule.games.iter().any(|game| game.competition.is_some());
+
+    let rows = schedule.games.iter().map(|game| {
+        let home = game.home_team.id == team_id;
+        let (opponent, scored, conceded) = if home {
+            (format!("vs {}", game.visitor_team.full_name), game.home_team_score, game.visitor_team_score)
+        } else {
+            (format!("@ {}", game.home_team.full_name), game.visitor_team_score, game.home_team_score)
+        };
+
+        let result = if game.is_final() {
+            let (label, style) = match scored.cmp(&conceded) {
+                Ordering::Greater => ("W", theme.good),
+                Ordering::Equal => ("D", theme.warning),
+                Ordering::Less => ("L", theme.bad),
+            };
+            Cell::from(format!("{} {}-{}", label, scored, conceded)).style(style)
+        } else {
+            Cell::from(clock::start_time(game).unwrap_or_default())
+        };
+
+        let mut cells = vec![Cell::from(game.date.chars().take(10).collect::<String>()), Cell::from(opponent), result];
+        if with_competition {
+            cells.push(Cell::from(game.competition.clone().unwrap_or_default()));
+        }
+        Row::new(cells)
+    });
+
+    let mut header = vec!["Date", "Opponent", "Result"];
+    let mut widths = vec![Constraint::Length(10), Constraint::Fill(1), Constraint::Length(10)];
+    if with_competition {
+        header.push("Comp");
+        widths.push(Constraint::Length(8));
+    }
+
+    let table = Table::new(rows, widths)
+        .header(Row::new(header).style(theme.header))
+        .column_spacing(2)
+        .highlight_style(theme.selected)
+        .block(block);
+    f.render_stateful_widget(table, area, &mut schedule.state);
+
+    if theme.plain {
+        return;
+    }
+    let mut scrollbar = ScrollbarState::new(schedule.games.len()).position(schedule.state.selected().unwrap_or_default());
+    f.render_stateful_widget(Scrollbar::new(ScrollbarOrientation::VerticalRight), area, &mut scrollbar);
+}
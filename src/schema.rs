@@ -0,0 +1,140 @@
+use anyhow::{bail, Result};
+use clap::ValueEnum;
+use serde_json::{json, Value};
+
+// Raised whenever fields are added, renamed or removed. Scripts pin a version with
+// --output-version and keep getting the fields of that version.
+pub const SCHEMA_VERSION: u32 = 1;
+
+// Pinned versions that still work but are going away
+const DEPRECATED_VERSIONS: &[u32] = &[];
+
+const DRAFT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum Format {
+    /// One game as exported, the CSV columns in order
+    Games,
+    /// One team of the standings
+    Standings,
+    /// A score change or final of a followed game
+    Events,
+    /// One top performer of a game, as listed by the leaders command
+    Leaders,
+}
+
+// The asked for output version, the latest when none was asked for
+pub fn output_version(requested: Option<u32>) -> Result<u32> {
+    let version = requested.unwrap_or(SCHEMA_VERSION);
+    if !(1..=SCHEMA_VERSION).contains(&version) {
+        bail!("Unknown output version {}, the latest is {}", version, SCHEMA_VERSION);
+    }
+    if DEPRECATED_VERSIONS.contains(&version) {
+        eprintln!("Warning: output version {} is deprecated, the latest is {}", version, SCHEMA_VERSION);
+    }
+    Ok(version)
+}
+
+// Columns of an exported game, one list per output version starting with 1
+const GAME_COLUMNS: &[&[&str]] =
+    &[&["date", "league", "competition", "visitor", "visitor_score", "home", "home_score", "status", "period", "postseason"]];
+
+pub fn game_columns(version: u32) -> &'static [&'static str] {
+    let index = (version.clamp(1, GAME_COLUMNS.len() as u32) - 1) as usize;
+    GAME_COLUMNS[index]
+}
+
+// JSON Schema of one record of an output format
+pub fn schema(format: Format, version: u32) -> Value {
+    let (title, properties, required) = match format {
+        Format::Games => (
+            "game",
+            pick(game_columns(version), json!({
+                "date": { "type": "string", "format": "date" },
+                "league": { "type": "string", "description": "league name, e.g. NBA" },
+                "competition": { "type": "string", "description": "empty unless the league mixes competitions" },
+                "visitor": { "type": "string", "description": "team abbreviation" },
+                "visitor_score": { "type": "integer", "minimum": 0 },
+                "home": { "type": "string", "description": "team abbreviation" },
+                "home_score": { "type": "integer", "minimum": 0 },
+                "status": { "type": "string", "description": "\"Final\", a period or a start time" },
+                "period": { "type": "integer", "minimum": 0 },
+                "postseason": { "type": "boolean" },
+            })),
+            game_columns(version).to_vec(),
+        ),
+        Format::Standings => (
+            "standings row",
+            json!({
+                "team": { "type": "string" },
+                "conference": { "type": "string" },
+                "division": { "type": "string" },
+                "wins": { "type": "integer", "minimum": 0 },
+                "draws": { "type": "integer", "minimum": 0 },
+                "losses": { "type": "integer", "minimum": 0 },
+                "overtime_losses": { "type": "integer", "minimum": 0 },
+                "points": { "type": "integer", "minimum": 0 },
+                "scored": { "type": "integer", "minimum": 0, "description": "goals, runs or points scored" },
+                "conceded": { "type": "integer", "minimum": 0 },
+                "conference_record": record(),
+                "division_record": record(),
+                "results": {
+                    "type": "array",
+                    "description": "results oldest first: W win, D draw, L loss, O loss in overtime or a shootout",
+                    "items": { "type": "string", "enum": ["W", "D", "L", "O"] },
+                },
+            }),
+            vec!["team", "wins", "losses", "points", "scored", "conceded"],
+        ),
+        Format::Events => (
+            "game event",
+            json!({
+                "kind": { "type": "string", "enum": ["score", "final"] },
+                "game_id": { "type": "integer", "minimum": 0 },
+                "league": { "type": "string" },
+                "home": { "type": "string", "description": "team abbreviation" },
+                "home_score": { "type": "integer", "minimum": 0 },
+                "visitor": { "type": "string", "description": "team abbreviation" },
+                "visitor_score": { "type": "integer", "minimum": 0 },
+                "at": { "type": "string", "format": "date-time" },
+            }),
+            vec!["kind", "game_id", "league", "home", "home_score", "visitor", "visitor_score", "at"],
+        ),
+        Format::Leaders => (
+            "game leader",
+            json!({
+                "game_id": { "type": "integer", "minimum": 0 },
+                "team": { "type": "string", "description": "team abbreviation" },
+                "category": { "type": "string", "description": "e.g. Points" },
+                "name": { "type": "string", "description": "player name" },
+                "value": { "type": "string", "description": "as the league reports it, e.g. 31" },
+            }),
+            vec!["game_id", "team", "category", "name", "value"],
+        ),
+    };
+
+    json!({
+        "$schema": DRAFT,
+        "title": format!("sports {}", title),
+        "x-version": version,
+        "type": "object",
+        "properties": properties,
+        "required": required,
+    })
+}
+
+// Only the properties of the given fields, e.g. the columns of an older version
+fn pick(fields: &[&str], properties: Value) -> Value {
+    let properties = fields.iter().filter_map(|field| Some((field.to_string(), properties.get(field)?.clone()))).collect();
+    Value::Object(properties)
+}
+
+// [wins, losses]
+fn record() -> Value {
+    json!({
+        "type": "array",
+        "items": { "type": "integer", "minimum": 0 },
+        "minItems": 2,
+        "maxItems": 2,
+    })
+}
@@ -0,0 +1,24 @@
+use crate::theme::Theme;
+use ratatui::{
+    prelude::{Frame, Rect},
+    widgets::{Scrollbar, ScrollbarOrientation, ScrollbarState, Table, TableState},
+};
+
+// Rows a scroll key moves a table by
+pub const STEP: usize = 5;
+
+// Draw a bordered table with a one line header and rows of one line, scrolled down
+// to the offset. The title and the column header stay in place while the rows move
+// under them, the offset stops once the last row is in view.
+pub fn render(table: Table, rows: usize, offset: usize, theme: &Theme, f: &mut Frame, area: Rect) {
+    let visible = area.height.saturating_sub(3) as usize;
+    let offset = offset.min(rows.saturating_sub(visible));
+    let mut state = TableState::default().with_offset(offset);
+    f.render_stateful_widget(table, area, &mut state);
+
+    if theme.plain || rows <= visible {
+        return;
+    }
+    let mut scrollbar = ScrollbarState::new(rows - visible + 1).position(offset);
+    f.render_stateful_widget(Scrollbar::new(ScrollbarOrientation::VerticalRight), area, &mut scrollbar);
+}
@@ -0,0 +1,79 @@
+use crate::theme::Theme;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    prelude::{Constraint, Direction, Frame, Layout},
+    widgets::{Paragraph, Wrap},
+};
+
+// First-run wizard state, asks for the balldontlie API key
+#[derive(Default)]
+pub struct SetupState {
+    pub input: String,
+    pub error: Option<String>,
+}
+
+// What the wizard wants the app to do after a key press
+pub enum SetupOutcome {
+    Pending,
+    Submit(String),
+    Cancel,
+}
+
+impl SetupState {
+    pub fn handle_key(&mut self, key: KeyEvent) -> SetupOutcome {
+        match key.code {
+            KeyCode::Char(c) => {
+                self.input.push(c);
+                self.error = None;
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Enter => {
+                let key = self.input.trim().to_string();
+                if key.is_empty() {
+                    self.error = Some("The API key can not be empty.".to_string());
+                } else {
+                    return SetupOutcome::Submit(key);
+                }
+            }
+            KeyCode::Esc => return SetupOutcome::Cancel,
+            _ => {}
+        }
+        SetupOutcome::Pending
+    }
+}
+
+pub fn ui(state: &SetupState, theme: &Theme, f: &mut Frame) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(6), Constraint::Length(3), Constraint::Length(3)])
+        .split(f.size());
+
+    let mut intro = String::new();
+    intro.push_str("Welcome! The balldontlie API requires an API key.\n\n");
+    intro.push_str("Create a free account at https://www.balldontlie.io to get one,\n");
+    intro.push_str("then paste it below and press Enter to validate and save it.\n\n");
+    intro.push_str(&format!("The key is stored in {}\n", crate::config::config_path().display()));
+    intro.push_str("Esc: quit");
+
+    f.render_widget(
+        Paragraph::new(intro).wrap(Wrap { trim: false }).block(theme.block("Setup")),
+        chunks[0],
+    );
+
+    f.render_widget(
+        Paragraph::new(state.input.as_str()).block(theme.block("API key")),
+        chunks[1],
+    );
+
+    if let Some(error) = &state.error {
+        f.render_widget(
+            Paragraph::new(error.as_str()).style(theme.bad).block(theme.block("")),
+            chunks[2],
+        );
+    }
+
+    // Cursor right after the typed text inside the input block
+    f.set_cursor(chunks[1].x + 1 + state.input.chars().count() as u16, chunks[1].y + 1);
+}
@@ -0,0 +1,50 @@
+use crate::provider::Provider;
+use crate::Game;
+use crate::theme::Theme;
+use ratatui::{
+    prelude::{Frame, Rect},
+    widgets::{Paragraph, Wrap},
+};
+use std::collections::HashSet;
+
+// Scores of started games are hidden for people watching replays, games are
+// revealed one at a time
+#[derive(Default)]
+pub struct Spoilers {
+    pub hidden: bool,
+    revealed: HashSet<u32>,
+}
+
+impl Spoilers {
+    pub fn new(hidden: bool) -> Spoilers {
+        Spoilers { hidden, revealed: HashSet::new() }
+    }
+
+    // Revealed games stay revealed when scores are hidden again
+    pub fn toggle(&mut self) {
+        self.hidden = !self.hidden;
+    }
+
+    pub fn reveal(&mut self, game: &Game) {
+        self.revealed.insert(game.id);
+    }
+
+    // Whether anything that gives away the result of the game may be shown
+    pub fn shows(&self, game: &Game) -> bool {
+        !self.hidden || self.revealed.contains(&game.id) || !(game.is_live() || game.is_final())
+    }
+
+    pub fn score(&self, provider: &dyn Provider, game: &Game) -> String {
+        match self.shows(game) {
+            true => provider.score(game),
+            false => "? - ?".to_string(),
+        }
+    }
+}
+
+// Stands in for the box score of a game whose score is hidden
+pub fn ui(game: &Game, theme: &Theme, f: &mut Frame, area: Rect) {
+    let title = format!("{} vs {}", game.home_team.full_name, game.visitor_team.full_name);
+    let text = "Scores are hidden. Press e to reveal this game, H to show all scores.";
+    f.render_widget(Paragraph::new(text).wrap(Wrap { trim: true }).block(theme.block(title)), area);
+}
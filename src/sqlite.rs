@@ -0,0 +1,414 @@
+use crate::archive::game_date;
+use crate::cache;
+use crate::store::Store;
+use crate::{Game, GameData};
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use std::ffi::{c_char, c_int, c_void, CStr, CString};
+use std::path::Path;
+use std::ptr;
+use std::sync::Mutex;
+
+// Bindings to the system libsqlite3, only what the store and the archive use
+#[allow(non_camel_case_types)]
+type sqlite3 = c_void;
+#[allow(non_camel_case_types)]
+type sqlite3_stmt = c_void;
+
+const SQLITE_OK: c_int = 0;
+const SQLITE_ROW: c_int = 100;
+const SQLITE_DONE: c_int = 101;
+const SQLITE_NULL: c_int = 5;
+const SQLITE_OPEN_READWRITE: c_int = 0x2;
+const SQLITE_OPEN_CREATE: c_int = 0x4;
+const SQLITE_OPEN_FULLMUTEX: c_int = 0x10000;
+
+// SQLITE_TRANSIENT, the text is copied before the bind returns
+fn transient() -> Option<unsafe extern "C" fn(*mut c_void)> {
+    unsafe { std::mem::transmute::<isize, Option<unsafe extern "C" fn(*mut c_void)>>(-1) }
+}
+
+#[link(name = "sqlite3")]
+extern "C" {
+    fn sqlite3_open_v2(filename: *const c_char, db: *mut *mut sqlite3, flags: c_int, vfs: *const c_char) -> c_int;
+    fn sqlite3_close(db: *mut sqlite3) -> c_int;
+    fn sqlite3_errmsg(db: *mut sqlite3) -> *const c_char;
+    fn sqlite3_busy_timeout(db: *mut sqlite3, ms: c_int) -> c_int;
+    fn sqlite3_exec(
+        db: *mut sqlite3,
+        sql: *const c_char,
+        callback: *const c_void,
+        arg: *mut c_void,
+        errmsg: *mut *mut c_char,
+    ) -> c_int;
+    fn sqlite3_prepare_v2(db: *mut sqlite3, sql: *const c_char, bytes: c_int, stmt: *mut *mut sqlite3_stmt, tail: *mut *const c_char) -> c_int;
+    fn sqlite3_bind_text(
+        stmt: *mut sqlite3_stmt,
+        index: c_int,
+        text: *const c_char,
+        bytes: c_int,
+        destructor: Option<unsafe extern "C" fn(*mut c_void)>,
+    ) -> c_int;
+    fn sqlite3_bind_int64(stmt: *mut sqlite3_stmt, index: c_int, value: i64) -> c_int;
+    fn sqlite3_step(stmt: *mut sqlite3_stmt) -> c_int;
+    fn sqlite3_column_count(stmt: *mut sqlite3_stmt) -> c_int;
+    fn sqlite3_column_type(stmt: *mut sqlite3_stmt, column: c_int) -> c_int;
+    fn sqlite3_column_text(stmt: *mut sqlite3_stmt, column: c_int) -> *const u8;
+    fn sqlite3_column_bytes(stmt: *mut sqlite3_stmt, column: c_int) -> c_int;
+    fn sqlite3_finalize(stmt: *mut sqlite3_stmt) -> c_int;
+    fn sqlite3_free(ptr: *mut c_void);
+}
+
+// Another instance writing the same file waits this long before giving up
+const BUSY_TIMEOUT_MS: c_int = 2000;
+
+/// A parameter bound to a `?` of a statement
+pub enum Param<'a> {
+    Text(&'a str),
+    Integer(i64),
+}
+
+/// Column values of a row as text, None for NULL
+pub type Row = Vec<Option<String>>;
+
+/// An open database, statements are run one at a time behind a lock by its users
+pub struct Connection {
+    db: *mut sqlite3,
+}
+
+// Opened with SQLITE_OPEN_FULLMUTEX, the handle may move between threads
+unsafe impl Send for Connection {}
+
+impl Connection {
+    /// Creates the file when it does not exist yet
+    pub fn open(path: &Path) -> Result<Connection> {
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|error| anyhow!("Could not create directory {}: {}", parent.display(), error))?;
+        }
+        Self::open_name(&path.to_string_lossy())
+    }
+
+    pub fn open_in_memory() -> Result<Connection> {
+        Self::open_name(":memory:")
+    }
+
+    fn open_name(name: &str) -> Result<Connection> {
+        let filename = CString::new(name)?;
+        let mut db = ptr::null_mut();
+        let flags = SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE | SQLITE_OPEN_FULLMUTEX;
+        let code = unsafe { sqlite3_open_v2(filename.as_ptr(), &mut db, flags, ptr::null()) };
+        // a handle is returned even when opening fails and must be closed
+        let connection = Connection { db };
+        if code != SQLITE_OK {
+            bail!("Could not open SQLite database {}: {}", name, connection.error());
+        }
+        unsafe { sqlite3_busy_timeout(db, BUSY_TIMEOUT_MS) };
+        Ok(connection)
+    }
+
+    fn error(&self) -> String {
+        if self.db.is_null() {
+            return "out of memory".to_string();
+        }
+        unsafe { CStr::from_ptr(sqlite3_errmsg(self.db)) }.to_string_lossy().into_owned()
+    }
+
+    /// Runs statements separated by semicolons, without parameters
+    pub fn execute_batch(&self, sql: &str) -> Result<()> {
+        let sql = CString::new(sql)?;
+        let mut message = ptr::null_mut();
+        let code = unsafe { sqlite3_exec(self.db, sql.as_ptr(), ptr::null(), ptr::null_mut(), &mut message) };
+        if code == SQLITE_OK {
+            return Ok(());
+        }
+        let error = match message.is_null() {
+            true => self.error(),
+            false => {
+                let error = unsafe { CStr::from_ptr(message) }.to_string_lossy().into_owned();
+                unsafe { sqlite3_free(message.cast()) };
+                error
+            }
+        };
+        bail!("SQLite: {}", error)
+    }
+
+    pub fn execute(&self, sql: &str, params: &[Param]) -> Result<()> {
+        self.query(sql, params).map(drop)
+    }
+
+    pub fn query(&self, sql: &str, params: &[Param]) -> Result<Vec<Row>> {
+        let statement = Statement::prepare(self, sql)?;
+        for (index, param) in params.iter().enumerate() {
+            statement.bind(index as c_int + 1, param)?;
+        }
+        let mut rows = Vec::new();
+        loop {
+            match unsafe { sqlite3_step(statement.stmt) } {
+                SQLITE_ROW => rows.push(statement.row()),
+                SQLITE_DONE => return Ok(rows),
+                _ => bail!("SQLite: {}", self.error()),
+            }
+        }
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        unsafe { sqlite3_close(self.db) };
+    }
+}
+
+// Finalized when dropped, also after an error
+struct Statement<'a> {
+    connection: &'a Connection,
+    stmt: *mut sqlite3_stmt,
+}
+
+impl<'a> Statement<'a> {
+    fn prepare(connection: &'a Connection, sql: &str) -> Result<Statement<'a>> {
+        let text = CString::new(sql)?;
+        let mut stmt = ptr::null_mut();
+        let code = unsafe { sqlite3_prepare_v2(connection.db, text.as_ptr(), -1, &mut stmt, ptr::null_mut()) };
+        if code != SQLITE_OK {
+            bail!("SQLite: {} in {}", connection.error(), sql);
+        }
+        Ok(Statement { connection, stmt })
+    }
+
+    fn bind(&self, index: c_int, param: &Param) -> Result<()> {
+        let code = match param {
+            Param::Text(text) => unsafe { sqlite3_bind_text(self.stmt, index, text.as_ptr().cast(), text.len() as c_int, transient()) },
+            Param::Integer(value) => unsafe { sqlite3_bind_int64(self.stmt, index, *value) },
+        };
+        if code != SQLITE_OK {
+            bail!("SQLite: {}", self.connection.error());
+        }
+        Ok(())
+    }
+
+    fn row(&self) -> Row {
+        let columns = unsafe { sqlite3_column_count(self.stmt) };
+        (0..columns)
+            .map(|column| unsafe {
+                if sqlite3_column_type(self.stmt, column) == SQLITE_NULL {
+                    return None;
+                }
+                // the pointer is read before the length, as sqlite3 asks
+                let text = sqlite3_column_text(self.stmt, column);
+                let bytes = sqlite3_column_bytes(self.stmt, column) as usize;
+                match text.is_null() {
+                    true => Some(String::new()),
+                    false => Some(String::from_utf8_lossy(std::slice::from_raw_parts(text, bytes)).into_owned()),
+                }
+            })
+            .collect()
+    }
+}
+
+impl Drop for Statement<'_> {
+    fn drop(&mut self) {
+        unsafe { sqlite3_finalize(self.stmt) };
+    }
+}
+
+// Finished days in an SQLite file, which other instances on the machine can share
+pub struct SqliteStore {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteStore {
+    pub fn open(path: &Path) -> Result<SqliteStore> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    fn with_connection(connection: Connection) -> Result<SqliteStore> {
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS days (
+                provider TEXT NOT NULL,
+                day TEXT NOT NULL,
+                game_data TEXT NOT NULL,
+                PRIMARY KEY (provider, day)
+            )",
+        )?;
+        Ok(SqliteStore { connection: Mutex::new(connection) })
+    }
+
+    fn query(&self, sql: &str, params: &[Param]) -> Result<Vec<Row>> {
+        let connection = self.connection.lock().map_err(|_| anyhow!("SQLite connection poisoned"))?;
+        connection.query(sql, params)
+    }
+}
+
+impl Store for SqliteStore {
+    fn get(&self, provider: &str, day: DateTime<Utc>) -> Option<GameData> {
+        let day = day.format("%Y-%m-%d").to_string();
+        let rows = self.query("SELECT game_data FROM days WHERE provider = ? AND day = ?", &[Param::Text(provider), Param::Text(&day)]).ok()?;
+        serde_json::from_str(rows.into_iter().next()?.into_iter().next()??.as_str()).ok()
+    }
+
+    fn put(&self, provider: &str, day: DateTime<Utc>, game_data: &GameData) -> Result<()> {
+        if !cache::is_settled(day, game_data) {
+            return Ok(());
+        }
+        let json = serde_json::to_string(game_data)?;
+        let day = day.format("%Y-%m-%d").to_string();
+        self.query(
+            "INSERT OR REPLACE INTO days (provider, day, game_data) VALUES (?, ?, ?)",
+            &[Param::Text(provider), Param::Text(&day), Param::Text(&json)],
+        )?;
+        Ok(())
+    }
+
+    fn contains(&self, provider: &str, day: DateTime<Utc>) -> bool {
+        let day = day.format("%Y-%m-%d").to_string();
+        self.query("SELECT 1 FROM days WHERE provider = ? AND day = ?", &[Param::Text(provider), Param::Text(&day)])
+            .is_ok_and(|rows| !rows.is_empty())
+    }
+}
+
+// Every archived game, keyed by league, id and date. A game moved to another day
+// replaces its old row, like a newer version of it does.
+pub struct SqliteArchive {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteArchive {
+    pub fn open(path: &Path) -> Result<SqliteArchive> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    fn with_connection(connection: Connection) -> Result<SqliteArchive> {
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS games (
+                league TEXT NOT NULL,
+                id INTEGER NOT NULL,
+                date TEXT NOT NULL,
+                fetched TEXT NOT NULL,
+                game TEXT NOT NULL,
+                PRIMARY KEY (league, id, date)
+            );
+            CREATE INDEX IF NOT EXISTS games_by_date ON games (league, date);
+            CREATE TABLE IF NOT EXISTS synced (
+                league TEXT NOT NULL,
+                season INTEGER NOT NULL,
+                at TEXT NOT NULL,
+                PRIMARY KEY (league, season)
+            )",
+        )?;
+        Ok(SqliteArchive { connection: Mutex::new(connection) })
+    }
+
+    fn connection(&self) -> Result<std::sync::MutexGuard<'_, Connection>> {
+        self.connection.lock().map_err(|_| anyhow!("SQLite connection poisoned"))
+    }
+
+    pub fn record(&self, league: &str, games: &[Game]) -> Result<()> {
+        let connection = self.connection()?;
+        let now = Utc::now().to_rfc3339();
+        // one transaction, a failed batch leaves the archive as it was
+        connection.execute_batch("BEGIN")?;
+        let recorded = games.iter().try_for_each(|game| {
+            let Some(date) = game_date(game) else {
+                return Ok(());
+            };
+            let (id, date) = (Param::Integer(game.id.into()), date.to_string());
+            connection.execute("DELETE FROM games WHERE league = ? AND id = ? AND date <> ?", &[Param::Text(league), id, Param::Text(&date)])?;
+            connection.execute(
+                "INSERT OR REPLACE INTO games (league, id, date, fetched, game) VALUES (?, ?, ?, ?, ?)",
+                &[Param::Text(league), Param::Integer(game.id.into()), Param::Text(&date), Param::Text(&now), Param::Text(&serde_json::to_string(game)?)],
+            )
+        });
+        match recorded {
+            Ok(()) => connection.execute_batch("COMMIT"),
+            Err(e) => {
+                connection.execute_batch("ROLLBACK")?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Games played between the dates, both included, oldest first
+    pub fn range(&self, league: &str, from: NaiveDate, to: NaiveDate) -> Result<Vec<Game>> {
+        let rows = self.connection()?.query(
+            "SELECT game FROM games WHERE league = ? AND date BETWEEN ? AND ? ORDER BY date, id",
+            &[Param::Text(league), Param::Text(&from.to_string()), Param::Text(&to.to_string())],
+        )?;
+        Ok(rows.into_iter().filter_map(|row| serde_json::from_str(row.into_iter().next()??.as_str()).ok()).collect())
+    }
+
+    pub fn mark_synced(&self, league: &str, season: i32) -> Result<()> {
+        self.connection()?.execute(
+            "INSERT OR REPLACE INTO synced (league, season, at) VALUES (?, ?, ?)",
+            &[Param::Text(league), Param::Integer(season.into()), Param::Text(&Utc::now().to_rfc3339())],
+        )
+    }
+
+    pub fn synced_at(&self, league: &str, season: i32) -> Result<Option<DateTime<Utc>>> {
+        let rows = self.connection()?.query("SELECT at FROM synced WHERE league = ? AND season = ?", &[Param::Text(league), Param::Integer(season.into())])?;
+        Ok(first_time(rows))
+    }
+
+    /// When the games of the day were recorded last
+    pub fn updated(&self, league: &str, date: NaiveDate) -> Result<Option<DateTime<Utc>>> {
+        let rows = self.connection()?.query("SELECT MAX(fetched) FROM games WHERE league = ? AND date = ?", &[Param::Text(league), Param::Text(&date.to_string())])?;
+        Ok(first_time(rows))
+    }
+}
+
+// The RFC 3339 time in the first column of the first row
+fn first_time(rows: Vec<Row>) -> Option<DateTime<Utc>> {
+    let time = rows.into_iter().next()?.into_iter().next()??;
+    DateTime::parse_from_rfc3339(&time).ok().map(|time| time.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn binds_parameters_and_reads_rows_back() {
+        let connection = Connection::open_in_memory().unwrap();
+        connection.execute_batch("CREATE TABLE t (name TEXT, n INTEGER, missing TEXT)").unwrap();
+        connection.execute("INSERT INTO t (name, n) VALUES (?, ?)", &[Param::Text("it's"), Param::Integer(42)]).unwrap();
+
+        let rows = connection.query("SELECT name, n, missing FROM t WHERE n = ?", &[Param::Integer(42)]).unwrap();
+        assert_eq!(rows, vec![vec![Some("it's".to_string()), Some("42".to_string()), None]]);
+        assert!(connection.execute("INSERT INTO nowhere VALUES (1)", &[]).is_err());
+    }
+
+    #[test]
+    fn keeps_only_settled_days() {
+        let store = SqliteStore::with_connection(Connection::open_in_memory().unwrap()).unwrap();
+        let past = Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap();
+        let today = Utc::now();
+        let game_data = GameData::from_games(Vec::new());
+
+        store.put("nba", past, &game_data).unwrap();
+        store.put("nba", today, &game_data).unwrap();
+        assert!(store.contains("nba", past));
+        assert!(store.get("nba", past).is_some());
+        assert!(!store.contains("nba", today));
+        assert!(!store.contains("nhl", past));
+    }
+
+    #[test]
+    fn a_rescheduled_game_replaces_its_old_day() {
+        let archive = SqliteArchive::with_connection(Connection::open_in_memory().unwrap()).unwrap();
+        let day = |date: &str| NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+        let game = |id: u32, date: &str| Game { id, date: date.to_string(), ..Game::default() };
+
+        archive.record("nba", &[game(1, "2024-01-02"), game(2, "2024-01-03T00:30:00Z")]).unwrap();
+        archive.record("nba", &[game(1, "2024-01-05")]).unwrap();
+        archive.record("nhl", &[game(1, "2024-01-02")]).unwrap();
+
+        let ids = |games: Vec<Game>| games.iter().map(|game| (game.id, game.date.clone())).collect::<Vec<_>>();
+        assert_eq!(ids(archive.range("nba", day("2024-01-01"), day("2024-01-31")).unwrap()), vec![(2, "2024-01-03T00:30:00Z".to_string()), (1, "2024-01-05".to_string())]);
+        assert!(archive.updated("nba", day("2024-01-02")).unwrap().is_none());
+        assert!(archive.updated("nba", day("2024-01-05")).unwrap().is_some());
+
+        assert!(archive.synced_at("nba", 2023).unwrap().is_none());
+        archive.mark_synced("nba", 2023).unwrap();
+        assert!(archive.synced_at("nba", 2023).unwrap().is_some());
+    }
+}
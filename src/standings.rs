@@ -0,0 +1,525 @@
+use crate::{Game, Team};
+use crate::elo::Ratings;
+use crate::form;
+use crate::scroll;
+use crate::theme::Theme;
+use ratatui::{
+    prelude::{Constraint, Frame, Rect},
+    widgets::canvas::{Canvas, Line},
+    widgets::{Row, Table},
+};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+// How a league ranks its teams, supplied by the provider. The engine and the
+// table only ever look at these rules, never at the league itself.
+#[derive(Clone, Copy, Debug)]
+pub struct RankingRules {
+    pub win: u32,
+    pub draw: u32,
+    pub overtime_loss: u32,
+    // Games past these periods were decided in overtime, None for leagues
+    // that do not count overtime losses
+    pub regulation_periods: Option<u32>,
+    // Tiebreakers, applied in order
+    pub order: &'static [Criterion],
+    pub columns: &'static [Column],
+}
+
+// Win percentage and games back, e.g. NBA
+pub const WIN_PCT: RankingRules = RankingRules {
+    win: 1,
+    draw: 0,
+    overtime_loss: 0,
+    regulation_periods: None,
+    order: &[Criterion::WinPct, Criterion::Wins],
+    columns: &[
+        Column::Wins,
+        Column::Losses,
+        Column::Pct,
+        Column::GamesBack,
+        Column::ConferenceRecord,
+        Column::DivisionRecord,
+        Column::Streak,
+        Column::LastTen,
+    ],
+};
+
+// Points, goal difference and recent form, e.g. soccer leagues
+pub const LEAGUE_TABLE: RankingRules = RankingRules {
+    win: 3,
+    draw: 1,
+    overtime_loss: 0,
+    regulation_periods: None,
+    order: &[Criterion::Points, Criterion::GoalDifference, Criterion::GoalsFor],
+    columns: &[
+        Column::Played,
+        Column::Wins,
+        Column::Draws,
+        Column::Losses,
+        Column::GoalDifference,
+        Column::Points,
+        Column::Form,
+    ],
+};
+
+// Gold, silver and bronze medals per country
+pub const MEDAL_TABLE: RankingRules = RankingRules {
+    win: 0,
+    draw: 0,
+    overtime_loss: 0,
+    regulation_periods: None,
+    order: &[Criterion::Gold, Criterion::Silver, Criterion::Bronze],
+    columns: &[Column::Gold, Column::Silver, Column::Bronze, Column::Medals],
+};
+
+#[derive(Clone, Copy, Debug)]
+pub enum Criterion {
+    WinPct,
+    Points,
+    // fewer games played ranks higher, for leagues ranked by points
+    FewerPlayed,
+    Wins,
+    GoalDifference,
+    GoalsFor,
+    Gold,
+    Silver,
+    Bronze,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Column {
+    Played,
+    Wins,
+    Draws,
+    Losses,
+    OvertimeLosses,
+    Pct,
+    GamesBack,
+    GoalDifference,
+    Points,
+    Form,
+    // wins and losses against teams of the same conference or division
+    ConferenceRecord,
+    DivisionRecord,
+    Streak,
+    // wins and losses of the last ten games
+    LastTen,
+    Gold,
+    Silver,
+    Bronze,
+    Medals,
+    // rating from the archived games, only shown when there is one
+    Elo,
+}
+
+#[derive(Default, Debug)]
+pub struct StandingsRow {
+    pub team: String,
+    pub conference: String,
+    pub division: String,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    pub overtime_losses: u32,
+    pub points: u32,
+    // goals, runs or points scored and conceded
+    pub scored: u32,
+    pub conceded: u32,
+    pub conference_record: (u32, u32),
+    pub division_record: (u32, u32),
+    pub gold: u32,
+    pub silver: u32,
+    pub bronze: u32,
+    // Results oldest first, only the last five are shown
+    pub results: Vec<char>,
+    pub elo: Option<f64>,
+}
+
+impl StandingsRow {
+    pub fn played(&self) -> u32 {
+        self.wins + self.draws + self.losses + self.overtime_losses
+    }
+
+    pub fn pct(&self) -> f64 {
+        match self.played() {
+            0 => 0.0,
+            played => self.wins as f64 / played as f64,
+        }
+    }
+
+    pub fn games_back(&self, leader: &StandingsRow) -> f64 {
+        ((leader.wins as f64 - self.wins as f64) + (self.losses as f64 - leader.losses as f64)) / 2.0
+    }
+
+    pub fn goal_difference(&self) -> i64 {
+        self.scored as i64 - self.conceded as i64
+    }
+
+    pub fn medals(&self) -> u32 {
+        self.gold + self.silver + self.bronze
+    }
+
+    pub fn form(&self) -> String {
+        let skip = self.results.len().saturating_sub(5);
+        self.results[skip..].iter().collect()
+    }
+
+    // Latest result and how many times in a row it happened, e.g. ('W', 3)
+    pub fn streak(&self) -> Option<(char, usize)> {
+        let last = *self.results.last()?;
+        Some((last, self.results.iter().rev().take_while(|result| **result == last).count()))
+    }
+}
+
+// Column a table is sorted by instead of its natural order, None keeps that order.
+// Used by the standings and the box score.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Sort {
+    pub column: Option<usize>,
+    pub reversed: bool,
+}
+
+impl Sort {
+    // Step through the columns, passing the natural order between the last and first
+    pub fn cycle(&mut self, columns: usize, delta: isize) {
+        let count = columns as isize + 1;
+        let position = self.column.map(|column| column as isize + 1).unwrap_or_default();
+        self.column = match (position + delta).rem_euclid(count) {
+            0 => None,
+            position => Some(position as usize - 1),
+        };
+    }
+}
+
+pub struct Standings {
+    pub rules: RankingRules,
+    pub rows: Vec<StandingsRow>,
+}
+
+impl Standings {
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    // Sort rows that were not ranked from games, e.g. a medal table
+    pub fn ranked(rules: RankingRules, mut rows: Vec<StandingsRow>) -> Standings {
+        rows.sort_by(|a, b| compare(&rules, a, b));
+        Standings { rules, rows }
+    }
+
+    // The columns of the rules, with the Elo rating once the teams have one
+    pub fn columns(&self) -> Vec<Column> {
+        let mut columns = self.rules.columns.to_vec();
+        if self.rows.iter().any(|row| row.elo.is_some()) {
+            columns.push(Column::Elo);
+        }
+        columns
+    }
+
+    pub fn rate(&mut self, ratings: &Ratings) {
+        for row in &mut self.rows {
+            row.elo = ratings.get(&row.team);
+        }
+    }
+}
+
+// Rank the teams from the finished regular season games
+pub fn compute(rules: RankingRules, games: &[Game]) -> Standings {
+    let regular_season: Vec<&Game> = games.iter().filter(|game| !game.postseason).collect();
+    rank(rules, &regular_season)
+}
+
+// Rank the teams from the finished games among the given ones, e.g. a tournament
+// group. Teams without a finished game are still listed.
+pub fn rank(rules: RankingRules, games: &[&Game]) -> Standings {
+    let mut finished: Vec<&Game> = games.iter().copied().filter(|game| game.is_final()).collect();
+    finished.sort_by(|a, b| a.date.cmp(&b.date));
+
+    let mut rows: Vec<StandingsRow> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for game in games {
+        for team in [&game.home_team, &game.visitor_team] {
+            if !index.contains_key(&team.abbreviation) {
+                index.insert(team.abbreviation.clone(), rows.len());
+                rows.push(StandingsRow {
+                    team: team.abbreviation.clone(),
+                    conference: team.conference.clone(),
+                    division: team.division.clone(),
+                    ..StandingsRow::default()
+                });
+            }
+        }
+    }
+
+    for game in finished {
+        let overtime = rules.regulation_periods.is_some_and(|periods| game.period > periods);
+        let same = |field: fn(&Team) -> &str| !field(&game.home_team).is_empty() && field(&game.home_team) == field(&game.visitor_team);
+        let (same_conference, same_division) = (same(|team| &team.conference), same(|team| &team.division));
+        let sides = [
+            (&game.home_team, game.home_team_score, game.visitor_team_score),
+            (&game.visitor_team, game.visitor_team_score, game.home_team_score),
+        ];
+        for (team, scored, conceded) in sides {
+            let row = &mut rows[index[&team.abbreviation]];
+            row.scored += scored;
+            row.conceded += conceded;
+            let won = u32::from(scored > conceded);
+            let lost = u32::from(scored < conceded);
+            if same_conference {
+                row.conference_record.0 += won;
+                row.conference_record.1 += lost;
+            }
+            if same_division {
+                row.division_record.0 += won;
+                row.division_record.1 += lost;
+            }
+            match scored.cmp(&conceded) {
+                Ordering::Greater => {
+                    row.wins += 1;
+                    row.points += rules.win;
+                    row.results.push('W');
+                }
+                Ordering::Equal => {
+                    row.draws += 1;
+                    row.points += rules.draw;
+                    row.results.push('D');
+                }
+                Ordering::Less if overtime => {
+                    row.overtime_losses += 1;
+                    row.points += rules.overtime_loss;
+                    row.results.push('O');
+                }
+                Ordering::Less => {
+                    row.losses += 1;
+                    row.results.push('L');
+                }
+            }
+        }
+    }
+
+    Standings::ranked(rules, rows)
+}
+
+fn compare(rules: &RankingRules, a: &StandingsRow, b: &StandingsRow) -> Ordering {
+    rules
+        .order
+        .iter()
+        .map(|criterion| match criterion {
+            Criterion::WinPct => b.pct().total_cmp(&a.pct()),
+            Criterion::Points => b.points.cmp(&a.points),
+            Criterion::FewerPlayed => a.played().cmp(&b.played()),
+            Criterion::Wins => b.wins.cmp(&a.wins),
+            Criterion::GoalDifference => b.goal_difference().cmp(&a.goal_difference()),
+            Criterion::GoalsFor => b.scored.cmp(&a.scored),
+            Criterion::Gold => b.gold.cmp(&a.gold),
+            Criterion::Silver => b.silver.cmp(&a.silver),
+            Criterion::Bronze => b.bronze.cmp(&a.bronze),
+        })
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+        .then(a.team.cmp(&b.team))
+}
+
+impl Column {
+    pub fn header(&self) -> &'static str {
+        match self {
+            Column::Played => "GP",
+            Column::ConferenceRecord => "Conf",
+            Column::DivisionRecord => "Div",
+            Column::Streak => "Strk",
+            Column::LastTen => "L10",
+            Column::Wins => "W",
+            Column::Draws => "D",
+            Column::Losses => "L",
+            Column::OvertimeLosses => "OTL",
+            Column::Pct => "PCT",
+            Column::GamesBack => "GB",
+            Column::GoalDifference => "GD",
+            Column::Points => "PTS",
+            Column::Form => "Form",
+            Column::Gold => "G",
+            Column::Silver => "S",
+            Column::Bronze => "B",
+            Column::Medals => "Total",
+            Column::Elo => "Elo",
+        }
+    }
+
+    fn width(&self) -> u16 {
+        match self {
+            Column::Pct | Column::Form | Column::Medals | Column::Streak | Column::Elo => 6,
+            Column::ConferenceRecord | Column::DivisionRecord | Column::LastTen => 7,
+            Column::GamesBack => 5,
+            _ => 4,
+        }
+    }
+
+    pub fn value(&self, row: &StandingsRow, leader: &StandingsRow) -> String {
+        match self {
+            Column::Played => row.played().to_string(),
+            Column::Wins => row.wins.to_string(),
+            Column::Draws => row.draws.to_string(),
+            Column::Losses => row.losses.to_string(),
+            Column::OvertimeLosses => row.overtime_losses.to_string(),
+            Column::Pct => format!("{:.3}", row.pct()),
+            Column::GamesBack => match row.games_back(leader) {
+                games_back if games_back > 0.0 => format!("{:.1}", games_back),
+                _ => "-".to_string(),
+            },
+            Column::GoalDifference => format!("{:+}", row.goal_difference()),
+            Column::Points => row.points.to_string(),
+            Column::Form => row.form(),
+            Column::ConferenceRecord => record(&row.conference, row.conference_record),
+            Column::DivisionRecord => record(&row.division, row.division_record),
+            Column::Streak => form::streak(&row.results).unwrap_or_else(|| "-".to_string()),
+            Column::LastTen if row.results.is_empty() => "-".to_string(),
+            Column::LastTen => form::last_ten(&row.results),
+            Column::Gold => row.gold.to_string(),
+            Column::Silver => row.silver.to_string(),
+            Column::Bronze => row.bronze.to_string(),
+            Column::Medals => row.medals().to_string(),
+            Column::Elo => row.elo.map(|elo| format!("{:.0}", elo)).unwrap_or_else(|| "-".to_string()),
+        }
+    }
+
+    // Higher is better, so the best team comes first when sorting by the column
+    fn sort_value(&self, row: &StandingsRow, leader: &StandingsRow) -> f64 {
+        let pct = |(wins, losses): (u32, u32)| if wins + losses == 0 { 0.0 } else { wins as f64 / (wins + losses) as f64 };
+        match self {
+            Column::Played => row.played() as f64,
+            Column::Wins => row.wins as f64,
+            Column::Draws => row.draws as f64,
+            Column::Losses => -(row.losses as f64),
+            Column::OvertimeLosses => -(row.overtime_losses as f64),
+            Column::Pct => row.pct(),
+            Column::GamesBack => -row.games_back(leader),
+            Column::GoalDifference => row.goal_difference() as f64,
+            Column::Points => row.points as f64,
+            Column::Form => row.form().chars().filter(|result| *result == 'W').count() as f64,
+            Column::ConferenceRecord => pct(row.conference_record),
+            Column::DivisionRecord => pct(row.division_record),
+            Column::Streak => match row.streak() {
+                Some(('W', count)) => count as f64,
+                Some(('D', _)) | None => 0.0,
+                Some((_, count)) => -(count as f64),
+            },
+            Column::LastTen => row.results.iter().rev().take(10).filter(|result| **result == 'W').count() as f64,
+            Column::Gold => row.gold as f64,
+            Column::Silver => row.silver as f64,
+            Column::Bronze => row.bronze as f64,
+            Column::Medals => row.medals() as f64,
+            Column::Elo => row.elo.unwrap_or_default(),
+        }
+    }
+}
+
+// Wins and losses, "-" for teams without a conference or division
+fn record(group: &str, (wins, losses): (u32, u32)) -> String {
+    if group.is_empty() {
+        return "-".to_string();
+    }
+    format!("{}-{}", wins, losses)
+}
+
+pub fn ui(standings: &Standings, sort: Sort, scroll: usize, title: &str, theme: &Theme, f: &mut Frame, area: Rect) {
+    let block = theme.block(title.to_string());
+    let columns = standings.columns();
+
+    // the sorted column is marked with the direction of the sort
+    let mut header = vec!["#".to_string(), "Team".to_string()];
+    header.extend(columns.iter().enumerate().map(|(index, column)| match sort.column {
+        Some(sorted) if sorted == index => format!("{}{}", column.header(), if sort.reversed { "▲" } else { "▼" }),
+        _ => column.header().to_string(),
+    }));
+
+    let mut widths = vec![Constraint::Length(4), Constraint::Length(5)];
+    widths.extend(columns.iter().enumerate().map(|(index, column)| {
+        let marker = u16::from(sort.column == Some(index));
+        Constraint::Length(column.width() + marker)
+    }));
+
+    // rows keep their rank number when sorted by another column
+    let Some(leader) = standings.rows.first() else {
+        f.render_widget(Table::new(Vec::<Row>::new(), widths).header(Row::new(header)).block(block), area);
+        return;
+    };
+    let mut ranked: Vec<(usize, &StandingsRow)> = standings.rows.iter().enumerate().collect();
+    if let Some(column) = sort.column.and_then(|index| columns.get(index)) {
+        ranked.sort_by(|(_, a), (_, b)| column.sort_value(b, leader).total_cmp(&column.sort_value(a, leader)));
+    }
+    if sort.reversed {
+        ranked.reverse();
+    }
+
+    let rows = ranked.into_iter().map(|(rank, row)| {
+        let mut cells = vec![(rank + 1).to_string(), row.team.clone()];
+        cells.extend(columns.iter().map(|column| column.value(row, leader)));
+        Row::new(cells)
+    });
+
+    let table = Table::new(rows, widths).header(Row::new(header).style(theme.header));
+    scroll::render(table.block(block), standings.len(), scroll, theme, f, area);
+}
+
+// Share of the points a team could have won, the win percentage in leagues
+// without draws. Medal tables have no games, they go by medal count.
+fn ladder_value(rules: &RankingRules, row: &StandingsRow) -> f64 {
+    if rules.win == 0 {
+        return row.medals() as f64;
+    }
+    let points = row.wins * rules.win + row.draws * rules.draw + row.overtime_losses * rules.overtime_loss;
+    match row.played() {
+        0 => 0.0,
+        played => points as f64 / (played * rules.win) as f64,
+    }
+}
+
+// Every team placed on a vertical scale by how well it does, one ladder per conference
+pub fn ladder_ui(standings: &Standings, title: &str, theme: &Theme, f: &mut Frame, area: Rect) {
+    let mut conferences: Vec<&str> = Vec::new();
+    for row in &standings.rows {
+        if !conferences.contains(&row.conference.as_str()) {
+            conferences.push(&row.conference);
+        }
+    }
+
+    let values: Vec<f64> = standings.rows.iter().map(|row| ladder_value(&standings.rules, row)).collect();
+    let (low, high) = values.iter().fold((f64::MAX, f64::MIN), |(low, high), value| (low.min(*value), high.max(*value)));
+    // room above and below for the labels of the best and worst team
+    let padding = ((high - low) * 0.1).max(0.05);
+    let (low, high) = (low - padding, high + padding);
+    let width = conferences.len().max(1) as f64;
+
+    let canvas = Canvas::default()
+        .block(theme.block(title.to_string()))
+        .x_bounds([0.0, width])
+        .y_bounds([low, high])
+        .paint(|ctx| {
+            for (column, conference) in conferences.iter().enumerate() {
+                let x = column as f64 + 0.1;
+                ctx.draw(&Line { x1: x, y1: low, x2: x, y2: high, color: Theme::color(theme.muted) });
+                if !conference.is_empty() {
+                    ctx.print(x, high, conference.to_string());
+                }
+
+                // neighbours on the ladder take turns left and right so close teams stay readable
+                let mut rows: Vec<(&StandingsRow, f64)> =
+                    standings.rows.iter().zip(&values).filter(|(row, _)| row.conference == *conference).map(|(row, value)| (row, *value)).collect();
+                rows.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+                for (index, (row, value)) in rows.into_iter().enumerate() {
+                    let offset = if index % 2 == 0 { 0.05 } else { 0.3 };
+                    let label = match standings.rules.win {
+                        0 => format!("{} {}", row.team, value),
+                        _ => format!("{} {:.3}", row.team, value),
+                    };
+                    ctx.print(x + offset, value, label);
+                }
+            }
+        });
+    f.render_widget(canvas, area);
+}
@@ -0,0 +1,46 @@
+use crate::theme::Theme;
+use chrono::{DateTime, Local};
+use ratatui::{
+    prelude::{Alignment, Constraint, Direction, Frame, Layout, Line, Rect, Span},
+    widgets::Paragraph,
+};
+
+// Outcome of the latest fetch of any view, shown in the status bar
+#[derive(Default)]
+pub struct FetchState {
+    fetched_at: Option<DateTime<Local>>,
+    failed: bool,
+}
+
+impl FetchState {
+    pub fn record(&mut self, ok: bool) {
+        if ok {
+            self.fetched_at = Some(Local::now());
+        }
+        self.failed = !ok;
+    }
+}
+
+// One line at the bottom: when data was fetched last, whether a fetch is running
+// or failed, notes of the shown view like the league's filter and key hints of the shown view on the right
+pub fn ui(fetch: &FetchState, spinner: &str, notes: Vec<String>, hints: &str, theme: &Theme, f: &mut Frame, area: Rect) {
+    let mut spans = vec![match fetch.fetched_at {
+        Some(fetched_at) => Span::raw(format!("Updated {}", fetched_at.format("%H:%M:%S"))),
+        None => Span::raw("Not updated yet"),
+    }];
+    if !spinner.is_empty() {
+        let spinner = if theme.plain { String::new() } else { format!("{} ", spinner) };
+        spans.push(Span::styled(format!("  {}fetching", spinner), theme.info));
+    } else if fetch.failed {
+        spans.push(Span::styled("  last fetch failed", theme.bad));
+    }
+    spans.extend(notes.into_iter().map(|note| Span::raw(format!("  {}", note))));
+
+    let parts = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(0), Constraint::Length(hints.chars().count() as u16 + 1)])
+        .split(area);
+    let style = theme.status_bar;
+    f.render_widget(Paragraph::new(Line::from(spans)).style(style), parts[0]);
+    f.render_widget(Paragraph::new(hints).alignment(Alignment::Right).style(style), parts[1]);
+}
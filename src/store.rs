@@ -0,0 +1,95 @@
+use crate::cache::{self, Cache};
+use crate::config::Config;
+use crate::GameData;
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+// Where fetched days are kept between fetches
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Backend {
+    // JSON files in the cache directory, kept between runs
+    #[default]
+    Files,
+    // nothing is written to disk, for servers with a read-only filesystem
+    Memory,
+    // a Redis server at redis_url, shared by several instances and kept across
+    // restarts (needs the `redis` feature)
+    Redis,
+    // days.sqlite3 in the cache directory, one file instead of one per day
+    // (needs the `sqlite` feature)
+    Sqlite,
+}
+
+#[cfg(feature = "redis")]
+const DEFAULT_REDIS_URL: &str = "redis://127.0.0.1:6379";
+
+// Days of games by provider cache key, only days that can not change anymore are kept
+pub trait Store: Send + Sync {
+    fn get(&self, provider: &str, day: DateTime<Utc>) -> Option<GameData>;
+    fn put(&self, provider: &str, day: DateTime<Utc>, game_data: &GameData) -> Result<()>;
+    fn contains(&self, provider: &str, day: DateTime<Utc>) -> bool;
+
+    fn read_only(&self) -> bool {
+        false
+    }
+}
+
+pub fn open(config: &Config) -> Result<Arc<dyn Store>> {
+    Ok(match config.store {
+        Backend::Files => Arc::new(Cache::new(config)),
+        Backend::Memory => Arc::new(MemoryStore::default()),
+        #[cfg(feature = "redis")]
+        Backend::Redis => Arc::new(crate::redis::RedisStore::new(config.redis_url.as_deref().unwrap_or(DEFAULT_REDIS_URL))?),
+        #[cfg(not(feature = "redis"))]
+        Backend::Redis => anyhow::bail!("The Redis store needs sports built with the redis feature"),
+        #[cfg(feature = "sqlite")]
+        Backend::Sqlite => Arc::new(crate::sqlite::SqliteStore::open(&cache::cache_dir(config).join("days.sqlite3"))?),
+        #[cfg(not(feature = "sqlite"))]
+        Backend::Sqlite => anyhow::bail!("The SQLite store needs sports built with the sqlite feature"),
+    })
+}
+
+impl Store for Cache {
+    fn get(&self, provider: &str, day: DateTime<Utc>) -> Option<GameData> {
+        Cache::get(self, provider, day)
+    }
+
+    fn put(&self, provider: &str, day: DateTime<Utc>, game_data: &GameData) -> Result<()> {
+        Cache::put(self, provider, day, game_data)
+    }
+
+    fn contains(&self, provider: &str, day: DateTime<Utc>) -> bool {
+        Cache::contains(self, provider, day)
+    }
+
+    fn read_only(&self) -> bool {
+        Cache::read_only(self)
+    }
+}
+
+// Lost when the process ends
+#[derive(Default)]
+pub struct MemoryStore {
+    days: Mutex<HashMap<(String, NaiveDate), GameData>>,
+}
+
+impl Store for MemoryStore {
+    fn get(&self, provider: &str, day: DateTime<Utc>) -> Option<GameData> {
+        self.days.lock().ok()?.get(&(provider.to_string(), day.date_naive())).cloned()
+    }
+
+    fn put(&self, provider: &str, day: DateTime<Utc>, game_data: &GameData) -> Result<()> {
+        if let (true, Ok(mut days)) = (cache::is_settled(day, game_data), self.days.lock()) {
+            days.insert((provider.to_string(), day.date_naive()), game_data.clone());
+        }
+        Ok(())
+    }
+
+    fn contains(&self, provider: &str, day: DateTime<Utc>) -> bool {
+        self.days.lock().is_ok_and(|days| days.contains_key(&(provider.to_string(), day.date_naive())))
+    }
+}
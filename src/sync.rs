@@ -0,0 +1,65 @@
+use crate::archive::Archive;
+use crate::provider::Provider;
+use anyhow::{anyhow, Result};
+use chrono::{Duration, NaiveDate};
+use std::time::Duration as Pause;
+
+// Days fetched with one request, the APIs page through longer ranges themselves
+const WINDOW_DAYS: i64 = 7;
+
+// Attempts of a window before the sync gives up, the pause doubles after every failure
+const ATTEMPTS: u32 = 4;
+
+// Windows of the range, oldest first
+fn windows(start: NaiveDate, end: NaiveDate) -> Vec<(NaiveDate, NaiveDate)> {
+    let mut windows = Vec::new();
+    let mut from = start;
+    while from <= end {
+        let to = (from + Duration::days(WINDOW_DAYS - 1)).min(end);
+        windows.push((from, to));
+        from = to + Duration::days(1);
+    }
+    windows
+}
+
+// Fetch every game of the season starting in the year into the archive, a week at
+// a time with a pause between requests so the API's rate limit is not hit. Failed
+// weeks are retried with longer pauses. The progress gets the finished and total
+// weeks and the games so far. Returns the number of archived games.
+pub fn sync(provider: &dyn Provider, archive: &Archive, season: i32, pause: Pause, mut progress: impl FnMut(usize, usize, usize)) -> Result<usize> {
+    let (start, end) = provider.season_range(season).ok_or_else(|| anyhow!("{} has no seasons to sync", provider.name()))?;
+    let windows = windows(start, end);
+    let cache_key = provider.cache_key();
+
+    let mut games = 0;
+    progress(0, windows.len(), games);
+    for (index, (from, to)) in windows.iter().enumerate() {
+        let mut wait = pause;
+        let mut attempt = 1;
+        let fetched = loop {
+            if index > 0 || attempt > 1 {
+                std::thread::sleep(wait);
+            }
+            match provider.fetch_range(*from, *to) {
+                Some(fetched) => break fetched,
+                None if attempt < ATTEMPTS => {
+                    attempt += 1;
+                    wait *= 2;
+                }
+                None => return Err(anyhow!("Could not fetch the games from {} to {} after {} attempts", from, to, ATTEMPTS)),
+            }
+        };
+        archive.record(&cache_key, &fetched)?;
+        games += fetched.len();
+        progress(index + 1, windows.len(), games);
+    }
+    archive.mark_synced(&cache_key, season)?;
+    Ok(games)
+}
+
+// e.g. "[########------------] 12/40 weeks, 341 games"
+pub fn progress_bar(done: usize, total: usize, games: usize) -> String {
+    const WIDTH: usize = 20;
+    let filled = (done * WIDTH).checked_div(total).unwrap_or(WIDTH);
+    format!("[{}{}] {}/{} weeks, {} games", "#".repeat(filled), "-".repeat(WIDTH - filled), done, total, games)
+}
@@ -0,0 +1,66 @@
+use crate::schedule::{self, Schedule};
+use crate::Team;
+use crate::theme::Theme;
+use ratatui::{
+    prelude::{Constraint, Frame, Rect},
+    widgets::{Paragraph, Row, Table, TableState},
+};
+
+// State of the teams view, the teams are fetched once per league
+#[derive(Default)]
+pub struct TeamsState {
+    pub teams: Option<Vec<Team>>,
+    pub state: TableState,
+    // Some while the schedule of the selected team is shown
+    pub schedule: Option<Schedule>,
+}
+
+impl TeamsState {
+    pub fn set_teams(&mut self, teams: Option<Vec<Team>>) {
+        let count = teams.as_ref().map(Vec::len).unwrap_or_default();
+        self.teams = teams;
+        self.state = TableState::default().with_selected((count > 0).then_some(0));
+    }
+
+    pub fn selected(&self) -> Option<&Team> {
+        self.teams.as_ref()?.get(self.state.selected()?)
+    }
+
+    pub fn select(&mut self, delta: isize) {
+        if let Some(schedule) = &mut self.schedule {
+            schedule.select(delta);
+            return;
+        }
+        let count = self.teams.as_ref().map(Vec::len).unwrap_or_default();
+        if count == 0 {
+            return;
+        }
+        let selected = self.state.selected().unwrap_or_default() as isize + delta;
+        self.state.select(Some(selected.clamp(0, count as isize - 1) as usize));
+    }
+}
+
+pub fn ui(teams: &mut TeamsState, league: &str, theme: &Theme, f: &mut Frame, area: Rect) {
+    if let Some(schedule) = &mut teams.schedule {
+        schedule::ui(schedule, theme, f, area);
+        return;
+    }
+
+    let block = theme.block(format!("{} teams (schedule: enter)", league));
+
+    let Some(list) = &teams.teams else {
+        f.render_widget(Paragraph::new(format!("No team list available for {}.", league)).block(block), area);
+        return;
+    };
+
+    let rows = list.iter().map(|team| {
+        Row::new(vec![team.abbreviation.clone(), team.full_name.clone(), team.conference.clone(), team.division.clone()])
+    });
+    let widths = [Constraint::Length(5), Constraint::Fill(1), Constraint::Length(10), Constraint::Length(12)];
+    let table = Table::new(rows, widths)
+        .header(Row::new(vec!["Abbr", "Team", "Conference", "Division"]).style(theme.header))
+        .column_spacing(2)
+        .highlight_style(theme.selected)
+        .block(block);
+    f.render_stateful_widget(table, area, &mut teams.state);
+}
@@ -0,0 +1,315 @@
+use crate::config::Config;
+use anyhow::{bail, Context, Result};
+use ratatui::{
+    style::{Color, Modifier, Style},
+    symbols::border,
+    widgets::{Block, Borders},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+// Built-in themes, custom ones from the config start from one of them
+const BUILT_IN: [&str; 7] = ["default", "light", "mono", "nord", "deuteranopia", "protanopia", "high_contrast"];
+
+// Names of the styles in custom themes
+const STYLES: [&str; 18] = [
+    "border", "title", "focused", "header", "group", "selected", "live", "finished", "upcoming", "muted", "accent", "good", "bad", "warning",
+    "info", "status_bar", "live_tag", "final_tag",
+];
+
+// Blank borders, the title line stays without drawing a box
+const PLAIN_BORDER: border::Set = border::Set {
+    top_left: " ",
+    top_right: " ",
+    bottom_left: " ",
+    bottom_right: " ",
+    vertical_left: " ",
+    vertical_right: " ",
+    horizontal_top: " ",
+    horizontal_bottom: " ",
+};
+
+// Every style the views draw with
+#[derive(Clone, Debug)]
+pub struct Theme {
+    pub name: String,
+    // plain text for screen readers: no box drawing, spinners, graphics or colors
+    pub plain: bool,
+    pub border: Style,
+    pub title: Style,
+    // the block with the focus, e.g. today in the week view
+    pub focused: Style,
+    // table headers
+    pub header: Style,
+    // group headers of the scoreboard
+    pub group: Style,
+    // the selected row
+    pub selected: Style,
+    pub live: Style,
+    pub finished: Style,
+    pub upcoming: Style,
+    // placeholders, hints and lines drawn behind charts
+    pub muted: Style,
+    // today in the calendar, the rim of the basket
+    pub accent: Style,
+    // wins, made shots and finished jobs
+    pub good: Style,
+    // losses, missed shots and errors
+    pub bad: Style,
+    pub warning: Style,
+    pub info: Style,
+    pub status_bar: Style,
+    // the tags in front of the followed live games and in the box score
+    pub live_tag: Style,
+    pub final_tag: Style,
+}
+
+// A custom theme in the config, styles written like "bold yellow on blue"
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ThemeSpec {
+    // built-in theme the other styles are taken from
+    pub base: Option<String>,
+    #[serde(flatten)]
+    pub styles: HashMap<String, String>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            name: "default".to_string(),
+            plain: false,
+            border: Style::default(),
+            title: Style::default(),
+            focused: Style::default().add_modifier(Modifier::BOLD),
+            header: Style::default().add_modifier(Modifier::BOLD),
+            group: Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            selected: Style::default().add_modifier(Modifier::REVERSED),
+            live: Style::default().fg(Color::Green),
+            finished: Style::default(),
+            upcoming: Style::default().fg(Color::DarkGray),
+            muted: Style::default().fg(Color::DarkGray),
+            accent: Style::default().fg(Color::Yellow),
+            good: Style::default().fg(Color::Green),
+            bad: Style::default().fg(Color::Red),
+            warning: Style::default().fg(Color::Yellow),
+            info: Style::default().fg(Color::Cyan),
+            status_bar: Style::default().add_modifier(Modifier::REVERSED),
+            live_tag: Style::default().fg(Color::Black).bg(Color::Green).add_modifier(Modifier::BOLD),
+            final_tag: Style::default().add_modifier(Modifier::REVERSED),
+        }
+    }
+}
+
+impl Theme {
+    // The theme named in the config
+    pub fn from_config(config: &Config) -> Result<Theme> {
+        Theme::named(config.theme.as_deref().unwrap_or("default"), config)
+    }
+
+    // Plain when the config asks for the accessible mode
+    pub fn named(name: &str, config: &Config) -> Result<Theme> {
+        let theme = Theme::styled(name, config)?;
+        Ok(if config.accessible { theme.plain() } else { theme })
+    }
+
+    fn styled(name: &str, config: &Config) -> Result<Theme> {
+        if let Some(theme) = built_in(name) {
+            return Ok(theme);
+        }
+        let Some(spec) = config.themes.get(name) else {
+            bail!("Unknown theme {}, try one of {}", name, Theme::names(config).join(", "));
+        };
+        let base = spec.base.as_deref().unwrap_or("default");
+        let mut theme = built_in(base).with_context(|| format!("Theme {} is based on unknown theme {}", name, base))?;
+        theme.name = name.to_string();
+        for (key, value) in &spec.styles {
+            let style = parse_style(value).with_context(|| format!("Invalid style for {} in theme {}", key, name))?;
+            *theme.style_mut(key).with_context(|| format!("Unknown style {} in theme {}", key, name))? = style;
+        }
+        Ok(theme)
+    }
+
+    // Built-in themes first, then the config's own in name order
+    pub fn names(config: &Config) -> Vec<String> {
+        let mut custom: Vec<String> = config.themes.keys().filter(|name| built_in(name).is_none()).cloned().collect();
+        custom.sort();
+        BUILT_IN.iter().map(|name| name.to_string()).chain(custom).collect()
+    }
+
+    // Only the modifiers of every style are kept, they are all a screen reader may tell apart
+    fn plain(mut self) -> Theme {
+        for key in STYLES {
+            if let Some(style) = self.style_mut(key) {
+                *style = Style::default().add_modifier(style.add_modifier);
+            }
+        }
+        Theme { plain: true, ..self }
+    }
+
+    fn style_mut(&mut self, key: &str) -> Option<&mut Style> {
+        Some(match key {
+            "border" => &mut self.border,
+            "title" => &mut self.title,
+            "focused" => &mut self.focused,
+            "header" => &mut self.header,
+            "group" => &mut self.group,
+            "selected" => &mut self.selected,
+            "live" => &mut self.live,
+            "finished" => &mut self.finished,
+            "upcoming" => &mut self.upcoming,
+            "muted" => &mut self.muted,
+            "accent" => &mut self.accent,
+            "good" => &mut self.good,
+            "bad" => &mut self.bad,
+            "warning" => &mut self.warning,
+            "info" => &mut self.info,
+            "status_bar" => &mut self.status_bar,
+            "live_tag" => &mut self.live_tag,
+            "final_tag" => &mut self.final_tag,
+            _ => return None,
+        })
+    }
+
+    // A bordered block with the theme's border and title styles
+    pub fn block<'a>(&self, title: impl Into<ratatui::text::Line<'a>>) -> Block<'a> {
+        match self.plain {
+            true => Block::default().title(title).borders(Borders::TOP).border_set(PLAIN_BORDER).title_style(self.title),
+            false => Block::default().title(title).borders(Borders::ALL).border_style(self.border).title_style(self.title),
+        }
+    }
+
+    // Foreground of a style for what is drawn on a canvas
+    pub fn color(style: Style) -> Color {
+        style.fg.unwrap_or(Color::Reset)
+    }
+}
+
+fn built_in(name: &str) -> Option<Theme> {
+    let default = Theme::default();
+    let theme = match name {
+        "default" => default,
+        // dark text for light terminal backgrounds
+        "light" => Theme {
+            live: Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+            upcoming: Style::default().fg(Color::Gray),
+            muted: Style::default().fg(Color::Gray),
+            accent: Style::default().fg(Color::Blue),
+            warning: Style::default().fg(Color::Magenta),
+            info: Style::default().fg(Color::Blue),
+            live_tag: Style::default().fg(Color::White).bg(Color::Green).add_modifier(Modifier::BOLD),
+            ..default
+        },
+        // no colors at all, only bold, dim, underlined and reversed text
+        "mono" => Theme {
+            live: Style::default().add_modifier(Modifier::BOLD),
+            upcoming: Style::default().add_modifier(Modifier::DIM),
+            muted: Style::default().add_modifier(Modifier::DIM),
+            accent: Style::default().add_modifier(Modifier::UNDERLINED),
+            good: Style::default().add_modifier(Modifier::BOLD),
+            bad: Style::default().add_modifier(Modifier::UNDERLINED),
+            warning: Style::default().add_modifier(Modifier::BOLD),
+            info: Style::default(),
+            live_tag: Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD),
+            ..default
+        },
+        "nord" => {
+            let frost = Color::Rgb(0x88, 0xc0, 0xd0);
+            let green = Color::Rgb(0xa3, 0xbe, 0x8c);
+            let grey = Color::Rgb(0x4c, 0x56, 0x6a);
+            Theme {
+                border: Style::default().fg(grey),
+                title: Style::default().fg(frost),
+                header: Style::default().fg(frost).add_modifier(Modifier::BOLD),
+                group: Style::default().fg(frost).add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+                selected: Style::default().bg(Color::Rgb(0x43, 0x4c, 0x5e)).add_modifier(Modifier::BOLD),
+                live: Style::default().fg(green),
+                upcoming: Style::default().fg(grey),
+                muted: Style::default().fg(grey),
+                accent: Style::default().fg(Color::Rgb(0xeb, 0xcb, 0x8b)),
+                good: Style::default().fg(green),
+                bad: Style::default().fg(Color::Rgb(0xbf, 0x61, 0x6a)),
+                warning: Style::default().fg(Color::Rgb(0xd0, 0x87, 0x70)),
+                info: Style::default().fg(frost),
+                status_bar: Style::default().fg(Color::Rgb(0xe5, 0xe9, 0xf0)).bg(Color::Rgb(0x3b, 0x42, 0x52)),
+                live_tag: Style::default().fg(Color::Rgb(0x2e, 0x34, 0x40)).bg(green).add_modifier(Modifier::BOLD),
+                ..default
+            }
+        }
+        // blue and orange from the Okabe-Ito palette instead of green and red
+        "deuteranopia" => {
+            let blue = Color::Rgb(0x56, 0xb4, 0xe9);
+            let orange = Color::Rgb(0xe6, 0x9f, 0x00);
+            Theme {
+                live: Style::default().fg(blue).add_modifier(Modifier::BOLD),
+                accent: Style::default().fg(Color::Rgb(0xf0, 0xe4, 0x42)),
+                good: Style::default().fg(blue),
+                bad: Style::default().fg(orange),
+                warning: Style::default().fg(Color::Rgb(0xf0, 0xe4, 0x42)),
+                info: Style::default().fg(Color::White),
+                live_tag: Style::default().fg(Color::Black).bg(blue).add_modifier(Modifier::BOLD),
+                ..default
+            }
+        }
+        // reds look dark to protanopes, losses and errors are bright yellow instead
+        "protanopia" => {
+            let blue = Color::Rgb(0x00, 0x9e, 0xe8);
+            let yellow = Color::Rgb(0xf0, 0xe4, 0x42);
+            Theme {
+                live: Style::default().fg(blue).add_modifier(Modifier::BOLD),
+                accent: Style::default().fg(Color::White).add_modifier(Modifier::UNDERLINED),
+                good: Style::default().fg(blue),
+                bad: Style::default().fg(yellow).add_modifier(Modifier::BOLD),
+                warning: Style::default().fg(Color::Rgb(0xe6, 0x9f, 0x00)),
+                info: Style::default().fg(Color::White),
+                live_tag: Style::default().fg(Color::Black).bg(blue).add_modifier(Modifier::BOLD),
+                ..default
+            }
+        }
+        // bright colors on black, nothing dimmed
+        "high_contrast" => Theme {
+            border: Style::default().fg(Color::White),
+            title: Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+            header: Style::default().fg(Color::White).add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            selected: Style::default().fg(Color::Black).bg(Color::White).add_modifier(Modifier::BOLD),
+            live: Style::default().fg(Color::LightCyan).add_modifier(Modifier::BOLD),
+            finished: Style::default().fg(Color::White),
+            upcoming: Style::default().fg(Color::White),
+            muted: Style::default().fg(Color::Gray),
+            accent: Style::default().fg(Color::LightYellow).add_modifier(Modifier::BOLD),
+            good: Style::default().fg(Color::LightCyan).add_modifier(Modifier::BOLD),
+            bad: Style::default().fg(Color::LightYellow).add_modifier(Modifier::BOLD),
+            warning: Style::default().fg(Color::LightMagenta).add_modifier(Modifier::BOLD),
+            info: Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+            status_bar: Style::default().fg(Color::Black).bg(Color::White),
+            live_tag: Style::default().fg(Color::Black).bg(Color::LightCyan).add_modifier(Modifier::BOLD),
+            final_tag: Style::default().fg(Color::Black).bg(Color::White).add_modifier(Modifier::BOLD),
+            ..default
+        },
+        _ => return None,
+    };
+    Some(Theme { name: name.to_string(), ..theme })
+}
+
+// Words in any order: modifiers, a foreground color and "on" a background color.
+// Colors are names, indexes or #rrggbb.
+fn parse_style(text: &str) -> Result<Style> {
+    let mut style = Style::default();
+    let mut words = text.split_whitespace();
+    while let Some(word) = words.next() {
+        style = match word.to_lowercase().as_str() {
+            "bold" => style.add_modifier(Modifier::BOLD),
+            "dim" => style.add_modifier(Modifier::DIM),
+            "italic" => style.add_modifier(Modifier::ITALIC),
+            "underlined" => style.add_modifier(Modifier::UNDERLINED),
+            "reversed" => style.add_modifier(Modifier::REVERSED),
+            "on" => style.bg(parse_color(words.next().context("Missing color after on")?)?),
+            color => style.fg(parse_color(color)?),
+        };
+    }
+    Ok(style)
+}
+
+fn parse_color(text: &str) -> Result<Color> {
+    Color::from_str(text).ok().with_context(|| format!("Unknown color {}", text))
+}
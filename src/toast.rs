@@ -0,0 +1,111 @@
+use crate::theme::Theme;
+use ratatui::{
+    prelude::{Frame, Rect},
+    style::Style,
+    widgets::{Clear, Paragraph, Wrap},
+};
+use std::time::{Duration, Instant};
+
+// Toasts never grow wider than this, longer messages wrap
+const TOAST_WIDTH: u16 = 40;
+// At most this many toasts are stacked, older ones are dropped first
+const MAX_TOASTS: usize = 4;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Level {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Level {
+    fn style(&self, theme: &Theme) -> Style {
+        match self {
+            Level::Info => theme.info,
+            Level::Warning => theme.warning,
+            Level::Error => theme.bad,
+        }
+    }
+
+    // named in the border too, not only told apart by color
+    fn label(&self) -> &'static str {
+        match self {
+            Level::Info => "",
+            Level::Warning => "Warning",
+            Level::Error => "Error",
+        }
+    }
+
+    // errors stay around long enough to be read
+    fn lifetime(&self) -> Duration {
+        match self {
+            Level::Info => Duration::from_secs(3),
+            Level::Warning => Duration::from_secs(5),
+            Level::Error => Duration::from_secs(8),
+        }
+    }
+}
+
+struct Toast {
+    level: Level,
+    message: String,
+    shown_at: Instant,
+}
+
+// Short lived messages in the bottom right corner, so nothing has to replace
+// the shown view to tell the user about a failed fetch or a finished export
+#[derive(Default)]
+pub struct Toasts {
+    toasts: Vec<Toast>,
+}
+
+impl Toasts {
+    pub fn push(&mut self, level: Level, message: impl Into<String>) {
+        if self.toasts.len() == MAX_TOASTS {
+            self.toasts.remove(0);
+        }
+        self.toasts.push(Toast { level, message: message.into(), shown_at: Instant::now() });
+    }
+
+    pub fn info(&mut self, message: impl Into<String>) {
+        self.push(Level::Info, message);
+    }
+
+    pub fn warning(&mut self, message: impl Into<String>) {
+        self.push(Level::Warning, message);
+    }
+
+    pub fn error(&mut self, message: impl Into<String>) {
+        self.push(Level::Error, message);
+    }
+
+    // Drop the toasts that have been shown long enough
+    pub fn expire(&mut self) {
+        self.toasts.retain(|toast| toast.shown_at.elapsed() < toast.level.lifetime());
+    }
+}
+
+// Newest toast at the bottom, stacked upwards
+pub fn ui(toasts: &Toasts, theme: &Theme, f: &mut Frame, area: Rect) {
+    let width = TOAST_WIDTH.min(area.width);
+    let mut bottom = area.y + area.height;
+
+    for toast in toasts.toasts.iter().rev() {
+        let lines = toast.message.chars().count().div_ceil(width.saturating_sub(2).max(1) as usize) as u16;
+        let height = lines + 2;
+        if bottom < area.y + height {
+            break;
+        }
+        bottom -= height;
+
+        let spot = Rect { x: area.x + area.width - width, y: bottom, width, height };
+        let style = toast.level.style(theme);
+        f.render_widget(Clear, spot);
+        f.render_widget(
+            Paragraph::new(toast.message.as_str())
+                .wrap(Wrap { trim: true })
+                .block(theme.block(toast.level.label()).border_style(style)),
+            spot,
+        );
+    }
+}
@@ -0,0 +1,252 @@
+use crate::standings::{self, RankingRules, Sort};
+use crate::Game;
+use crate::theme::Theme;
+use ratatui::{
+    prelude::{Constraint, Direction, Frame, Layout, Rect},
+    widgets::canvas::{Canvas, Line},
+    widgets::Paragraph,
+};
+
+// Canvas units per round of the drawn bracket, the last two connect to the next round
+const ROUND_WIDTH: f64 = 10.0;
+
+// A competition with a group stage followed by knockout rounds, e.g. the
+// World Cup, the Euros or the NBA Cup
+pub struct Tournament {
+    pub name: String,
+    pub rules: RankingRules,
+    pub groups: Vec<Group>,
+    pub rounds: Vec<Round>,
+}
+
+pub struct Group {
+    pub name: String,
+    pub games: Vec<Game>,
+}
+
+pub struct Round {
+    pub name: String,
+    pub ties: Vec<Tie>,
+}
+
+// One pairing of a knockout round, two legged ties are aggregated
+pub struct Tie {
+    pub home: String,
+    pub away: String,
+    pub home_score: u32,
+    pub away_score: u32,
+    pub played: bool,
+    pub finished: bool,
+}
+
+impl Tie {
+    pub fn winner(&self) -> Option<&str> {
+        if !self.finished || self.home_score == self.away_score {
+            return None;
+        }
+        Some(if self.home_score > self.away_score { &self.home } else { &self.away })
+    }
+}
+
+// Sort the games into groups and rounds by their stage, games without one are ignored
+pub fn build(name: &str, rules: RankingRules, mut games: Vec<Game>) -> Tournament {
+    games.sort_by(|a, b| a.date.cmp(&b.date));
+
+    let mut groups: Vec<Group> = Vec::new();
+    let mut rounds: Vec<Round> = Vec::new();
+
+    for game in games {
+        let Some(stage) = game.stage.clone() else {
+            continue;
+        };
+
+        if stage.contains("Group") {
+            match groups.iter_mut().find(|group| group.name == stage) {
+                Some(group) => group.games.push(game),
+                None => groups.push(Group { name: stage, games: vec![game] }),
+            }
+            continue;
+        }
+
+        // rounds keep the order in which they are first played
+        let round = match rounds.iter().position(|round| round.name == stage) {
+            Some(index) => &mut rounds[index],
+            None => {
+                rounds.push(Round { name: stage, ties: Vec::new() });
+                rounds.last_mut().expect("round was just pushed")
+            }
+        };
+        add_to_round(round, &game);
+    }
+
+    groups.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Tournament { name: name.to_string(), rules, groups, rounds }
+}
+
+fn add_to_round(round: &mut Round, game: &Game) {
+    let home = &game.home_team.abbreviation;
+    let away = &game.visitor_team.abbreviation;
+    let played = game.is_final() || game.is_live();
+
+    let existing = round.ties.iter_mut().find(|tie| {
+        (&tie.home == home && &tie.away == away) || (&tie.home == away && &tie.away == home)
+    });
+
+    match existing {
+        // second leg, scores are added from the first leg's perspective
+        Some(tie) => {
+            let (home_score, away_score) = if &tie.home == home {
+                (game.home_team_score, game.visitor_team_score)
+            } else {
+                (game.visitor_team_score, game.home_team_score)
+            };
+            if played {
+                tie.home_score += home_score;
+                tie.away_score += away_score;
+                tie.played = true;
+            }
+            tie.finished = game.is_final();
+        }
+        None => round.ties.push(Tie {
+            home: home.clone(),
+            away: away.clone(),
+            home_score: if played { game.home_team_score } else { 0 },
+            away_score: if played { game.visitor_team_score } else { 0 },
+            played,
+            finished: game.is_final(),
+        }),
+    }
+}
+
+// Group tables on the left, the knockout bracket on the right, drawn with lines if asked for
+pub fn ui(tournament: &Tournament, graphics: bool, theme: &Theme, f: &mut Frame, area: Rect) {
+    let block = theme.block(tournament.name.clone());
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    if tournament.groups.is_empty() && tournament.rounds.is_empty() {
+        f.render_widget(Paragraph::new("No tournament games found."), inner);
+        return;
+    }
+
+    let constraints = match (tournament.groups.is_empty(), tournament.rounds.is_empty()) {
+        (false, false) => [Constraint::Percentage(50), Constraint::Percentage(50)],
+        (true, _) => [Constraint::Length(0), Constraint::Min(0)],
+        (_, true) => [Constraint::Min(0), Constraint::Length(0)],
+    };
+    let chunks = Layout::default().direction(Direction::Horizontal).constraints(constraints).split(inner);
+
+    render_groups(tournament, theme, f, chunks[0]);
+    if graphics {
+        render_bracket_canvas(&tournament.rounds, theme, f, chunks[1]);
+    } else {
+        render_bracket(&tournament.rounds, theme, f, chunks[1]);
+    }
+}
+
+// Two columns of group tables, sized to the number of teams per group
+fn render_groups(tournament: &Tournament, theme: &Theme, f: &mut Frame, area: Rect) {
+    if tournament.groups.is_empty() {
+        return;
+    }
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+
+    for (column, groups) in tournament.groups.chunks(tournament.groups.len().div_ceil(2)).enumerate() {
+        let tables: Vec<_> = groups
+            .iter()
+            .map(|group| {
+                let games: Vec<&Game> = group.games.iter().collect();
+                standings::rank(tournament.rules, &games)
+            })
+            .collect();
+        let heights: Vec<Constraint> = tables.iter().map(|table| Constraint::Length(table.len() as u16 + 3)).collect();
+        let cells = Layout::default().direction(Direction::Vertical).constraints(heights).split(columns[column]);
+
+        for ((group, table), cell) in groups.iter().zip(&tables).zip(cells.iter()) {
+            standings::ui(table, Sort::default(), 0, &group.name, theme, f, *cell);
+        }
+    }
+}
+
+// One column per round, ties of later rounds are spread out to line up with the earlier ones
+fn render_bracket(rounds: &[Round], theme: &Theme, f: &mut Frame, area: Rect) {
+    if rounds.is_empty() {
+        return;
+    }
+
+    let constraints = vec![Constraint::Ratio(1, rounds.len() as u32); rounds.len()];
+    let columns = Layout::default().direction(Direction::Horizontal).constraints(constraints).split(area);
+
+    for (index, (round, column)) in rounds.iter().zip(columns.iter()).enumerate() {
+        let spacing = (1usize << index).saturating_sub(1);
+        let mut text = String::new();
+        text.push_str(&"\n".repeat(spacing));
+        for tie in &round.ties {
+            text.push_str(&tie_line(tie));
+            text.push_str(&"\n".repeat(2 * spacing + 2));
+        }
+        f.render_widget(
+            Paragraph::new(text).block(theme.block(round.name.clone())),
+            *column,
+        );
+    }
+}
+
+// The bracket as a tree, each tie joined by lines to the tie its winner plays next
+fn render_bracket_canvas(rounds: &[Round], theme: &Theme, f: &mut Frame, area: Rect) {
+    if rounds.is_empty() {
+        return;
+    }
+
+    // ties of a round are spread evenly, which lines them up between the two ties they follow
+    let position = |round: usize, index: usize| {
+        let ties = rounds[round].ties.len().max(1) as f64;
+        100.0 - (index as f64 + 0.5) * 100.0 / ties
+    };
+
+    let canvas = Canvas::default()
+        .block(theme.block("Bracket"))
+        .x_bounds([0.0, rounds.len() as f64 * ROUND_WIDTH])
+        .y_bounds([0.0, 100.0])
+        .paint(|ctx| {
+            for (round, stage) in rounds.iter().enumerate() {
+                let x = round as f64 * ROUND_WIDTH;
+                ctx.print(x, 100.0, stage.name.clone());
+
+                for (index, tie) in stage.ties.iter().enumerate() {
+                    let y = position(round, index);
+                    ctx.print(x, y, tie_line(tie));
+                    if round + 1 < rounds.len() && !rounds[round + 1].ties.is_empty() {
+                        let next = position(round + 1, (index / 2).min(rounds[round + 1].ties.len() - 1));
+                        let (end, joint) = (x + ROUND_WIDTH, x + ROUND_WIDTH - 1.0);
+                        ctx.draw(&Line { x1: joint - 1.0, y1: y, x2: joint, y2: y, color: Theme::color(theme.muted) });
+                        ctx.draw(&Line { x1: joint, y1: y, x2: joint, y2: next, color: Theme::color(theme.muted) });
+                        ctx.draw(&Line { x1: joint, y1: next, x2: end, y2: next, color: Theme::color(theme.muted) });
+                    }
+                }
+            }
+        });
+    f.render_widget(canvas, area);
+}
+
+fn tie_line(tie: &Tie) -> String {
+    let marker = |team: &str| if tie.winner() == Some(team) { "*" } else { " " };
+    if tie.played {
+        format!(
+            "{}{} {}-{} {}{}",
+            marker(&tie.home),
+            tie.home,
+            tie.home_score,
+            tie.away_score,
+            tie.away,
+            marker(&tie.away)
+        )
+    } else {
+        format!(" {} v {}", tie.home, tie.away)
+    }
+}
@@ -0,0 +1,69 @@
+use crate::provider::Provider;
+use crate::spoilers::Spoilers;
+use crate::Game;
+use crate::theme::Theme;
+use chrono::{Datelike, Duration, NaiveDate};
+use ratatui::{
+    prelude::{Constraint, Direction, Frame, Layout, Line, Rect},
+    widgets::Paragraph,
+};
+
+// Games of one Monday to Sunday week, fetched with a single range request
+pub struct Week {
+    pub start: NaiveDate,
+    pub games: Vec<Game>,
+}
+
+// Monday and Sunday of the week a day belongs to
+pub fn week_range(day: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let start = day - Duration::days(day.weekday().num_days_from_monday() as i64);
+    (start, start + Duration::days(6))
+}
+
+impl Week {
+    fn games_on(&self, date: NaiveDate) -> Vec<&Game> {
+        let date = date.format("%Y-%m-%d").to_string();
+        let mut games: Vec<&Game> = self.games.iter().filter(|game| game.date.starts_with(&date)).collect();
+        games.sort_by(|a, b| a.datetime.cmp(&b.datetime));
+        games
+    }
+}
+
+// One column per day, the browsed day highlighted. Each game takes two lines,
+// the teams and below them the score or start time.
+pub fn ui(week: &Week, provider: &dyn Provider, spoilers: &Spoilers, day: NaiveDate, theme: &Theme, f: &mut Frame, area: Rect) {
+    let title = format!("{} week of {} (one week: h|l, one day: j|k)", provider.name(), week.start.format("%Y-%m-%d"));
+    let block = theme.block(title);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Ratio(1, 7); 7])
+        .split(inner);
+
+    for (offset, column) in columns.iter().enumerate() {
+        let date = week.start + Duration::days(offset as i64);
+        let games = week.games_on(date);
+
+        let mut lines = Vec::new();
+        for game in &games {
+            lines.push(Line::from(format!("{} vs {}", game.home_team.abbreviation, game.visitor_team.abbreviation)));
+            let state = if game.is_final() || game.is_live() {
+                format!("{} {}", spoilers.score(provider, game), provider.clock(game))
+            } else {
+                provider.clock(game)
+            };
+            lines.push(Line::from(format!(" {}", state)));
+        }
+        if games.is_empty() {
+            lines.push(Line::from("No games"));
+        }
+
+        let mut block = theme.block(date.format("%a %m-%d").to_string());
+        if date == day {
+            block = block.border_style(theme.focused).title_style(theme.selected);
+        }
+        f.render_widget(Paragraph::new(lines).block(block), *column);
+    }
+}